@@ -1,6 +1,86 @@
 pub mod binary_search;
 pub mod bubble_sort;
+pub mod connectivity;
+pub mod flow;
+pub mod graph_traversal;
 pub mod insertion_sort;
 pub mod merge_sort;
 pub mod quick_sort;
 pub mod selection_sort;
+pub mod shortest_path;
+pub mod sort_stats;
+pub mod sorter;
+pub mod spanning_tree;
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bubble_sort::*, insertion_sort::*, merge_sort::*, quick_sort::*, selection_sort::*,
+    };
+    use crate::random::Random;
+    use std::cmp::{Ordering, Reverse};
+
+    // Key and original position, so the order of equal keys after a sort shows whether it was stable.
+    type Entry = (u8, usize);
+    type SortBy = fn(&mut [Entry], Box<dyn FnMut(&Entry, &Entry) -> Ordering>);
+    type SortByKey = fn(&mut [Entry], Box<dyn FnMut(&Entry) -> u8>);
+
+    /// Checks the `_by` and `_by_key` variants of a sort against the standard library's stable sort. Unstable sorts
+    /// only have to agree on the keys.
+    fn check_variants(name: &str, is_stable: bool, sort_by: SortBy, sort_by_key: SortByKey) {
+        let mut random = Random::new(47);
+
+        for len in 0..40 {
+            let list: Vec<Entry> = (0..len)
+                .map(|index| (random.range(0..5) as u8, index))
+                .collect();
+
+            let mut descending = list.clone();
+            sort_by(&mut descending, Box::new(|a, b| b.0.cmp(&a.0)));
+            let mut expected = list.clone();
+            expected.sort_by_key(|entry| Reverse(entry.0));
+            check_sorted(name, is_stable, &descending, &expected);
+
+            let mut by_key = list.clone();
+            sort_by_key(&mut by_key, Box::new(|entry| entry.0));
+            let mut expected = list;
+            expected.sort_by_key(|entry| entry.0);
+            check_sorted(name, is_stable, &by_key, &expected);
+        }
+    }
+
+    fn check_sorted(name: &str, is_stable: bool, actual: &[Entry], expected: &[Entry]) {
+        if is_stable {
+            assert_eq!(actual, expected, "{name} reordered equal keys.");
+        } else {
+            let keys = |entries: &[Entry]| entries.iter().map(|entry| entry.0).collect::<Vec<_>>();
+            assert_eq!(keys(actual), keys(expected), "{name} failed.");
+
+            let mut indices: Vec<_> = actual.iter().map(|entry| entry.1).collect();
+            indices.sort();
+            assert!(
+                indices.into_iter().eq(0..actual.len()),
+                "{name} lost values."
+            );
+        }
+    }
+
+    #[test]
+    fn sorts_by_comparator_and_key() {
+        check_variants("bubble sort", true, bubble_sort_by, bubble_sort_by_key);
+        check_variants(
+            "insertion sort",
+            true,
+            insertion_sort_by,
+            insertion_sort_by_key,
+        );
+        check_variants("merge sort", true, merge_sort_by, merge_sort_by_key);
+        check_variants("quick sort", false, quick_sort_by, quick_sort_by_key);
+        check_variants(
+            "selection sort",
+            false,
+            selection_sort_by,
+            selection_sort_by_key,
+        );
+    }
+}
@@ -1,6 +1,26 @@
-pub fn bubble_sort<T>(list: &mut Vec<T>)
+use super::sort_stats::SortObserver;
+use std::cmp::Ordering;
+
+pub fn bubble_sort<T>(list: &mut [T])
 where
     T: Ord,
+{
+    bubble_sort_by(list, T::cmp);
+}
+
+/// Sorts with `compare` instead of `Ord`. Stable, only neighbors that compare greater are swapped.
+pub fn bubble_sort_by<T, F>(list: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    bubble_sort_observed(list, compare, &mut ());
+}
+
+/// `bubble_sort_by` reporting its work to `observer`.
+pub fn bubble_sort_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
 {
     if list.len() == 0 {
         return;
@@ -8,13 +28,24 @@ where
 
     for i in 0..list.len() {
         for j in 0..(list.len() - i - 1) {
-            if list[j] > list[j + 1] {
+            observer.compare();
+            if compare(&list[j], &list[j + 1]) == Ordering::Greater {
                 list.swap(j, j + 1);
+                observer.swap();
             }
         }
     }
 }
 
+/// `bubble_sort_by` comparing the keys `key` extracts.
+pub fn bubble_sort_by_key<T, K, F>(list: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    bubble_sort_by(list, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
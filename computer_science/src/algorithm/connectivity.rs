@@ -0,0 +1,365 @@
+use super::graph_traversal::{depth_first_search, Components, DfsEvent};
+
+/// Tarjan's algorithm, one depth-first search in O(n + m). Components are numbered in reverse topological order of
+/// the condensation: edges between components go from higher to lower labels.
+pub fn strongly_connected_components_tarjan(graph: &[Vec<usize>]) -> Components {
+    let mut labels = vec![usize::MAX; graph.len()];
+    let mut count = 0;
+    // Discovery time and the earliest one reachable through the search tree and one more edge.
+    let mut times = vec![0; graph.len()];
+    let mut lows = vec![0; graph.len()];
+    let mut time = 0;
+    // Discovered nodes without a component, and the nodes being explored.
+    let mut pending = Vec::new();
+    let mut path = Vec::new();
+
+    depth_first_search(graph, 0..graph.len(), |event| match event {
+        DfsEvent::Discover(node) => {
+            times[node] = time;
+            lows[node] = time;
+            time += 1;
+            pending.push(node);
+            path.push(node);
+        }
+        DfsEvent::BackEdge(from, to) | DfsEvent::ForwardOrCrossEdge(from, to) => {
+            if labels[to] == usize::MAX {
+                lows[from] = lows[from].min(times[to]);
+            }
+        }
+        DfsEvent::TreeEdge(..) => {}
+        DfsEvent::Finish(node) => {
+            path.pop();
+            if lows[node] == times[node] {
+                // The node is the first of its component, which is everything pending after it.
+                loop {
+                    let member = pending.pop().unwrap();
+                    labels[member] = count;
+                    if member == node {
+                        break;
+                    }
+                }
+                count += 1;
+            }
+            if let Some(&parent) = path.last() {
+                lows[parent] = lows[parent].min(lows[node]);
+            }
+        }
+    });
+
+    Components { count, labels }
+}
+
+/// Kosaraju's algorithm, two depth-first searches in O(n + m). Components are numbered in topological order of the
+/// condensation: edges between components go from lower to higher labels.
+pub fn strongly_connected_components_kosaraju(graph: &[Vec<usize>]) -> Components {
+    let mut finished = Vec::with_capacity(graph.len());
+    depth_first_search(graph, 0..graph.len(), |event| {
+        if let DfsEvent::Finish(node) = event {
+            finished.push(node);
+        }
+    });
+
+    let mut reversed = vec![Vec::new(); graph.len()];
+    for (from, successors) in graph.iter().enumerate() {
+        for &to in successors {
+            reversed[to].push(from);
+        }
+    }
+
+    // Searching the reversed graph from the last finished node first reaches exactly one component per search.
+    let mut labels = vec![0; graph.len()];
+    let mut count = 0;
+    let mut depth = 0;
+    depth_first_search(&reversed, finished.into_iter().rev(), |event| match event {
+        DfsEvent::Discover(node) => {
+            labels[node] = count;
+            depth += 1;
+        }
+        DfsEvent::Finish(_) => {
+            depth -= 1;
+            if depth == 0 {
+                count += 1;
+            }
+        }
+        _ => {}
+    });
+
+    Components { count, labels }
+}
+
+/// Tree edge of an undirected depth-first search, reported by `undirected_low_links` once the child is done.
+struct TreeEdge {
+    parent: usize,
+    child: usize,
+    parent_is_root: bool,
+    parent_time: usize,
+    /// Earliest discovery time the child's subtree reaches without this edge.
+    low: usize,
+}
+
+fn undirected_low_links(graph: &[Vec<usize>], mut visit: impl FnMut(TreeEdge)) {
+    let mut times = vec![0; graph.len()];
+    let mut lows = vec![0; graph.len()];
+    let mut time = 0;
+    let mut path: Vec<usize> = Vec::new();
+    // Whether the entry back to the parent was skipped, further ones are parallel edges.
+    let mut skipped = vec![false; graph.len()];
+
+    depth_first_search(graph, 0..graph.len(), |event| match event {
+        DfsEvent::Discover(node) => {
+            times[node] = time;
+            lows[node] = time;
+            time += 1;
+            path.push(node);
+        }
+        DfsEvent::BackEdge(from, to) => {
+            let parent = path.len().checked_sub(2).map(|index| path[index]);
+            if parent == Some(to) && !skipped[from] {
+                skipped[from] = true;
+            } else {
+                lows[from] = lows[from].min(times[to]);
+            }
+        }
+        DfsEvent::Finish(node) => {
+            path.pop();
+            if let Some(&parent) = path.last() {
+                lows[parent] = lows[parent].min(lows[node]);
+                visit(TreeEdge {
+                    parent,
+                    child: node,
+                    parent_is_root: path.len() == 1,
+                    parent_time: times[parent],
+                    low: lows[node],
+                });
+            }
+        }
+        _ => {}
+    });
+}
+
+/// Nodes whose removal disconnects their component, in increasing order. The graph must be undirected.
+pub fn articulation_points(graph: &[Vec<usize>]) -> Vec<usize> {
+    let mut is_cut = vec![false; graph.len()];
+    // Search roots are cut nodes with several tree children, others when a child's subtree can't climb above them.
+    let mut root_children = vec![0; graph.len()];
+
+    undirected_low_links(graph, |edge| {
+        if edge.parent_is_root {
+            root_children[edge.parent] += 1;
+        } else if edge.low >= edge.parent_time {
+            is_cut[edge.parent] = true;
+        }
+    });
+
+    (0..graph.len())
+        .filter(|node| is_cut[*node] || root_children[*node] >= 2)
+        .collect()
+}
+
+/// Edges whose removal disconnects their component as `(smaller, larger)` end pairs, in increasing order. The graph
+/// must be undirected.
+pub fn bridges(graph: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let mut bridges = Vec::new();
+    undirected_low_links(graph, |edge| {
+        if edge.low > edge.parent_time {
+            bridges.push((edge.parent.min(edge.child), edge.parent.max(edge.child)));
+        }
+    });
+    bridges.sort_unstable();
+    bridges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::graph_traversal::connected_components;
+    use crate::random::Random;
+
+    fn random_graph(random: &mut Random, len: usize, edges: usize) -> Vec<Vec<usize>> {
+        let mut graph = vec![Vec::new(); len];
+        for _ in 0..edges {
+            let (from, to) = (random.range(0..len), random.range(0..len));
+            graph[from].push(to);
+        }
+        graph
+    }
+
+    fn undirected(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut result = graph.to_vec();
+        for (from, successors) in graph.iter().enumerate() {
+            for &to in successors {
+                if from != to {
+                    result[to].push(from);
+                }
+            }
+        }
+        result
+    }
+
+    // Reachability of every pair by Warshall's transitive closure.
+    fn reachable(graph: &[Vec<usize>]) -> Vec<Vec<bool>> {
+        let len = graph.len();
+        let mut reach = vec![vec![false; len]; len];
+        for (from, successors) in graph.iter().enumerate() {
+            reach[from][from] = true;
+            for &to in successors {
+                reach[from][to] = true;
+            }
+        }
+        for middle in 0..len {
+            let through = reach[middle].clone();
+            for row in &mut reach {
+                if row[middle] {
+                    for (cell, reaches) in row.iter_mut().zip(&through) {
+                        *cell |= *reaches;
+                    }
+                }
+            }
+        }
+        reach
+    }
+
+    fn assert_strongly_connected(graph: &[Vec<usize>], components: &Components, topological: bool) {
+        let reach = reachable(graph);
+        let labels = &components.labels;
+        assert!(labels.iter().all(|label| *label < components.count));
+        for a in 0..graph.len() {
+            for b in 0..graph.len() {
+                assert_eq!(labels[a] == labels[b], reach[a][b] && reach[b][a]);
+            }
+        }
+        for (from, successors) in graph.iter().enumerate() {
+            for &to in successors {
+                if topological {
+                    assert!(labels[from] <= labels[to]);
+                } else {
+                    assert!(labels[from] >= labels[to]);
+                }
+            }
+        }
+    }
+
+    fn component_count(
+        graph: &[Vec<usize>],
+        removed_node: Option<usize>,
+        removed_edge: Option<(usize, usize)>,
+    ) -> usize {
+        let kept: Vec<Vec<usize>> = graph
+            .iter()
+            .enumerate()
+            .map(|(from, successors)| {
+                let mut skipped = false;
+                successors
+                    .iter()
+                    .copied()
+                    .filter(|to| {
+                        if removed_node == Some(from) || removed_node == Some(*to) {
+                            return false;
+                        }
+                        // Drops a single entry of the edge, parallel copies stay.
+                        if !skipped
+                            && (removed_edge == Some((from, *to))
+                                || removed_edge == Some((*to, from)))
+                        {
+                            skipped = true;
+                            return false;
+                        }
+                        true
+                    })
+                    .collect()
+            })
+            .collect();
+        connected_components(&kept).count - removed_node.map_or(0, |_| 1)
+    }
+
+    #[test]
+    fn finds_strongly_connected_components() {
+        let graph = vec![
+            vec![1],
+            vec![2],
+            vec![0, 3],
+            vec![4],
+            vec![5],
+            vec![3],
+            vec![5],
+        ];
+
+        let tarjan = strongly_connected_components_tarjan(&graph);
+        assert_eq!(tarjan.count, 3);
+        assert_eq!(tarjan.labels, vec![1, 1, 1, 0, 0, 0, 2]);
+
+        let kosaraju = strongly_connected_components_kosaraju(&graph);
+        assert_eq!(kosaraju.count, 3);
+        assert_eq!(kosaraju.labels, vec![1, 1, 1, 2, 2, 2, 0]);
+    }
+
+    #[test]
+    fn strongly_connected_components_match_reachability() {
+        let mut random = Random::new(45);
+
+        for _ in 0..100 {
+            let len = 1 + random.range(0..25);
+            let edges = random.range(0..3 * len);
+            let graph = random_graph(&mut random, len, edges);
+
+            let tarjan = strongly_connected_components_tarjan(&graph);
+            let kosaraju = strongly_connected_components_kosaraju(&graph);
+            assert_eq!(tarjan.count, kosaraju.count);
+            assert_strongly_connected(&graph, &tarjan, false);
+            assert_strongly_connected(&graph, &kosaraju, true);
+        }
+    }
+
+    #[test]
+    fn finds_cut_nodes_and_bridges() {
+        // Triangle 0 1 2 hanging from 2 by the bridge to 3, which leads to 4 and a doubled edge to 5.
+        let graph = undirected(&[vec![1], vec![2], vec![0, 3], vec![4, 5, 5], vec![], vec![]]);
+
+        assert_eq!(articulation_points(&graph), vec![2, 3]);
+        assert_eq!(bridges(&graph), vec![(2, 3), (3, 4)]);
+        assert_eq!(bridges(&undirected(&[vec![1, 1], vec![]])), vec![]);
+        assert_eq!(
+            articulation_points(&undirected(&[vec![1], vec![2], vec![]])),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn cut_nodes_and_bridges_match_removal() {
+        let mut random = Random::new(45);
+
+        for _ in 0..200 {
+            let len = 1 + random.range(0..15);
+            let edges = random.range(0..2 * len);
+            let graph = undirected(&random_graph(&mut random, len, edges));
+            let count = component_count(&graph, None, None);
+
+            let points = articulation_points(&graph);
+            for node in 0..graph.len() {
+                let separates = component_count(&graph, Some(node), None) > count;
+                assert_eq!(
+                    points.contains(&node),
+                    separates,
+                    "Node {} of {:?}.",
+                    node,
+                    graph
+                );
+            }
+
+            let found = bridges(&graph);
+            for (from, successors) in graph.iter().enumerate() {
+                for &to in successors.iter().filter(|to| from < **to) {
+                    let separates = component_count(&graph, None, Some((from, to))) > count;
+                    assert_eq!(
+                        found.contains(&(from, to)),
+                        separates,
+                        "Edge {}-{} of {:?}.",
+                        from,
+                        to,
+                        graph
+                    );
+                }
+            }
+        }
+    }
+}
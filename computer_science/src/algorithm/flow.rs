@@ -0,0 +1,632 @@
+use super::shortest_path::Weight;
+use crate::data_structure::queue::Queue;
+use std::ops::Sub;
+
+/// Edge capacities, `Default` being zero. Sums are expected not to overflow.
+pub trait Capacity: Weight + Sub<Output = Self> {}
+
+impl<T: Weight + Sub<Output = T>> Capacity for T {}
+
+/// Directed network with capacities and the flow on them, which the max-flow functions fill in.
+#[derive(Clone, Debug)]
+pub struct FlowNetwork<C> {
+    // Arc 2i runs along the i-th edge and arc 2i + 1 against it. Each holds how much more can pass, pushing along an
+    // arc takes from it and gives to its twin.
+    heads: Vec<usize>,
+    residuals: Vec<C>,
+    capacities: Vec<C>,
+    // Arcs leaving every node.
+    arcs: Vec<Vec<usize>>,
+}
+
+impl<C: Capacity> FlowNetwork<C> {
+    pub fn new(len: usize) -> Self {
+        FlowNetwork {
+            heads: Vec::new(),
+            residuals: Vec::new(),
+            capacities: Vec::new(),
+            arcs: vec![Vec::new(); len],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.arcs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.arcs.is_empty()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.capacities.len()
+    }
+
+    /// Adds an edge without flow and returns its index.
+    pub fn add_edge(&mut self, from: usize, to: usize, capacity: C) -> usize {
+        assert!(from < self.len() && to < self.len(), "Missing node.");
+        assert!(capacity >= C::default(), "Negative capacity.");
+
+        let edge = self.capacities.len();
+        self.arcs[from].push(2 * edge);
+        self.arcs[to].push(2 * edge + 1);
+        self.heads.extend([to, from]);
+        self.residuals.extend([capacity, C::default()]);
+        self.capacities.push(capacity);
+        edge
+    }
+
+    pub fn endpoints(&self, edge: usize) -> (usize, usize) {
+        (self.heads[2 * edge + 1], self.heads[2 * edge])
+    }
+
+    pub fn capacity(&self, edge: usize) -> C {
+        self.capacities[edge]
+    }
+
+    pub fn flow(&self, edge: usize) -> C {
+        self.residuals[2 * edge + 1]
+    }
+
+    pub fn clear_flow(&mut self) {
+        for (edge, capacity) in self.capacities.iter().enumerate() {
+            self.residuals[2 * edge] = *capacity;
+            self.residuals[2 * edge + 1] = C::default();
+        }
+    }
+
+    fn push(&mut self, arc: usize, amount: C) {
+        self.residuals[arc] = self.residuals[arc] - amount;
+        self.residuals[arc ^ 1] = self.residuals[arc ^ 1] + amount;
+    }
+
+    /// Nodes reachable from `source` over arcs with room left, breadth-first, with the arc first reaching each.
+    fn residual_search(&self, source: usize) -> Vec<Option<usize>> {
+        // The source is marked with an arc that is never followed.
+        let mut parents = vec![None; self.len()];
+        parents[source] = Some(usize::MAX);
+
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+        while let Some(node) = queue.dequeue() {
+            for &arc in &self.arcs[node] {
+                let head = self.heads[arc];
+                if parents[head].is_none() && self.residuals[arc] > C::default() {
+                    parents[head] = Some(arc);
+                    queue.enqueue(head);
+                }
+            }
+        }
+        parents
+    }
+}
+
+fn start<C: Capacity>(network: &mut FlowNetwork<C>, source: usize, sink: usize) {
+    assert!(
+        source < network.len() && sink < network.len(),
+        "Missing node."
+    );
+    assert_ne!(source, sink, "Source and sink must differ.");
+    network.clear_flow();
+}
+
+/// Edmonds-Karp algorithm, O(nm²): augments along shortest paths with room left. Replaces any flow in the network by
+/// a maximum one and returns its value.
+pub fn edmonds_karp<C: Capacity>(network: &mut FlowNetwork<C>, source: usize, sink: usize) -> C {
+    start(network, source, sink);
+    let mut total = C::default();
+
+    loop {
+        let parents = network.residual_search(source);
+        if parents[sink].is_none() {
+            return total;
+        }
+
+        let mut path = Vec::new();
+        let mut node = sink;
+        while node != source {
+            let arc = parents[node].unwrap();
+            path.push(arc);
+            node = network.heads[arc ^ 1];
+        }
+
+        let amount = path
+            .iter()
+            .map(|arc| network.residuals[*arc])
+            .min()
+            .unwrap();
+        for arc in path {
+            network.push(arc, amount);
+        }
+        total = total + amount;
+    }
+}
+
+/// Dinic's algorithm, O(n²m): saturates all shortest paths of one length at a time. Replaces any flow in the network
+/// by a maximum one and returns its value.
+pub fn dinic<C: Capacity>(network: &mut FlowNetwork<C>, source: usize, sink: usize) -> C {
+    start(network, source, sink);
+    let mut total = C::default();
+
+    loop {
+        // Breadth-first levels over arcs with room left, augmenting paths go one level up per arc.
+        let mut levels = vec![usize::MAX; network.len()];
+        levels[source] = 0;
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+        while let Some(node) = queue.dequeue() {
+            for &arc in &network.arcs[node] {
+                let head = network.heads[arc];
+                if levels[head] == usize::MAX && network.residuals[arc] > C::default() {
+                    levels[head] = levels[node] + 1;
+                    queue.enqueue(head);
+                }
+            }
+        }
+        if levels[sink] == usize::MAX {
+            return total;
+        }
+
+        // Arcs before a node's position lead nowhere in this phase.
+        let mut positions = vec![0; network.len()];
+        loop {
+            let amount = blocking_path(network, &levels, &mut positions, source, sink);
+            if amount == C::default() {
+                break;
+            }
+            total = total + amount;
+        }
+    }
+}
+
+/// Pushes as much as fits along one level-increasing path from `source` to `sink`, returning how much got through.
+/// Iterative, long paths would overflow the stack when recursing per arc.
+fn blocking_path<C: Capacity>(
+    network: &mut FlowNetwork<C>,
+    levels: &[usize],
+    positions: &mut [usize],
+    source: usize,
+    sink: usize,
+) -> C {
+    // Arcs from the source to `node`.
+    let mut path: Vec<usize> = Vec::new();
+    let mut node = source;
+
+    while node != sink {
+        match network.arcs[node].get(positions[node]) {
+            Some(&arc) => {
+                let head = network.heads[arc];
+                if network.residuals[arc] > C::default() && levels[head] == levels[node] + 1 {
+                    path.push(arc);
+                    node = head;
+                } else {
+                    positions[node] += 1;
+                }
+            }
+            None => {
+                // Dead end: back up and skip the arc that led here.
+                let Some(arc) = path.pop() else {
+                    return C::default();
+                };
+                node = network.heads[arc ^ 1];
+                positions[node] += 1;
+            }
+        }
+    }
+
+    let amount = path
+        .iter()
+        .map(|arc| network.residuals[*arc])
+        .min()
+        .unwrap();
+    for arc in path {
+        network.push(arc, amount);
+    }
+    amount
+}
+
+/// Push-relabel algorithm with first-in first-out selection, O(n³): floods excess downhill from the source and lifts
+/// nodes that can't pass theirs on. Replaces any flow in the network by a maximum one and returns its value.
+pub fn push_relabel<C: Capacity>(network: &mut FlowNetwork<C>, source: usize, sink: usize) -> C {
+    start(network, source, sink);
+    let len = network.len();
+    let mut heights = vec![0; len];
+    let mut excesses = vec![C::default(); len];
+    // Next arc to try for every node, reset on relabeling.
+    let mut positions = vec![0; len];
+    let mut active = Queue::new();
+
+    heights[source] = len;
+    for index in 0..network.arcs[source].len() {
+        let arc = network.arcs[source][index];
+        let (head, room) = (network.heads[arc], network.residuals[arc]);
+        if room > C::default() {
+            network.push(arc, room);
+            if head != sink && head != source && excesses[head] == C::default() {
+                active.enqueue(head);
+            }
+            excesses[head] = excesses[head] + room;
+        }
+    }
+
+    while let Some(node) = active.dequeue() {
+        while excesses[node] > C::default() {
+            let Some(&arc) = network.arcs[node].get(positions[node]) else {
+                // Some arc has room since excess can always flow back to the source.
+                heights[node] = 1 + network.arcs[node]
+                    .iter()
+                    .filter(|arc| network.residuals[**arc] > C::default())
+                    .map(|arc| heights[network.heads[*arc]])
+                    .min()
+                    .unwrap();
+                positions[node] = 0;
+                continue;
+            };
+
+            let head = network.heads[arc];
+            let room = network.residuals[arc];
+            if room > C::default() && heights[node] == heights[head] + 1 {
+                let amount = excesses[node].min(room);
+                network.push(arc, amount);
+                excesses[node] = excesses[node] - amount;
+                if head != sink && head != source && excesses[head] == C::default() {
+                    active.enqueue(head);
+                }
+                excesses[head] = excesses[head] + amount;
+            } else {
+                positions[node] += 1;
+            }
+        }
+    }
+    excesses[sink]
+}
+
+/// Cut separating source and sink, as read off a maximum flow.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MinCut<C> {
+    pub capacity: C,
+    /// Whether each node is on the source's side.
+    pub source_side: Vec<bool>,
+    /// Edges from the source's side to the other, in increasing order.
+    pub edges: Vec<usize>,
+}
+
+/// Minimum cut of a network holding a maximum flow from `source`: the nodes still reachable from it over edges with
+/// room left, and the saturated edges leaving them.
+pub fn min_cut<C: Capacity>(network: &FlowNetwork<C>, source: usize) -> MinCut<C> {
+    let source_side: Vec<bool> = network
+        .residual_search(source)
+        .iter()
+        .map(Option::is_some)
+        .collect();
+
+    let mut cut = MinCut {
+        capacity: C::default(),
+        source_side,
+        edges: Vec::new(),
+    };
+    for edge in 0..network.edge_count() {
+        let (from, to) = network.endpoints(edge);
+        if cut.source_side[from] && !cut.source_side[to] {
+            cut.capacity = cut.capacity + network.capacity(edge);
+            cut.edges.push(edge);
+        }
+    }
+    cut
+}
+
+/// Pairs of a bipartite matching.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Matching {
+    pub size: usize,
+    /// Right partner of every left node.
+    pub left: Vec<Option<usize>>,
+    /// Left partner of every right node.
+    pub right: Vec<Option<usize>>,
+}
+
+/// Hopcroft-Karp algorithm, O(m √n): maximum matching of a bipartite graph given as the right neighbors of every
+/// left node, right nodes being `0..right_len`.
+pub fn hopcroft_karp(graph: &[Vec<usize>], right_len: usize) -> Matching {
+    let mut matching = Matching {
+        size: 0,
+        left: vec![None; graph.len()],
+        right: vec![None; right_len],
+    };
+
+    loop {
+        // Layers of alternating paths from free left nodes, by left node.
+        let mut layers = vec![usize::MAX; graph.len()];
+        let mut queue = Queue::new();
+        for (node, partner) in matching.left.iter().enumerate() {
+            if partner.is_none() {
+                layers[node] = 0;
+                queue.enqueue(node);
+            }
+        }
+
+        // Shortest augmenting paths end at the layer where a free right node is first seen, deeper ones wait.
+        let mut free_layer = usize::MAX;
+        while let Some(node) = queue.dequeue() {
+            if layers[node] > free_layer {
+                break;
+            }
+            for &right in &graph[node] {
+                match matching.right[right] {
+                    None => free_layer = layers[node],
+                    Some(next) if layers[next] == usize::MAX => {
+                        layers[next] = layers[node] + 1;
+                        queue.enqueue(next);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        if free_layer == usize::MAX {
+            return matching;
+        }
+
+        for node in 0..graph.len() {
+            if matching.left[node].is_none()
+                && augment(graph, &mut matching, &mut layers, free_layer, node)
+            {
+                matching.size += 1;
+            }
+        }
+    }
+}
+
+/// Follows layers from a free left node to a free right node at `free_layer` and flips the path, dead ends are dropped
+/// from the layers. Iterative, paths can be as long as the matching.
+fn augment(
+    graph: &[Vec<usize>],
+    matching: &mut Matching,
+    layers: &mut [usize],
+    free_layer: usize,
+    root: usize,
+) -> bool {
+    // Left nodes of the path with the position of their next edge, the one before it leads to the next entry.
+    let mut stack = vec![(root, 0)];
+
+    while let Some(&(node, position)) = stack.last() {
+        let Some(&right) = graph[node].get(position) else {
+            layers[node] = usize::MAX;
+            stack.pop();
+            continue;
+        };
+        stack.last_mut().unwrap().1 += 1;
+
+        match matching.right[right] {
+            None if layers[node] == free_layer => {
+                for &(node, position) in &stack {
+                    let right = graph[node][position - 1];
+                    matching.left[node] = Some(right);
+                    matching.right[right] = Some(node);
+                }
+                return true;
+            }
+            Some(next) if layers[node] < free_layer && layers[next] == layers[node] + 1 => {
+                stack.push((next, 0));
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    type MaxFlow = fn(&mut FlowNetwork<u64>, usize, usize) -> u64;
+
+    const ALGORITHMS: [MaxFlow; 3] = [edmonds_karp, dinic, push_relabel];
+
+    fn random_network(random: &mut Random, len: usize, edges: usize) -> FlowNetwork<u64> {
+        let mut network = FlowNetwork::new(len);
+        for _ in 0..edges {
+            let (from, to) = (random.range(0..len), random.range(0..len));
+            network.add_edge(from, to, random.range(0..20) as u64);
+        }
+        network
+    }
+
+    fn assert_valid_flow(network: &FlowNetwork<u64>, source: usize, sink: usize, value: u64) {
+        let mut balances = vec![0i64; network.len()];
+        for edge in 0..network.edge_count() {
+            let (from, to) = network.endpoints(edge);
+            let flow = network.flow(edge);
+            assert!(flow <= network.capacity(edge));
+            balances[from] -= flow as i64;
+            balances[to] += flow as i64;
+        }
+        for (node, balance) in balances.iter().enumerate() {
+            let expected = match node {
+                _ if node == source => -(value as i64),
+                _ if node == sink => value as i64,
+                _ => 0,
+            };
+            assert_eq!(*balance, expected, "Node {} is unbalanced.", node);
+        }
+    }
+
+    #[test]
+    fn finds_maximum_flow() {
+        // The network from Introduction to Algorithms, maximum flow 23.
+        let edges = [
+            (0, 1, 16),
+            (0, 2, 13),
+            (1, 3, 12),
+            (2, 1, 4),
+            (2, 4, 14),
+            (3, 2, 9),
+            (3, 5, 20),
+            (4, 3, 7),
+            (4, 5, 4),
+        ];
+        let mut network = FlowNetwork::new(6);
+        for (from, to, capacity) in edges {
+            network.add_edge(from, to, capacity);
+        }
+
+        for algorithm in ALGORITHMS {
+            assert_eq!(algorithm(&mut network, 0, 5), 23);
+            assert_valid_flow(&network, 0, 5, 23);
+
+            let cut = min_cut(&network, 0);
+            assert_eq!(cut.capacity, 23);
+            assert_eq!(cut.source_side, vec![true, true, true, false, true, false]);
+            assert_eq!(cut.edges, vec![2, 7, 8]);
+        }
+    }
+
+    #[test]
+    fn handles_unreachable_sink() {
+        let mut network = FlowNetwork::new(3);
+        network.add_edge(0, 1, 5);
+        network.add_edge(2, 1, 5);
+
+        for algorithm in ALGORITHMS {
+            assert_eq!(algorithm(&mut network, 0, 2), 0);
+            assert_eq!(min_cut(&network, 0).edges, vec![]);
+        }
+    }
+
+    #[test]
+    fn handles_long_paths() {
+        let len = 100_000;
+        let mut network = FlowNetwork::new(len);
+        for node in 1..len {
+            network.add_edge(node - 1, node, node as u64 % 7 + 1);
+        }
+
+        assert_eq!(dinic(&mut network, 0, len - 1), 1);
+        assert_valid_flow(&network, 0, len - 1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Source and sink must differ.")]
+    fn rejects_same_source_and_sink() {
+        dinic(&mut FlowNetwork::<u64>::new(2), 1, 1);
+    }
+
+    #[test]
+    fn max_flow_equals_min_cut() {
+        let mut random = Random::new(46);
+
+        for _ in 0..200 {
+            let len = 2 + random.range(0..20);
+            let edges = random.range(0..4 * len);
+            let mut network = random_network(&mut random, len, edges);
+            let (source, sink) = (0, len - 1);
+
+            let values: Vec<_> = ALGORITHMS
+                .iter()
+                .map(|algorithm| {
+                    let value = algorithm(&mut network, source, sink);
+                    assert_valid_flow(&network, source, sink, value);
+
+                    let cut = min_cut(&network, source);
+                    assert!(cut.source_side[source] && !cut.source_side[sink]);
+                    assert_eq!(cut.capacity, value);
+                    value
+                })
+                .collect();
+            assert!(values.windows(2).all(|pair| pair[0] == pair[1]));
+        }
+    }
+
+    #[test]
+    fn min_cut_matches_brute_force() {
+        let mut random = Random::new(46);
+
+        for _ in 0..100 {
+            let len = 7;
+            let mut network = random_network(&mut random, len, 15);
+            let value = push_relabel(&mut network, 0, len - 1);
+
+            // Every split with the source on one side and the sink on the other.
+            let best = (0..1u32 << (len - 2))
+                .map(|mask| {
+                    let side = |node: usize| {
+                        node == 0 || (node < len - 1 && mask & (1 << (node - 1)) != 0)
+                    };
+                    (0..network.edge_count())
+                        .filter(|edge| {
+                            let (from, to) = network.endpoints(*edge);
+                            side(from) && !side(to)
+                        })
+                        .map(|edge| network.capacity(edge))
+                        .sum::<u64>()
+                })
+                .min()
+                .unwrap();
+            assert_eq!(value, best);
+        }
+    }
+
+    #[test]
+    fn finds_maximum_matching() {
+        let graph = vec![vec![0, 1], vec![0], vec![1, 2], vec![2]];
+        let matching = hopcroft_karp(&graph, 4);
+
+        assert_eq!(matching.size, 3);
+        // The four left nodes only reach right nodes 0 to 2, so one of them stays free.
+        assert_eq!(
+            matching
+                .left
+                .iter()
+                .filter(|partner| partner.is_none())
+                .count(),
+            1
+        );
+        assert_eq!(matching.right[3], None);
+        assert_eq!(hopcroft_karp(&[vec![], vec![]], 2).size, 0);
+    }
+
+    #[test]
+    fn augments_along_long_paths() {
+        // Left i prefers right i + 1, so the first phase leaves left n - 1 and right 0 free, joined by a single
+        // alternating path through every node.
+        let len = 100_000;
+        let mut graph: Vec<Vec<usize>> = (0..len - 1).map(|node| vec![node + 1, node]).collect();
+        graph.push(vec![len - 1]);
+
+        let matching = hopcroft_karp(&graph, len);
+        assert_eq!(matching.size, len);
+        assert!((0..len).all(|node| matching.left[node] == Some(node)));
+    }
+
+    #[test]
+    fn matching_agrees_with_max_flow() {
+        let mut random = Random::new(46);
+
+        for _ in 0..200 {
+            let (left, right) = (1 + random.range(0..15), 1 + random.range(0..15));
+            let mut graph = vec![Vec::new(); left];
+            let mut network = FlowNetwork::new(left + right + 2);
+            let (source, sink) = (left + right, left + right + 1);
+            for _ in 0..random.range(0..3 * left) {
+                let (from, to) = (random.range(0..left), random.range(0..right));
+                graph[from].push(to);
+                network.add_edge(from, left + to, 1u64);
+            }
+            for node in 0..left {
+                network.add_edge(source, node, 1);
+            }
+            for node in 0..right {
+                network.add_edge(left + node, sink, 1);
+            }
+
+            let matching = hopcroft_karp(&graph, right);
+            assert_eq!(matching.size as u64, dinic(&mut network, source, sink));
+            assert_eq!(matching.left.iter().flatten().count(), matching.size);
+            for (node, partner) in matching.left.iter().enumerate() {
+                if let Some(partner) = partner {
+                    assert!(graph[node].contains(partner));
+                    assert_eq!(matching.right[*partner], Some(node));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,561 @@
+use crate::data_structure::{queue::Queue, stack::Stack};
+
+/// Shortest (fewest edges) paths from a source, as found by breadth-first search.
+pub struct SearchTree {
+    /// Reached nodes in the order they were visited.
+    pub order: Vec<usize>,
+    pub distances: Vec<Option<usize>>,
+    pub parents: Vec<Option<usize>>,
+}
+
+impl SearchTree {
+    /// Nodes from the source to `node`, `None` if it wasn't reached.
+    pub fn path_to(&self, node: usize) -> Option<Vec<usize>> {
+        self.distances[node]?;
+
+        let mut path = vec![node];
+        while let Some(parent) = self.parents[*path.last().unwrap()] {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Graphs are adjacency lists, `graph[node]` holding the successors of `node` among `0..graph.len()`. Undirected
+/// graphs list every edge from both ends, as `Graph::adjacency_lists` builds them.
+pub fn breadth_first_search(graph: &[Vec<usize>], source: usize) -> SearchTree {
+    let mut tree = SearchTree {
+        order: Vec::new(),
+        distances: vec![None; graph.len()],
+        parents: vec![None; graph.len()],
+    };
+
+    let mut queue = Queue::new();
+    tree.distances[source] = Some(0);
+    queue.enqueue(source);
+    while let Some(node) = queue.dequeue() {
+        tree.order.push(node);
+        let distance = tree.distances[node].unwrap() + 1;
+        for &next in &graph[node] {
+            if tree.distances[next].is_none() {
+                tree.distances[next] = Some(distance);
+                tree.parents[next] = Some(node);
+                queue.enqueue(next);
+            }
+        }
+    }
+    tree
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DfsEvent {
+    /// First visit of a node, its pre-order position.
+    Discover(usize),
+    /// Edge to an undiscovered node, which is discovered next.
+    TreeEdge(usize, usize),
+    /// Edge to a node still being explored, closing a cycle. In undirected graphs the edge back to the parent shows up
+    /// here too.
+    BackEdge(usize, usize),
+    /// Edge to an already finished node.
+    ForwardOrCrossEdge(usize, usize),
+    /// Every successor of the node is explored, its post-order position.
+    Finish(usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Undiscovered,
+    Active,
+    Finished,
+}
+
+/// Iterative depth-first search from every source in turn, skipping sources already reached, reporting events in the
+/// order a recursive search would. Successors are explored in list order.
+pub fn depth_first_search(
+    graph: &[Vec<usize>],
+    sources: impl IntoIterator<Item = usize>,
+    mut visit: impl FnMut(DfsEvent),
+) {
+    let mut states = vec![State::Undiscovered; graph.len()];
+    // Frames are a node and the index of its next successor to explore.
+    let mut stack = Stack::new();
+
+    for source in sources {
+        if states[source] != State::Undiscovered {
+            continue;
+        }
+        states[source] = State::Active;
+        visit(DfsEvent::Discover(source));
+        stack.push((source, 0));
+
+        while let Some((node, next)) = stack.pop() {
+            let Some(&successor) = graph[node].get(next) else {
+                states[node] = State::Finished;
+                visit(DfsEvent::Finish(node));
+                continue;
+            };
+            stack.push((node, next + 1));
+
+            match states[successor] {
+                State::Undiscovered => {
+                    visit(DfsEvent::TreeEdge(node, successor));
+                    states[successor] = State::Active;
+                    visit(DfsEvent::Discover(successor));
+                    stack.push((successor, 0));
+                }
+                State::Active => visit(DfsEvent::BackEdge(node, successor)),
+                State::Finished => visit(DfsEvent::ForwardOrCrossEdge(node, successor)),
+            }
+        }
+    }
+}
+
+/// Nodes of a directed cycle, each with an edge to the next and the last with one to the first.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Cycle(pub Vec<usize>);
+
+/// Topological order by Kahn's algorithm: repeatedly takes a node without remaining incoming edges.
+pub fn topological_sort_kahn(graph: &[Vec<usize>]) -> Result<Vec<usize>, Cycle> {
+    let mut in_degrees = vec![0; graph.len()];
+    for successors in graph {
+        for &next in successors {
+            in_degrees[next] += 1;
+        }
+    }
+
+    let mut queue = Queue::new();
+    for (node, degree) in in_degrees.iter().enumerate() {
+        if *degree == 0 {
+            queue.enqueue(node);
+        }
+    }
+
+    let mut order = Vec::with_capacity(graph.len());
+    while let Some(node) = queue.dequeue() {
+        order.push(node);
+        for &next in &graph[node] {
+            in_degrees[next] -= 1;
+            if in_degrees[next] == 0 {
+                queue.enqueue(next);
+            }
+        }
+    }
+
+    if order.len() == graph.len() {
+        return Ok(order);
+    }
+
+    // Every leftover node still has a leftover predecessor, walking predecessors must repeat a node.
+    let mut predecessors = vec![None; graph.len()];
+    for (node, successors) in graph.iter().enumerate() {
+        if in_degrees[node] > 0 {
+            for &next in successors {
+                if in_degrees[next] > 0 {
+                    predecessors[next] = Some(node);
+                }
+            }
+        }
+    }
+
+    let mut seen = vec![false; graph.len()];
+    let mut node = (0..graph.len()).find(|node| in_degrees[*node] > 0).unwrap();
+    while !seen[node] {
+        seen[node] = true;
+        node = predecessors[node].unwrap();
+    }
+
+    let mut cycle = vec![node];
+    let mut previous = predecessors[node].unwrap();
+    while previous != node {
+        cycle.push(previous);
+        previous = predecessors[previous].unwrap();
+    }
+    cycle.reverse();
+    Err(Cycle(cycle))
+}
+
+/// Topological order as reversed depth-first post-order, a back edge reveals a cycle.
+pub fn topological_sort_dfs(graph: &[Vec<usize>]) -> Result<Vec<usize>, Cycle> {
+    let mut order = Vec::with_capacity(graph.len());
+    // Nodes being explored, from the search root down.
+    let mut path = Vec::new();
+    let mut cycle = None;
+
+    depth_first_search(graph, 0..graph.len(), |event| match event {
+        DfsEvent::Discover(node) => path.push(node),
+        DfsEvent::Finish(node) => {
+            path.pop();
+            order.push(node);
+        }
+        DfsEvent::BackEdge(_, ancestor) if cycle.is_none() => {
+            let start = path.iter().position(|node| *node == ancestor).unwrap();
+            cycle = Some(Cycle(path[start..].to_vec()));
+        }
+        _ => {}
+    });
+
+    match cycle {
+        Some(cycle) => Err(cycle),
+        None => {
+            order.reverse();
+            Ok(order)
+        }
+    }
+}
+
+/// Some cycle of a directed graph, self loops included.
+pub fn find_cycle(graph: &[Vec<usize>]) -> Option<Cycle> {
+    topological_sort_dfs(graph).err()
+}
+
+/// Side of every node in a two-coloring where each edge joins different sides, `None` if there is an odd cycle. The
+/// graph must be undirected.
+pub fn bipartition(graph: &[Vec<usize>]) -> Option<Vec<bool>> {
+    let mut sides = vec![None; graph.len()];
+
+    for source in 0..graph.len() {
+        if sides[source].is_some() {
+            continue;
+        }
+
+        sides[source] = Some(false);
+        let mut queue = Queue::new();
+        queue.enqueue(source);
+        while let Some(node) = queue.dequeue() {
+            let side = sides[node].unwrap();
+            for &next in &graph[node] {
+                match sides[next] {
+                    None => {
+                        sides[next] = Some(!side);
+                        queue.enqueue(next);
+                    }
+                    Some(next_side) if next_side == side => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Some(sides.into_iter().map(Option::unwrap).collect())
+}
+
+/// Connected components of an undirected graph.
+pub struct Components {
+    pub count: usize,
+    /// Component of every node, numbered in order of their smallest node.
+    pub labels: Vec<usize>,
+}
+
+pub fn connected_components(graph: &[Vec<usize>]) -> Components {
+    let mut labels = vec![usize::MAX; graph.len()];
+    let mut count = 0;
+
+    for source in 0..graph.len() {
+        if labels[source] != usize::MAX {
+            continue;
+        }
+
+        labels[source] = count;
+        let mut stack = Stack::new();
+        stack.push(source);
+        while let Some(node) = stack.pop() {
+            for &next in &graph[node] {
+                if labels[next] == usize::MAX {
+                    labels[next] = count;
+                    stack.push(next);
+                }
+            }
+        }
+        count += 1;
+    }
+
+    Components { count, labels }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::{disjoint_set::DisjointSet, graph::Graph};
+    use crate::random::Random;
+
+    fn random_graph(random: &mut Random, len: usize, edges: usize) -> Vec<Vec<usize>> {
+        let mut graph = vec![Vec::new(); len];
+        for _ in 0..edges {
+            let (from, to) = (random.range(0..len), random.range(0..len));
+            graph[from].push(to);
+        }
+        graph
+    }
+
+    fn undirected(graph: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut result = graph.to_vec();
+        for (from, successors) in graph.iter().enumerate() {
+            for &to in successors {
+                if from != to {
+                    result[to].push(from);
+                }
+            }
+        }
+        result
+    }
+
+    fn assert_cycle(graph: &[Vec<usize>], Cycle(cycle): &Cycle) {
+        assert!(!cycle.is_empty());
+        for (index, node) in cycle.iter().enumerate() {
+            let next = cycle[(index + 1) % cycle.len()];
+            assert!(graph[*node].contains(&next), "{:?} isn't a cycle.", cycle);
+        }
+    }
+
+    fn assert_topological(graph: &[Vec<usize>], order: &[usize]) {
+        let mut position = vec![usize::MAX; graph.len()];
+        for (index, node) in order.iter().enumerate() {
+            position[*node] = index;
+        }
+        assert!(position.iter().all(|position| *position != usize::MAX));
+        for (from, successors) in graph.iter().enumerate() {
+            assert!(successors.iter().all(|to| position[from] < position[*to]));
+        }
+    }
+
+    #[test]
+    fn breadth_first_finds_shortest_paths() {
+        let graph = vec![vec![1, 2], vec![3], vec![3], vec![4], vec![], vec![0]];
+        let tree = breadth_first_search(&graph, 0);
+
+        assert_eq!(tree.order, vec![0, 1, 2, 3, 4]);
+        assert_eq!(
+            tree.distances,
+            vec![Some(0), Some(1), Some(1), Some(2), Some(3), None]
+        );
+        assert_eq!(tree.path_to(4), Some(vec![0, 1, 3, 4]));
+        assert_eq!(tree.path_to(5), None);
+    }
+
+    #[test]
+    fn breadth_first_matches_relaxation() {
+        let mut random = Random::new(43);
+
+        for _ in 0..50 {
+            let graph = random_graph(&mut random, 30, 60);
+            let tree = breadth_first_search(&graph, 0);
+
+            // Bellman-Ford style relaxation with unit weights.
+            let mut expected = vec![None; graph.len()];
+            expected[0] = Some(0);
+            for _ in 0..graph.len() {
+                for (from, successors) in graph.iter().enumerate() {
+                    if let Some(distance) = expected[from] {
+                        for &to in successors {
+                            if expected[to].is_none_or(|current| current > distance + 1) {
+                                expected[to] = Some(distance + 1);
+                            }
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(tree.distances, expected);
+            for (node, distance) in expected.iter().enumerate() {
+                if let Some(path) = tree.path_to(node) {
+                    assert_eq!(path.len() - 1, distance.unwrap());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn depth_first_reports_events() {
+        let graph = vec![vec![1, 2], vec![2], vec![0], vec![2]];
+        let mut events = Vec::new();
+        depth_first_search(&graph, [0, 3], |event| events.push(event));
+
+        use DfsEvent::*;
+        assert_eq!(
+            events,
+            vec![
+                Discover(0),
+                TreeEdge(0, 1),
+                Discover(1),
+                TreeEdge(1, 2),
+                Discover(2),
+                BackEdge(2, 0),
+                Finish(2),
+                Finish(1),
+                ForwardOrCrossEdge(0, 2),
+                Finish(0),
+                Discover(3),
+                ForwardOrCrossEdge(3, 2),
+                Finish(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn depth_first_matches_recursion() {
+        fn recurse(
+            graph: &[Vec<usize>],
+            node: usize,
+            seen: &mut [bool],
+            events: &mut Vec<DfsEvent>,
+        ) {
+            seen[node] = true;
+            events.push(DfsEvent::Discover(node));
+            for &next in &graph[node] {
+                if !seen[next] {
+                    events.push(DfsEvent::TreeEdge(node, next));
+                    recurse(graph, next, seen, events);
+                }
+            }
+            events.push(DfsEvent::Finish(node));
+        }
+
+        let mut random = Random::new(43);
+        for _ in 0..50 {
+            let graph = random_graph(&mut random, 20, 40);
+
+            let mut expected = Vec::new();
+            let mut seen = vec![false; graph.len()];
+            for node in 0..graph.len() {
+                if !seen[node] {
+                    recurse(&graph, node, &mut seen, &mut expected);
+                }
+            }
+
+            let mut events = Vec::new();
+            depth_first_search(&graph, 0..graph.len(), |event| {
+                if matches!(
+                    event,
+                    DfsEvent::Discover(_) | DfsEvent::TreeEdge(..) | DfsEvent::Finish(_)
+                ) {
+                    events.push(event);
+                }
+            });
+            assert_eq!(events, expected);
+        }
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let graph = vec![vec![], vec![0], vec![0, 1], vec![2], vec![1, 3]];
+        assert_eq!(topological_sort_kahn(&graph), Ok(vec![4, 3, 2, 1, 0]));
+        assert_topological(&graph, &topological_sort_dfs(&graph).unwrap());
+        assert_eq!(find_cycle(&graph), None);
+        assert_eq!(topological_sort_kahn(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        // 4 hangs off the cycle 1 -> 2 -> 3 -> 1 without being part of it.
+        let graph = vec![vec![1], vec![2], vec![3, 4], vec![1], vec![]];
+        assert_eq!(topological_sort_kahn(&graph), Err(Cycle(vec![2, 3, 1])));
+        assert_eq!(topological_sort_dfs(&graph), Err(Cycle(vec![1, 2, 3])));
+        assert_eq!(find_cycle(&[vec![0]]), Some(Cycle(vec![0])));
+    }
+
+    #[test]
+    fn sorts_or_finds_cycles_in_random_graphs() {
+        let mut random = Random::new(43);
+
+        for round in 0..200 {
+            // Mostly forward edges so about half the graphs are acyclic.
+            let len = 15;
+            let mut graph = vec![Vec::new(); len];
+            for _ in 0..25 {
+                let (a, b) = (random.range(0..len), random.range(0..len));
+                if a < b || random.next_bool(0.02) {
+                    graph[a].push(b);
+                }
+            }
+
+            let (kahn, dfs) = (topological_sort_kahn(&graph), topological_sort_dfs(&graph));
+            assert_eq!(kahn.is_ok(), dfs.is_ok(), "Round {}.", round);
+            for result in [kahn, dfs] {
+                match result {
+                    Ok(order) => assert_topological(&graph, &order),
+                    Err(cycle) => assert_cycle(&graph, &cycle),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn checks_bipartiteness() {
+        let square = undirected(&[vec![1], vec![2], vec![3], vec![0]]);
+        let sides = bipartition(&square).unwrap();
+        assert_eq!(sides, vec![false, true, false, true]);
+
+        let triangle = undirected(&[vec![1], vec![2], vec![0]]);
+        assert_eq!(bipartition(&triangle), None);
+        assert_eq!(bipartition(&[vec![0]]), None);
+    }
+
+    #[test]
+    fn bipartition_matches_brute_force() {
+        let mut random = Random::new(43);
+
+        for _ in 0..200 {
+            let graph = undirected(&random_graph(&mut random, 8, 8));
+            let valid = |sides: &dyn Fn(usize) -> bool| {
+                (0..graph.len()).all(|from| graph[from].iter().all(|to| sides(from) != sides(*to)))
+            };
+            let exists =
+                (0..1u32 << graph.len()).any(|mask| valid(&|node| mask & (1 << node) != 0));
+
+            match bipartition(&graph) {
+                Some(sides) => assert!(valid(&|node| sides[node])),
+                None => assert!(!exists),
+            }
+        }
+    }
+
+    #[test]
+    fn finds_components_like_disjoint_set() {
+        let mut random = Random::new(43);
+
+        for _ in 0..50 {
+            let directed = random_graph(&mut random, 40, 30);
+            let graph = undirected(&directed);
+            let components = connected_components(&graph);
+
+            let mut set = DisjointSet::new(graph.len());
+            for (from, successors) in directed.iter().enumerate() {
+                for &to in successors {
+                    set.union(from, to);
+                }
+            }
+
+            assert_eq!(components.count, set.count_sets());
+            for a in 0..graph.len() {
+                for b in 0..graph.len() {
+                    assert_eq!(
+                        components.labels[a] == components.labels[b],
+                        set.connected(a, b)
+                    );
+                }
+            }
+            // Labels are numbered by smallest node.
+            let firsts: Vec<_> = (0..components.count)
+                .map(|label| components.labels.iter().position(|l| *l == label).unwrap())
+                .collect();
+            assert!(firsts.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn runs_on_graph_type() {
+        let mut graph = Graph::undirected();
+        let nodes: Vec<_> = (0..4).map(|value| graph.add_node(value)).collect();
+        graph.add_edge(nodes[0], nodes[1], ());
+        graph.add_edge(nodes[2], nodes[3], ());
+        graph.remove_node(nodes[1]);
+
+        // The removed node is left out, so 2 and 3 move down to indices 1 and 2.
+        let (ids, lists) = graph.adjacency_lists();
+        assert_eq!(ids, vec![nodes[0], nodes[2], nodes[3]]);
+        let components = connected_components(&lists);
+        assert_eq!(components.count, 2);
+        assert_eq!(components.labels, vec![0, 1, 1]);
+    }
+}
@@ -1,17 +1,48 @@
-pub fn insertion_sort<T>(list: &mut Vec<T>)
+use super::sort_stats::SortObserver;
+use std::cmp::Ordering;
+
+pub fn insertion_sort<T>(list: &mut [T])
 where
     T: Ord,
+{
+    insertion_sort_by(list, T::cmp);
+}
+
+/// Sorts with `compare` instead of `Ord`. Stable, a value only moves past strictly greater ones.
+pub fn insertion_sort_by<T, F>(list: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    insertion_sort_observed(list, compare, &mut ());
+}
+
+/// `insertion_sort_by` reporting its work to `observer`.
+pub fn insertion_sort_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
 {
     for i in 1..list.len() {
         for j in (1..=i).rev() {
-            if list[j] >= list[j - 1] {
+            observer.compare();
+            if compare(&list[j], &list[j - 1]) != Ordering::Less {
                 break;
             }
             list.swap(j, j - 1);
+            observer.swap();
         }
     }
 }
 
+/// `insertion_sort_by` comparing the keys `key` extracts.
+pub fn insertion_sort_by_key<T, K, F>(list: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    insertion_sort_by(list, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
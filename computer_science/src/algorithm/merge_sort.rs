@@ -1,49 +1,134 @@
-pub fn merge_sort<T: Ord + Copy>(list: &mut [T]) {
+use super::sort_stats::SortObserver;
+use std::{cmp::Ordering, ptr};
+
+pub fn merge_sort<T: Ord>(list: &mut [T]) {
+    merge_sort_by(list, T::cmp);
+}
+
+/// Sorts with `compare` instead of `Ord`. Stable, merges take ties from the left run.
+pub fn merge_sort_by<T, F>(list: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    merge_sort_observed(list, compare, &mut ());
+}
+
+/// `merge_sort_by` reporting its work to `observer`.
+pub fn merge_sort_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    // Merges move the left half out of the way, which is never longer than half the list.
+    let mut scratch = Vec::with_capacity(list.len() / 2);
+    observer.allocate(scratch.capacity());
+    recursion(list, &mut scratch, &mut compare, observer);
+}
+
+/// `merge_sort_by` comparing the keys `key` extracts.
+pub fn merge_sort_by_key<T, K, F>(list: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    merge_sort_by(list, |a, b| key(a).cmp(&key(b)));
+}
+
+fn recursion<T, F, O>(list: &mut [T], scratch: &mut Vec<T>, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     if list.len() <= 1 {
         return;
     }
 
     let partition_index = list.len() / 2;
 
-    let mut left = &mut list[0..partition_index].to_vec();
-    let mut right = &mut list[partition_index..list.len()].to_vec();
+    recursion(&mut list[..partition_index], scratch, compare, observer);
+    recursion(&mut list[partition_index..], scratch, compare, observer);
 
-    merge_sort(&mut left);
-    merge_sort(&mut right);
+    merge(list, partition_index, scratch, compare, observer);
+}
 
-    let mut merge_index = 0;
-    let mut left_index = 0;
-    let mut right_index = 0;
+/// Merges the sorted runs `list[..partition_index]` and `list[partition_index..]`.
+fn merge<T, F, O>(
+    list: &mut [T],
+    partition_index: usize,
+    scratch: &mut Vec<T>,
+    compare: &mut F,
+    observer: &mut O,
+) where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
+    let len = list.len();
+    let list_pointer = list.as_mut_ptr();
+    let mut right_index = partition_index;
 
-    while left_index < left.len() && right_index < right.len() {
-        let left_value = left[left_index];
-        let right_value = right[right_index];
+    // The left run moves into the scratch buffer, whose length stays 0 so it never drops them. Values then move back
+    // one at a time into the gap in front of the right run, which always has room for the left values still out.
+    unsafe {
+        ptr::copy_nonoverlapping(list_pointer, scratch.as_mut_ptr(), partition_index);
+        let mut gap = Gap {
+            left: scratch.as_ptr(),
+            left_index: 0,
+            left_len: partition_index,
+            destination: list_pointer,
+        };
 
-        if left_value < right_value {
-            list[merge_index] = left_value;
-            left_index += 1;
-        } else {
-            list[merge_index] = right_value;
-            right_index += 1;
-        }
+        while gap.left_index < gap.left_len && right_index < len {
+            let left = gap.left.add(gap.left_index);
+            let right = list_pointer.add(right_index);
 
-        merge_index += 1;
+            // Ties take the left value, which keeps equal values in order.
+            observer.compare();
+            let source = if compare(&*right, &*left) == Ordering::Less {
+                right_index += 1;
+                right
+            } else {
+                gap.left_index += 1;
+                left
+            };
+            ptr::copy_nonoverlapping(source, gap.destination, 1);
+            gap.destination = gap.destination.add(1);
+        }
+        // Dropping the gap moves the rest of the left run back, also when `compare` panics.
     }
 
-    for i in left_index..left.len() {
-        list[merge_index] = left[i];
-        merge_index += 1;
-    }
+    // The left run went out and back, right values only moved while left ones remained.
+    observer.moves(2 * partition_index + right_index - partition_index);
+}
 
-    for i in right_index..right.len() {
-        list[merge_index] = right[i];
-        merge_index += 1;
+/// Left values of a merge still in the scratch buffer and where they go back to.
+struct Gap<T> {
+    left: *const T,
+    left_index: usize,
+    left_len: usize,
+    destination: *mut T,
+}
+
+impl<T> Drop for Gap<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(
+                self.left.add(self.left_index),
+                self.destination,
+                self.left_len - self.left_index,
+            );
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::random::Random;
+    use std::{
+        cell::Cell,
+        panic::{self, AssertUnwindSafe},
+        rc::Rc,
+    };
 
     #[test]
     fn sorts() {
@@ -70,4 +155,76 @@ mod tests {
         assert_eq!(list.len(), 1);
         assert_eq!(list[0], 1);
     }
+
+    #[test]
+    fn sorts_owned_values() {
+        let mut list: Vec<String> = ["pear", "apple", "fig", "banana", "cherry", "apple"]
+            .iter()
+            .map(|word| word.to_string())
+            .collect();
+        merge_sort(&mut list);
+        assert_eq!(
+            list,
+            vec!["apple", "apple", "banana", "cherry", "fig", "pear"]
+        );
+
+        merge_sort_by_key(&mut list[1..5], |word| word.len());
+        assert_eq!(
+            list,
+            vec!["apple", "fig", "apple", "banana", "cherry", "pear"]
+        );
+    }
+
+    #[test]
+    fn matches_std_sort() {
+        let mut random = Random::new(48);
+
+        for len in 0..200 {
+            let mut list: Vec<_> = (0..len).map(|_| random.range(0..50).to_string()).collect();
+            let mut expected = list.clone();
+            expected.sort();
+
+            merge_sort(&mut list);
+            assert_eq!(list, expected);
+        }
+    }
+
+    #[test]
+    fn keeps_every_value_when_compare_panics() {
+        // Counts drops to catch values lost or dropped twice.
+        struct Tracked(u32, Rc<Cell<usize>>);
+
+        impl Drop for Tracked {
+            fn drop(&mut self) {
+                self.1.set(self.1.get() + 1);
+            }
+        }
+
+        let mut random = Random::new(48);
+        for limit in 0..100 {
+            let drops = Rc::new(Cell::new(0));
+            let mut list: Vec<_> = (0..40)
+                .map(|_| Tracked(random.range(0..1000) as u32, drops.clone()))
+                .collect();
+            let mut expected: Vec<_> = list.iter().map(|value| value.0).collect();
+
+            let mut comparisons = 0;
+            let result = panic::catch_unwind(AssertUnwindSafe(|| {
+                merge_sort_by(&mut list, |a, b| {
+                    comparisons += 1;
+                    assert!(comparisons < limit, "Comparison limit.");
+                    a.0.cmp(&b.0)
+                });
+            }));
+            assert!(result.is_err());
+
+            let mut values: Vec<_> = list.iter().map(|value| value.0).collect();
+            values.sort();
+            expected.sort();
+            assert_eq!(values, expected);
+
+            drop(list);
+            assert_eq!(drops.get(), 40);
+        }
+    }
 }
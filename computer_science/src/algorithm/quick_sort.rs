@@ -1,31 +1,76 @@
-pub fn quick_sort<T: Ord>(list: &mut Vec<T>) {
+use super::sort_stats::SortObserver;
+use std::cmp::Ordering;
+
+pub fn quick_sort<T: Ord>(list: &mut [T]) {
+    quick_sort_by(list, T::cmp);
+}
+
+/// Sorts with `compare` instead of `Ord`. Not stable, partitioning swaps values across equal ones.
+pub fn quick_sort_by<T, F>(list: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    quick_sort_observed(list, compare, &mut ());
+}
+
+/// `quick_sort_by` reporting its work to `observer`.
+pub fn quick_sort_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     if list.len() <= 1 {
         return;
     }
 
-    recursion(list, 0, list.len() - 1);
+    recursion(list, 0, list.len() - 1, &mut compare, observer);
+}
+
+/// `quick_sort_by` comparing the keys `key` extracts.
+pub fn quick_sort_by_key<T, K, F>(list: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    quick_sort_by(list, |a, b| key(a).cmp(&key(b)));
 }
 
-fn recursion<T: Ord>(list: &mut Vec<T>, low: usize, high: usize) {
+fn recursion<T, F, O>(list: &mut [T], low: usize, high: usize, compare: &mut F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     if low >= high {
         return;
     }
 
-    let partition_index = create_partitions(list, low, high);
+    let partition_index = create_partitions(list, low, high, compare, observer);
 
     if partition_index > 0 {
-        recursion(list, low, partition_index - 1);
+        recursion(list, low, partition_index - 1, compare, observer);
     }
-    recursion(list, partition_index + 1, high);
+    recursion(list, partition_index + 1, high, compare, observer);
 }
 
-fn create_partitions<T: Ord>(list: &mut Vec<T>, low: usize, high: usize) -> usize {
+fn create_partitions<T, F, O>(
+    list: &mut [T],
+    low: usize,
+    high: usize,
+    compare: &mut F,
+    observer: &mut O,
+) -> usize
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
+{
     let mut swap_index = low + 1;
 
     for j in (low + 1)..=high {
-        if list[j] < list[low] {
+        observer.compare();
+        if compare(&list[j], &list[low]) == Ordering::Less {
             if swap_index != j {
                 list.swap(swap_index, j);
+                observer.swap();
             }
             swap_index += 1;
         }
@@ -34,6 +79,7 @@ fn create_partitions<T: Ord>(list: &mut Vec<T>, low: usize, high: usize) -> usiz
     let partition_index = swap_index - 1;
     if low != partition_index {
         list.swap(low, partition_index);
+        observer.swap();
     }
 
     partition_index
@@ -1,6 +1,27 @@
-pub fn selection_sort<T>(list: &mut Vec<T>)
+use super::sort_stats::SortObserver;
+use std::cmp::Ordering;
+
+pub fn selection_sort<T>(list: &mut [T])
 where
     T: Ord,
+{
+    selection_sort_by(list, T::cmp);
+}
+
+/// Sorts with `compare` instead of `Ord`. Not stable, swapping the minimum to the front can move the value it
+/// replaces behind an equal one.
+pub fn selection_sort_by<T, F>(list: &mut [T], compare: F)
+where
+    F: FnMut(&T, &T) -> Ordering,
+{
+    selection_sort_observed(list, compare, &mut ());
+}
+
+/// `selection_sort_by` reporting its work to `observer`.
+pub fn selection_sort_observed<T, F, O>(list: &mut [T], mut compare: F, observer: &mut O)
+where
+    F: FnMut(&T, &T) -> Ordering,
+    O: SortObserver,
 {
     if list.len() == 0 {
         return;
@@ -10,7 +31,8 @@ where
         let mut min_value_index = i;
 
         for j in (i + 1)..list.len() {
-            if list[j] < list[min_value_index] {
+            observer.compare();
+            if compare(&list[j], &list[min_value_index]) == Ordering::Less {
                 min_value_index = j;
             }
         }
@@ -20,9 +42,19 @@ where
         }
 
         list.swap(min_value_index, i);
+        observer.swap();
     }
 }
 
+/// `selection_sort_by` comparing the keys `key` extracts.
+pub fn selection_sort_by_key<T, K, F>(list: &mut [T], mut key: F)
+where
+    K: Ord,
+    F: FnMut(&T) -> K,
+{
+    selection_sort_by(list, |a, b| key(a).cmp(&key(b)));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,465 @@
+use std::{cmp::Reverse, collections::BinaryHeap, ops::Add};
+
+/// Edge weights, `Default` being zero. Sums are expected not to overflow.
+pub trait Weight: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<T: Copy + Ord + Add<Output = T> + Default> Weight for T {}
+
+/// Distances from a source and the tree of routes realizing them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ShortestPaths<W> {
+    pub source: usize,
+    pub distances: Vec<Option<W>>,
+    /// Node before each node on its route, `None` for the source and unreached nodes.
+    pub parents: Vec<Option<usize>>,
+}
+
+impl<W: Weight> ShortestPaths<W> {
+    fn new(len: usize, source: usize) -> Self {
+        let mut distances = vec![None; len];
+        distances[source] = Some(W::default());
+        ShortestPaths {
+            source,
+            distances,
+            parents: vec![None; len],
+        }
+    }
+
+    pub fn distance(&self, node: usize) -> Option<W> {
+        self.distances[node]
+    }
+
+    /// Nodes from the source to `node`, `None` if it wasn't reached.
+    pub fn path_to(&self, node: usize) -> Option<Vec<usize>> {
+        self.distances[node]?;
+
+        let mut path = vec![node];
+        while let Some(parent) = self.parents[*path.last().unwrap()] {
+            path.push(parent);
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Lowers the distance of `to` if going through `from` is shorter.
+    fn relax(&mut self, from: usize, to: usize, weight: W) -> bool {
+        let Some(distance) = self.distances[from].map(|distance| distance + weight) else {
+            return false;
+        };
+        if self.distances[to].is_some_and(|current| current <= distance) {
+            return false;
+        }
+        self.distances[to] = Some(distance);
+        self.parents[to] = Some(from);
+        true
+    }
+}
+
+/// Nodes of a cycle of negative total weight, each with an edge to the next and the last with one to the first.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NegativeCycle(pub Vec<usize>);
+
+/// Dijkstra's algorithm with a binary heap, O((n + m) log n). Graphs are adjacency lists of `(target, weight)` pairs,
+/// as `Graph::weighted_adjacency_lists` builds them. Weights must not be negative.
+pub fn dijkstra<W: Weight>(graph: &[Vec<(usize, W)>], source: usize) -> ShortestPaths<W> {
+    let mut paths = ShortestPaths::new(graph.len(), source);
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((W::default(), source)));
+
+    while let Some(Reverse((distance, node))) = heap.pop() {
+        // Nodes are pushed again on every improvement, only the latest entry counts.
+        if paths.distances[node] != Some(distance) {
+            continue;
+        }
+        for &(next, weight) in &graph[node] {
+            assert!(weight >= W::default(), "Negative edge weight.");
+            if paths.relax(node, next, weight) {
+                heap.push(Reverse((distance + weight, next)));
+            }
+        }
+    }
+    paths
+}
+
+/// Bellman-Ford algorithm, O(nm). Allows negative weights and fails with a negative cycle reachable from the source.
+pub fn bellman_ford<W: Weight>(
+    graph: &[Vec<(usize, W)>],
+    source: usize,
+) -> Result<ShortestPaths<W>, NegativeCycle> {
+    let mut paths = ShortestPaths::new(graph.len(), source);
+
+    // Shortest routes have fewer than n edges, an improvement in round n means a negative cycle.
+    for round in 1..=graph.len() {
+        let mut last = None;
+        for (node, edges) in graph.iter().enumerate() {
+            for &(next, weight) in edges {
+                if paths.relax(node, next, weight) {
+                    last = Some(next);
+                }
+            }
+        }
+
+        match last {
+            None => break,
+            Some(node) if round == graph.len() => return Err(negative_cycle(&paths.parents, node)),
+            Some(_) => {}
+        }
+    }
+    Ok(paths)
+}
+
+/// Walks parents from a node improved in the last round into the cycle and collects it.
+fn negative_cycle(parents: &[Option<usize>], mut node: usize) -> NegativeCycle {
+    // The parents of a node still improving in round n lead into the cycle, n steps back are on it.
+    for _ in 0..parents.len() {
+        node = parents[node].unwrap();
+    }
+
+    let mut cycle = vec![node];
+    let mut previous = parents[node].unwrap();
+    while previous != node {
+        cycle.push(previous);
+        previous = parents[previous].unwrap();
+    }
+    cycle.reverse();
+    NegativeCycle(cycle)
+}
+
+/// Floyd-Warshall algorithm, O(n³) time and O(n²) memory. Shortest paths from every source in turn, fails with a
+/// negative cycle anywhere in the graph.
+pub fn floyd_warshall<W: Weight>(
+    graph: &[Vec<(usize, W)>],
+) -> Result<Vec<ShortestPaths<W>>, NegativeCycle> {
+    let len = graph.len();
+    let mut all: Vec<_> = (0..len)
+        .map(|source| ShortestPaths::new(len, source))
+        .collect();
+    for (node, edges) in graph.iter().enumerate() {
+        for &(next, weight) in edges {
+            all[node].relax(node, next, weight);
+        }
+    }
+
+    for middle in 0..len {
+        // Routes through `middle` from and to it don't change this round, a copy of its row suffices.
+        let through = all[middle].clone();
+        for paths in &mut all {
+            let Some(first) = paths.distances[middle] else {
+                continue;
+            };
+            for target in 0..len {
+                let Some(second) = through.distances[target] else {
+                    continue;
+                };
+                if paths.distances[target].is_none_or(|current| first + second < current) {
+                    paths.distances[target] = Some(first + second);
+                    paths.parents[target] = through.parents[target];
+                }
+            }
+        }
+
+        // A negative cycle makes some node shorter than itself, Bellman-Ford from it recovers the cycle. Checked every
+        // round, since further rounds keep adding the cycle's weight to itself until it overflows.
+        if let Some(node) = (0..len).find(|node| all[*node].distances[*node] < Some(W::default())) {
+            return Err(bellman_ford(graph, node).err().unwrap());
+        }
+    }
+    Ok(all)
+}
+
+/// A* search from `source` until `target` is settled, guided by a `heuristic` estimate of the remaining distance to
+/// `target`. An estimate never above the true distance gives a shortest route. Only distances on that route are
+/// final, other reached nodes hold upper bounds. Weights must not be negative.
+pub fn a_star<W: Weight>(
+    graph: &[Vec<(usize, W)>],
+    source: usize,
+    target: usize,
+    mut heuristic: impl FnMut(usize) -> W,
+) -> ShortestPaths<W> {
+    let mut paths = ShortestPaths::new(graph.len(), source);
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(source), W::default(), source)));
+
+    while let Some(Reverse((_, distance, node))) = heap.pop() {
+        // Inconsistent heuristics can improve settled nodes, which are then expanded again.
+        if paths.distances[node] != Some(distance) {
+            continue;
+        }
+        if node == target {
+            break;
+        }
+        for &(next, weight) in &graph[node] {
+            assert!(weight >= W::default(), "Negative edge weight.");
+            if paths.relax(node, next, weight) {
+                let distance = distance + weight;
+                heap.push(Reverse((distance + heuristic(next), distance, next)));
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::graph::Graph;
+    use crate::random::Random;
+
+    fn random_graph(
+        random: &mut Random,
+        len: usize,
+        edges: usize,
+        weights: std::ops::Range<i64>,
+    ) -> Vec<Vec<(usize, i64)>> {
+        let span = (weights.end - weights.start) as usize;
+        let mut graph = vec![Vec::new(); len];
+        for _ in 0..edges {
+            let (from, to) = (random.range(0..len), random.range(0..len));
+            let weight = weights.start + random.range(0..span) as i64;
+            graph[from].push((to, weight));
+        }
+        graph
+    }
+
+    // Total weight along a route, taking the lightest of parallel edges.
+    fn route_weight(graph: &[Vec<(usize, i64)>], route: &[usize]) -> i64 {
+        route
+            .windows(2)
+            .map(|pair| {
+                graph[pair[0]]
+                    .iter()
+                    .filter(|(to, _)| *to == pair[1])
+                    .map(|(_, weight)| *weight)
+                    .min()
+                    .expect("Route follows a missing edge.")
+            })
+            .sum()
+    }
+
+    fn assert_routes(graph: &[Vec<(usize, i64)>], paths: &ShortestPaths<i64>) {
+        for node in 0..graph.len() {
+            match paths.path_to(node) {
+                Some(route) => {
+                    assert_eq!(route[0], paths.source);
+                    assert_eq!(*route.last().unwrap(), node);
+                    assert_eq!(Some(route_weight(graph, &route)), paths.distance(node));
+                }
+                None => assert_eq!(paths.distance(node), None),
+            }
+        }
+    }
+
+    // Cells of a grid are nodes, stepping onto a cell costs its value and walls (`None`) can't be entered.
+    fn grid_graph(cells: &[Vec<Option<i64>>]) -> Vec<Vec<(usize, i64)>> {
+        let (rows, columns) = (cells.len(), cells[0].len());
+        let mut graph = vec![Vec::new(); rows * columns];
+        for row in 0..rows {
+            for column in 0..columns {
+                let steps = [(0, 1), (2, 1), (1, 0), (1, 2)];
+                for (dr, dc) in steps {
+                    let (r, c) = ((row + dr).wrapping_sub(1), (column + dc).wrapping_sub(1));
+                    if let Some(Some(cost)) = cells.get(r).and_then(|cells| cells.get(c)) {
+                        graph[row * columns + column].push((r * columns + c, *cost));
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn finds_shortest_routes() {
+        let graph = vec![
+            vec![(1, 4), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 5)],
+            vec![],
+            vec![(0, 1)],
+        ];
+        let paths = dijkstra(&graph, 0);
+
+        assert_eq!(
+            paths.distances,
+            vec![Some(0), Some(3), Some(1), Some(4), None]
+        );
+        assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(paths.path_to(4), None);
+        assert_eq!(bellman_ford(&graph, 0), Ok(paths.clone()));
+        assert_eq!(floyd_warshall(&graph).unwrap()[0], paths);
+        assert_eq!(
+            a_star(&graph, 0, 3, |_| 0).path_to(3),
+            Some(vec![0, 2, 1, 3])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Negative edge weight.")]
+    fn dijkstra_rejects_negative_weights() {
+        dijkstra(&[vec![(1, -1)], vec![]], 0);
+    }
+
+    #[test]
+    fn handles_negative_weights() {
+        let graph = vec![vec![(1, 4), (2, 5)], vec![(3, 3)], vec![(1, -3)], vec![]];
+        let paths = bellman_ford(&graph, 0).unwrap();
+
+        assert_eq!(paths.distances, vec![Some(0), Some(2), Some(5), Some(5)]);
+        assert_eq!(paths.path_to(3), Some(vec![0, 2, 1, 3]));
+        assert_eq!(floyd_warshall(&graph).unwrap()[0], paths);
+    }
+
+    #[test]
+    fn reports_negative_cycles() {
+        // 1 -> 2 -> 3 -> 1 weighs -1 in total, 4 is only reachable through it.
+        let graph = vec![
+            vec![(1, 1)],
+            vec![(2, 1)],
+            vec![(3, 1), (4, 1)],
+            vec![(1, -3)],
+            vec![],
+        ];
+
+        let NegativeCycle(cycle) = bellman_ford(&graph, 0).unwrap_err();
+        assert_eq!(cycle.len(), 3);
+        assert_eq!(
+            route_weight(&graph, &[cycle.clone(), vec![cycle[0]]].concat()),
+            -1
+        );
+        assert!(floyd_warshall(&graph).is_err());
+
+        // Every pair of a complete graph is a cycle of -2, which must be caught before the weights overflow.
+        let complete: Vec<Vec<(usize, i64)>> = (0..80)
+            .map(|_| (0..80).map(|to| (to, -1)).collect())
+            .collect();
+        let NegativeCycle(cycle) = floyd_warshall(&complete).unwrap_err();
+        assert!(route_weight(&complete, &[cycle.clone(), vec![cycle[0]]].concat()) < 0);
+
+        // Unreachable from 4, so Bellman-Ford from there succeeds.
+        assert!(bellman_ford(&graph, 4).is_ok());
+        assert_eq!(
+            bellman_ford(&[vec![(0, -1)]], 0),
+            Err(NegativeCycle(vec![0]))
+        );
+    }
+
+    #[test]
+    fn algorithms_agree_on_random_graphs() {
+        let mut random = Random::new(44);
+
+        for _ in 0..50 {
+            let graph = random_graph(&mut random, 25, 80, 0..20);
+            let all = floyd_warshall(&graph).unwrap();
+
+            for (source, from_source) in all.iter().enumerate() {
+                let paths = dijkstra(&graph, source);
+                assert_routes(&graph, &paths);
+                assert_routes(&graph, from_source);
+                assert_eq!(from_source.distances, paths.distances);
+                assert_eq!(
+                    bellman_ford(&graph, source).unwrap().distances,
+                    paths.distances
+                );
+
+                let target = random.range(0..graph.len());
+                let found = a_star(&graph, source, target, |_| 0);
+                assert_eq!(found.distance(target), paths.distance(target));
+                if let Some(route) = found.path_to(target) {
+                    assert_eq!(Some(route_weight(&graph, &route)), paths.distance(target));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn negative_weights_agree_on_random_graphs() {
+        let mut random = Random::new(44);
+        let mut cycles = 0;
+
+        for _ in 0..200 {
+            let graph = random_graph(&mut random, 12, 30, -3..20);
+            let all = floyd_warshall(&graph);
+
+            for source in 0..graph.len() {
+                match bellman_ford(&graph, source) {
+                    Ok(paths) => {
+                        assert_routes(&graph, &paths);
+                        if let Ok(all) = &all {
+                            // Routes of equal weight may differ.
+                            assert_eq!(all[source].distances, paths.distances);
+                        }
+                    }
+                    Err(NegativeCycle(cycle)) => {
+                        assert!(all.is_err());
+                        let closed = [cycle.clone(), vec![cycle[0]]].concat();
+                        assert!(route_weight(&graph, &closed) < 0);
+                        cycles += 1;
+                    }
+                }
+            }
+            if let Ok(all) = &all {
+                for paths in all {
+                    assert_routes(&graph, paths);
+                }
+            }
+        }
+        assert!(cycles > 0);
+    }
+
+    #[test]
+    fn a_star_crosses_grids() {
+        let mut random = Random::new(44);
+
+        for _ in 0..30 {
+            let (rows, columns) = (20, 30);
+            let cells: Vec<Vec<_>> = (0..rows)
+                .map(|_| {
+                    (0..columns)
+                        .map(|_| (!random.next_bool(0.25)).then(|| 1 + random.range(0..9) as i64))
+                        .collect()
+                })
+                .collect();
+            let graph = grid_graph(&cells);
+            let (source, target) = (0, rows * columns - 1);
+
+            // Every step costs at least 1, so the Manhattan distance never overestimates.
+            let manhattan = |node: usize| {
+                let (row, column) = (node / columns, node % columns);
+                ((rows - 1 - row) + (columns - 1 - column)) as i64
+            };
+            let found = a_star(&graph, source, target, manhattan);
+            let expected = dijkstra(&graph, source);
+
+            assert_eq!(found.distance(target), expected.distance(target));
+            if let Some(route) = found.path_to(target) {
+                assert_eq!(
+                    Some(route_weight(&graph, &route)),
+                    expected.distance(target)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn a_star_follows_open_grid() {
+        let cells = vec![vec![Some(1); 5]; 5];
+        let graph = grid_graph(&cells);
+        let paths = a_star(&graph, 0, 24, |node| (4 - node / 5 + 4 - node % 5) as i64);
+
+        assert_eq!(paths.distance(24), Some(8));
+        assert_eq!(paths.path_to(24).unwrap().len(), 9);
+    }
+
+    #[test]
+    fn runs_on_graph_type() {
+        let mut graph = Graph::directed();
+        let nodes: Vec<_> = (0..3).map(|value| graph.add_node(value)).collect();
+        graph.add_edge(nodes[0], nodes[1], 5);
+        graph.add_edge(nodes[0], nodes[2], 1);
+        graph.add_edge(nodes[2], nodes[1], 2);
+
+        let (ids, lists) = graph.weighted_adjacency_lists();
+        assert_eq!(ids, nodes);
+        let paths = dijkstra(&lists, 0);
+        assert_eq!(paths.distances, vec![Some(0), Some(3), Some(1)]);
+    }
+}
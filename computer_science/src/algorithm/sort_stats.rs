@@ -0,0 +1,133 @@
+/// Receives the work a sort does as it happens. `()` ignores it, which is what the plain sort functions pass.
+pub trait SortObserver {
+    fn compare(&mut self) {}
+
+    /// Two values traded places.
+    fn swap(&mut self) {}
+
+    /// Values moved one by one rather than swapped, e.g. into and out of a merge buffer.
+    fn moves(&mut self, count: usize) {
+        let _ = count;
+    }
+
+    /// A buffer for `count` values was allocated.
+    fn allocate(&mut self, count: usize) {
+        let _ = count;
+    }
+}
+
+impl SortObserver for () {}
+
+/// Work one sort run did, as reported by `Sorter::sort_with_stats`.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct SortStats {
+    pub comparisons: usize,
+    pub swaps: usize,
+    pub moves: usize,
+    /// Values' worth of auxiliary buffers, summed over allocations.
+    pub allocated: usize,
+}
+
+impl SortObserver for SortStats {
+    fn compare(&mut self) {
+        self.comparisons += 1;
+    }
+
+    fn swap(&mut self) {
+        self.swaps += 1;
+    }
+
+    fn moves(&mut self, count: usize) {
+        self.moves += count;
+    }
+
+    fn allocate(&mut self, count: usize) {
+        self.allocated += count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::sorter::{sorters, InsertionSort, MergeSort, QuickSort, Sorter};
+    use crate::random::Random;
+
+    fn random_list(random: &mut Random, len: usize) -> Vec<u32> {
+        (0..len).map(|_| random.next_u64() as u32).collect()
+    }
+
+    #[test]
+    fn insertion_sort_is_linear_on_sorted_input() {
+        let mut sorted: Vec<u32> = (0..1000).collect();
+        let stats = InsertionSort.sort_with_stats(&mut sorted);
+        assert_eq!(
+            stats,
+            SortStats {
+                comparisons: 999,
+                ..SortStats::default()
+            }
+        );
+
+        // Reversed input swaps every pair.
+        let mut reversed: Vec<u32> = (0..1000).rev().collect();
+        let stats = InsertionSort.sort_with_stats(&mut reversed);
+        assert_eq!(stats.swaps, 1000 * 999 / 2);
+        assert_eq!(stats.comparisons, 1000 * 999 / 2);
+    }
+
+    #[test]
+    fn quick_sort_degrades_on_sorted_input() {
+        let mut random = Random::new(50);
+        let len = 1000;
+
+        let mut sorted: Vec<u32> = (0..len as u32).collect();
+        let degraded = QuickSort.sort_with_stats(&mut sorted);
+        assert_eq!(degraded.comparisons, len * (len - 1) / 2);
+
+        let mut shuffled = random_list(&mut random, len);
+        let typical = QuickSort.sort_with_stats(&mut shuffled);
+        // 2n ln n comparisons on average, about 13.8k here.
+        assert!(typical.comparisons < 20_000, "{:?}", typical);
+        assert!(degraded.comparisons > 20 * typical.comparisons);
+    }
+
+    #[test]
+    fn merge_sort_reports_buffer_and_moves() {
+        let mut random = Random::new(50);
+        let mut list = random_list(&mut random, 1024);
+        let stats = MergeSort.sort_with_stats(&mut list);
+
+        assert_eq!(stats.allocated, 512);
+        assert_eq!(stats.swaps, 0);
+        // Each of the 10 levels moves the left halves out and back, and part of the right halves.
+        assert!(
+            (10 * 1024..=20 * 1024).contains(&stats.moves),
+            "{:?}",
+            stats
+        );
+        assert!(stats.comparisons <= 10 * 1024);
+    }
+
+    #[test]
+    fn counts_match_plain_sorts() {
+        let mut random = Random::new(50);
+
+        for sorter in sorters::<u32>() {
+            for len in [0, 1, 2, 10, 100] {
+                let list = random_list(&mut random, len);
+                let mut expected = list.clone();
+                sorter.sort(&mut expected);
+
+                let mut counted = list.clone();
+                let stats = sorter.sort_with_stats(&mut counted);
+                assert_eq!(counted, expected, "{} sorted differently.", sorter.name());
+                if len < 2 {
+                    assert_eq!(stats.comparisons, 0);
+                }
+                if sorter.is_in_place() {
+                    assert_eq!((stats.moves, stats.allocated), (0, 0));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use super::{
+    bubble_sort::{bubble_sort, bubble_sort_observed},
+    insertion_sort::{insertion_sort, insertion_sort_observed},
+    merge_sort::{merge_sort, merge_sort_observed},
+    quick_sort::{quick_sort, quick_sort_observed},
+    selection_sort::{selection_sort, selection_sort_observed},
+    sort_stats::SortStats,
+};
+
+/// Big-O bounds of a sort over n values, e.g. `"O(n log n)"`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Complexity {
+    pub best: &'static str,
+    pub average: &'static str,
+    pub worst: &'static str,
+    /// Extra memory beyond the list, recursion included.
+    pub space: &'static str,
+}
+
+/// Sorting algorithm behind a common interface, so callers can pick one at runtime or run all of `sorters`.
+pub trait Sorter<T: Ord> {
+    fn name(&self) -> &'static str;
+
+    /// Whether equal values keep their order.
+    fn is_stable(&self) -> bool;
+
+    /// Whether values are only moved within the list, never into a buffer.
+    fn is_in_place(&self) -> bool;
+
+    fn complexity(&self) -> Complexity;
+
+    fn sort(&self, list: &mut [T]);
+
+    /// Sorts like `sort` while counting the work done.
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats;
+}
+
+pub struct BubbleSort;
+
+impl<T: Ord> Sorter<T> for BubbleSort {
+    fn name(&self) -> &'static str {
+        "bubble sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity {
+            best: "O(n²)",
+            average: "O(n²)",
+            worst: "O(n²)",
+            space: "O(1)",
+        }
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        bubble_sort(list);
+    }
+
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats {
+        let mut stats = SortStats::default();
+        bubble_sort_observed(list, T::cmp, &mut stats);
+        stats
+    }
+}
+
+pub struct InsertionSort;
+
+impl<T: Ord> Sorter<T> for InsertionSort {
+    fn name(&self) -> &'static str {
+        "insertion sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity {
+            best: "O(n)",
+            average: "O(n²)",
+            worst: "O(n²)",
+            space: "O(1)",
+        }
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        insertion_sort(list);
+    }
+
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats {
+        let mut stats = SortStats::default();
+        insertion_sort_observed(list, T::cmp, &mut stats);
+        stats
+    }
+}
+
+pub struct MergeSort;
+
+impl<T: Ord> Sorter<T> for MergeSort {
+    fn name(&self) -> &'static str {
+        "merge sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        true
+    }
+
+    fn is_in_place(&self) -> bool {
+        false
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity {
+            best: "O(n log n)",
+            average: "O(n log n)",
+            worst: "O(n log n)",
+            space: "O(n)",
+        }
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        merge_sort(list);
+    }
+
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats {
+        let mut stats = SortStats::default();
+        merge_sort_observed(list, T::cmp, &mut stats);
+        stats
+    }
+}
+
+pub struct QuickSort;
+
+impl<T: Ord> Sorter<T> for QuickSort {
+    fn name(&self) -> &'static str {
+        "quick sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+
+    fn complexity(&self) -> Complexity {
+        // The first value is the pivot, so sorted input hits the worst case and recurses n deep.
+        Complexity {
+            best: "O(n log n)",
+            average: "O(n log n)",
+            worst: "O(n²)",
+            space: "O(n)",
+        }
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        quick_sort(list);
+    }
+
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats {
+        let mut stats = SortStats::default();
+        quick_sort_observed(list, T::cmp, &mut stats);
+        stats
+    }
+}
+
+pub struct SelectionSort;
+
+impl<T: Ord> Sorter<T> for SelectionSort {
+    fn name(&self) -> &'static str {
+        "selection sort"
+    }
+
+    fn is_stable(&self) -> bool {
+        false
+    }
+
+    fn is_in_place(&self) -> bool {
+        true
+    }
+
+    fn complexity(&self) -> Complexity {
+        Complexity {
+            best: "O(n²)",
+            average: "O(n²)",
+            worst: "O(n²)",
+            space: "O(1)",
+        }
+    }
+
+    fn sort(&self, list: &mut [T]) {
+        selection_sort(list);
+    }
+
+    fn sort_with_stats(&self, list: &mut [T]) -> SortStats {
+        let mut stats = SortStats::default();
+        selection_sort_observed(list, T::cmp, &mut stats);
+        stats
+    }
+}
+
+/// Every sort in `algorithm`, in alphabetical order.
+pub fn sorters<T: Ord + 'static>() -> Vec<&'static dyn Sorter<T>> {
+    vec![
+        &BubbleSort,
+        &InsertionSort,
+        &MergeSort,
+        &QuickSort,
+        &SelectionSort,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::cmp::Ordering;
+
+    // Ordered by key only, so equal keys with different tags show whether a sort kept their order.
+    #[derive(Clone, Copy, Debug)]
+    struct Tagged {
+        key: u8,
+        tag: usize,
+    }
+
+    impl PartialEq for Tagged {
+        fn eq(&self, other: &Self) -> bool {
+            self.key == other.key
+        }
+    }
+
+    impl Eq for Tagged {}
+
+    impl PartialOrd for Tagged {
+        fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Tagged {
+        fn cmp(&self, other: &Self) -> Ordering {
+            self.key.cmp(&other.key)
+        }
+    }
+
+    #[test]
+    fn lists_every_sort() {
+        let names: Vec<_> = sorters::<i32>()
+            .iter()
+            .map(|sorter| sorter.name())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "bubble sort",
+                "insertion sort",
+                "merge sort",
+                "quick sort",
+                "selection sort"
+            ]
+        );
+    }
+
+    #[test]
+    fn every_sorter_sorts() {
+        let mut random = Random::new(49);
+
+        for sorter in sorters::<String>() {
+            for len in 0..60 {
+                let mut list: Vec<_> = (0..len).map(|_| random.range(0..20).to_string()).collect();
+                let mut expected = list.clone();
+                expected.sort();
+
+                sorter.sort(&mut list);
+                assert_eq!(list, expected, "{} failed.", sorter.name());
+            }
+        }
+    }
+
+    #[test]
+    fn stable_sorters_keep_equal_values_in_order() {
+        let mut random = Random::new(49);
+
+        for sorter in sorters::<Tagged>()
+            .into_iter()
+            .filter(|sorter| sorter.is_stable())
+        {
+            for _ in 0..20 {
+                let mut list: Vec<_> = (0..50)
+                    .map(|tag| Tagged {
+                        key: random.range(0..5) as u8,
+                        tag,
+                    })
+                    .collect();
+                sorter.sort(&mut list);
+
+                let kept = list
+                    .windows(2)
+                    .all(|pair| pair[0].key < pair[1].key || pair[0].tag < pair[1].tag);
+                assert!(kept, "{} reordered equal values.", sorter.name());
+            }
+        }
+    }
+
+    #[test]
+    fn describes_complexity() {
+        for sorter in sorters::<u8>() {
+            let complexity = sorter.complexity();
+            assert!([
+                complexity.best,
+                complexity.average,
+                complexity.worst,
+                complexity.space
+            ]
+            .iter()
+            .all(|bound| bound.starts_with("O(")));
+        }
+    }
+}
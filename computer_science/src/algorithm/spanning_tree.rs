@@ -0,0 +1,232 @@
+use super::{merge_sort::merge_sort, shortest_path::Weight};
+use crate::data_structure::disjoint_set::DisjointSet;
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+/// Minimum spanning tree of every connected component.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SpanningForest<W> {
+    pub weight: W,
+    /// Chosen `(from, to, weight)` edges, n minus the number of components of them.
+    pub edges: Vec<(usize, usize, W)>,
+}
+
+/// Kruskal's algorithm, O(m log m): takes edges by increasing weight unless they close a cycle. Graphs are undirected
+/// adjacency lists of `(target, weight)` pairs listing every edge from both ends.
+pub fn kruskal<W: Weight>(graph: &[Vec<(usize, W)>]) -> SpanningForest<W> {
+    // Weight first so sorting orders by it, each edge once from its smaller end.
+    let mut edges: Vec<_> = graph
+        .iter()
+        .enumerate()
+        .flat_map(|(from, edges)| {
+            edges
+                .iter()
+                .filter(move |(to, _)| from < *to)
+                .map(move |&(to, weight)| (weight, from, to))
+        })
+        .collect();
+    merge_sort(&mut edges);
+
+    let mut components = DisjointSet::new(graph.len());
+    let mut forest = SpanningForest {
+        weight: W::default(),
+        edges: Vec::new(),
+    };
+    for (weight, from, to) in edges {
+        if components.union(from, to) {
+            forest.weight = forest.weight + weight;
+            forest.edges.push((from, to, weight));
+        }
+    }
+    forest
+}
+
+/// Prim's algorithm with a binary heap, O(m log n): grows a tree from each unreached node by its lightest leaving edge.
+pub fn prim<W: Weight>(graph: &[Vec<(usize, W)>]) -> SpanningForest<W> {
+    let mut reached = vec![false; graph.len()];
+    let mut forest = SpanningForest {
+        weight: W::default(),
+        edges: Vec::new(),
+    };
+
+    for root in 0..graph.len() {
+        if reached[root] {
+            continue;
+        }
+
+        reached[root] = true;
+        let mut heap: BinaryHeap<_> = graph[root]
+            .iter()
+            .map(|&(to, weight)| Reverse((weight, root, to)))
+            .collect();
+        while let Some(Reverse((weight, from, to))) = heap.pop() {
+            if reached[to] {
+                continue;
+            }
+            reached[to] = true;
+            forest.weight = forest.weight + weight;
+            forest.edges.push((from, to, weight));
+            heap.extend(
+                graph[to]
+                    .iter()
+                    .filter(|(next, _)| !reached[*next])
+                    .map(|&(next, weight)| Reverse((weight, to, next))),
+            );
+        }
+    }
+    forest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::graph_traversal::connected_components;
+    use crate::data_structure::graph::Graph;
+    use crate::random::Random;
+
+    fn random_graph(random: &mut Random, len: usize, edges: usize) -> Vec<Vec<(usize, i64)>> {
+        let mut graph = vec![Vec::new(); len];
+        for _ in 0..edges {
+            let (from, to) = (random.range(0..len), random.range(0..len));
+            let weight = random.range(0..20) as i64 - 5;
+            graph[from].push((to, weight));
+            if from != to {
+                graph[to].push((from, weight));
+            }
+        }
+        graph
+    }
+
+    fn assert_spanning(graph: &[Vec<(usize, i64)>], forest: &SpanningForest<i64>) {
+        let unweighted: Vec<Vec<_>> = graph
+            .iter()
+            .map(|edges| edges.iter().map(|(to, _)| *to).collect())
+            .collect();
+        let count = connected_components(&unweighted).count;
+        assert_eq!(forest.edges.len(), graph.len() - count);
+
+        let mut components = DisjointSet::new(graph.len());
+        for &(from, to, weight) in &forest.edges {
+            assert!(graph[from].contains(&(to, weight)));
+            assert!(components.union(from, to), "Forest has a cycle.");
+        }
+        assert_eq!(
+            forest
+                .edges
+                .iter()
+                .map(|(_, _, weight)| weight)
+                .sum::<i64>(),
+            forest.weight
+        );
+    }
+
+    #[test]
+    fn finds_minimum_spanning_tree() {
+        let edges = [
+            (0, 1, 4),
+            (0, 2, 1),
+            (1, 2, 2),
+            (1, 3, 5),
+            (2, 3, 8),
+            (3, 4, 3),
+        ];
+        let mut graph = vec![Vec::new(); 5];
+        for (from, to, weight) in edges {
+            graph[from].push((to, weight));
+            graph[to].push((from, weight));
+        }
+
+        let forest = kruskal(&graph);
+        assert_eq!(forest.weight, 11);
+        assert_eq!(
+            forest.edges,
+            vec![(0, 2, 1), (1, 2, 2), (3, 4, 3), (1, 3, 5)]
+        );
+        assert_eq!(prim(&graph).weight, 11);
+        assert_spanning(&graph, &prim(&graph));
+    }
+
+    #[test]
+    fn spans_every_component() {
+        let graph = vec![
+            vec![(1, 2)],
+            vec![(0, 2)],
+            vec![],
+            vec![(4, 7)],
+            vec![(3, 7)],
+        ];
+        for forest in [kruskal(&graph), prim(&graph)] {
+            assert_eq!(forest.weight, 9);
+            assert_eq!(forest.edges.len(), 2);
+        }
+        assert_eq!(kruskal::<i64>(&[]).edges, vec![]);
+    }
+
+    #[test]
+    fn kruskal_and_prim_agree() {
+        let mut random = Random::new(45);
+
+        for _ in 0..200 {
+            let len = 1 + random.range(0..30);
+            let edges = random.range(0..4 * len);
+            let graph = random_graph(&mut random, len, edges);
+
+            let (by_kruskal, by_prim) = (kruskal(&graph), prim(&graph));
+            assert_spanning(&graph, &by_kruskal);
+            assert_spanning(&graph, &by_prim);
+            assert_eq!(by_kruskal.weight, by_prim.weight);
+        }
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut random = Random::new(45);
+
+        for _ in 0..100 {
+            let graph = random_graph(&mut random, 6, 10);
+            let edges: Vec<_> = graph
+                .iter()
+                .enumerate()
+                .flat_map(|(from, edges)| {
+                    edges
+                        .iter()
+                        .filter(move |(to, _)| from < *to)
+                        .map(move |e| (from, e.0, e.1))
+                })
+                .collect();
+            let forest = kruskal(&graph);
+
+            // Lightest acyclic edge subset of the forest's size.
+            let best = (0..1u32 << edges.len())
+                .filter(|mask| mask.count_ones() as usize == forest.edges.len())
+                .filter_map(|mask| {
+                    let mut components = DisjointSet::new(graph.len());
+                    let mut weight = 0;
+                    for (index, &(from, to, edge)) in edges.iter().enumerate() {
+                        if mask & (1 << index) != 0 {
+                            if !components.union(from, to) {
+                                return None;
+                            }
+                            weight += edge;
+                        }
+                    }
+                    Some(weight)
+                })
+                .min()
+                .unwrap();
+            assert_eq!(forest.weight, best);
+        }
+    }
+
+    #[test]
+    fn runs_on_graph_type() {
+        let mut graph = Graph::undirected();
+        let nodes: Vec<_> = (0..3).map(|value| graph.add_node(value)).collect();
+        graph.add_edge(nodes[0], nodes[1], 3);
+        graph.add_edge(nodes[1], nodes[2], 1);
+        graph.add_edge(nodes[2], nodes[0], 2);
+
+        let (_, lists) = graph.weighted_adjacency_lists();
+        assert_eq!(kruskal(&lists).weight, 3);
+        assert_eq!(prim(&lists).weight, 3);
+    }
+}
@@ -1,5 +1,26 @@
+pub mod b_tree_map;
+pub mod bitset;
+pub mod bloom_filter;
+pub mod cache;
+pub mod chained_hash_map;
 pub mod circular_array;
+pub mod disjoint_set;
 pub mod dynamic_array;
+pub mod fenwick_tree;
+pub mod graph;
+pub mod hash_map;
+pub mod hash_set;
+pub mod lca;
+pub mod left_leaning_red_black_map;
 pub mod linked_list;
+pub mod ordered_map;
 pub mod queue;
+pub mod radix_tree;
+pub mod red_black_map;
+pub mod segment_tree;
+pub mod sketch;
+pub mod skip_list;
+pub mod sparse_table;
 pub mod stack;
+pub mod treap;
+pub mod trie;
@@ -0,0 +1,1053 @@
+use super::ordered_map::OrderedMap;
+use std::{
+    cmp::Ordering,
+    mem,
+    ops::{Bound, RangeBounds},
+};
+
+struct Node<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    // Empty for leaves, otherwise always `keys.len() + 1` children.
+    children: Vec<Node<K, V>>,
+}
+
+impl<K, V> Node<K, V> {
+    fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            values: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            keys: Vec::with_capacity(capacity),
+            values: Vec::with_capacity(capacity),
+            children: Vec::new(),
+        }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Ordered map stored as a B-tree of minimum degree `B`.
+///
+/// Every node except the root holds between `B - 1` and `2 * B - 1` sorted keys in one contiguous array, so a lookup
+/// touches only O(log_B n) nodes and scans each of them linearly in cache, instead of chasing a pointer per key like
+/// binary search trees do.
+pub struct BTreeMap<K, V, const B: usize = 6> {
+    root: Node<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V, const B: usize> BTreeMap<K, V, B> {
+    const CAPACITY: usize = 2 * B - 1;
+    const MIN_KEYS: usize = B - 1;
+
+    pub fn new() -> Self {
+        const { assert!(B >= 2, "B-tree order has to be at least 2.") };
+
+        Self {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    /// Builds the tree bottom-up in O(n) from entries sorted by key. For duplicate keys the last value wins.
+    ///
+    /// Panics if the entries are not sorted.
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut entries: Vec<(K, V)> = Vec::new();
+
+        for (key, value) in iter {
+            if let Some((last_key, last_value)) = entries.last_mut() {
+                match key.cmp(last_key) {
+                    Ordering::Less => panic!("Entries are not sorted."),
+                    Ordering::Equal => {
+                        *last_value = value;
+                        continue;
+                    }
+                    Ordering::Greater => {}
+                }
+            }
+            entries.push((key, value));
+        }
+
+        let mut map = Self::new();
+        map.len = entries.len();
+
+        // Smallest height whose full tree fits all entries.
+        let mut height = 0;
+        while Self::max_entries(height) < entries.len() {
+            height += 1;
+        }
+
+        map.root = Self::build(&mut entries.into_iter(), map.len, height);
+        map
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.root = Node::new();
+        self.len = 0;
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut node = &self.root;
+
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(index) => return Some(&node.values[index]),
+                Err(_) if node.is_leaf() => return None,
+                Err(index) => node = &node.children[index],
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut node = &mut self.root;
+
+        loop {
+            match node.keys.binary_search(key) {
+                Ok(index) => return Some(&mut node.values[index]),
+                Err(_) if node.is_leaf() => return None,
+                Err(index) => node = &mut node.children[index],
+            }
+        }
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while let Some(child) = node.children.first() {
+            node = child;
+        }
+
+        Some((node.keys.first()?, node.values.first()?))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = &self.root;
+        while let Some(child) = node.children.last() {
+            node = child;
+        }
+
+        Some((node.keys.last()?, node.values.last()?))
+    }
+
+    pub fn pop_first(&mut self) -> Option<(K, V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let entry = Self::remove_first(&mut self.root);
+        self.after_remove();
+        Some(entry)
+    }
+
+    pub fn pop_last(&mut self) -> Option<(K, V)> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let entry = Self::remove_last(&mut self.root);
+        self.after_remove();
+        Some(entry)
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        // Full root is split up front, the tree grows in height only here.
+        if self.root.keys.len() == Self::CAPACITY {
+            let old_root = mem::replace(&mut self.root, Node::with_capacity(Self::CAPACITY));
+            self.root.children.push(old_root);
+            Self::split_child(&mut self.root, 0);
+        }
+
+        let previous = Self::insert_non_full(&mut self.root, key, value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+
+        previous
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (_, value) = Self::remove_from(&mut self.root, key)?;
+        self.after_remove();
+        Some(value)
+    }
+
+    /// Moves all entries with keys >= `key` into a new map.
+    ///
+    /// Every node on the path to `key` is cut in two, then the nodes along the cut are topped up or merged with their
+    /// siblings, O(B log n). Neither half knows its size afterwards, counting the smaller one adds O(min(k, n - k)).
+    pub fn split_off(&mut self, key: &K) -> Self {
+        let mut upper = Self {
+            root: Self::split_node(&mut self.root, key),
+            len: 0,
+        };
+        Self::fix_right_border(&mut self.root);
+        Self::fix_left_border(&mut upper.root);
+
+        // Counting both halves in lockstep stops at the end of the smaller one.
+        let (mut lower_entries, mut upper_entries) = (self.iter(), upper.iter());
+        let mut count = 0;
+        let lower_len = loop {
+            if lower_entries.next().is_none() {
+                break count;
+            }
+            if upper_entries.next().is_none() {
+                break self.len - count;
+            }
+            count += 1;
+        };
+        upper.len = self.len - lower_len;
+        self.len = lower_len;
+
+        upper
+    }
+
+    /// Moves all entries of `other` into this map, values from `other` win for equal keys.
+    ///
+    /// Both maps are merged as sorted sequences and rebuilt with `from_sorted_iter`, O(n + m).
+    pub fn append(&mut self, other: &mut Self) {
+        let mut left = mem::take(self).into_iter().peekable();
+        let mut right = mem::take(other).into_iter().peekable();
+        let mut merged = Vec::with_capacity(left.len() + right.len());
+
+        loop {
+            let take_left = match (left.peek(), right.peek()) {
+                (Some((left_key, _)), Some((right_key, _))) => left_key <= right_key,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+
+            // On equal keys the left entry is pushed first and then overwritten by the bulk-load.
+            match take_left {
+                true => merged.push(left.next().unwrap()),
+                false => merged.push(right.next().unwrap()),
+            }
+        }
+
+        *self = Self::from_sorted_iter(merged);
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Iterates over entries whose keys fall into the range, in ascending order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        // Stack holds (node, index of the next key to yield) for the path to the first entry in range.
+        let mut stack = Vec::new();
+        let mut node = &self.root;
+        loop {
+            let index = match range.start_bound() {
+                Bound::Included(key) => node.keys.partition_point(|node_key| node_key < key),
+                Bound::Excluded(key) => node.keys.partition_point(|node_key| node_key <= key),
+                Bound::Unbounded => 0,
+            };
+            stack.push((node, index));
+
+            match node.children.get(index) {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+
+        let mut iter = Iter {
+            stack,
+            end: self.end_position(range.end_bound()),
+        };
+
+        // Start past the end (e.g. `5..3`) yields nothing instead of running to the last entry.
+        iter.skip_exhausted();
+        if let (Some(&(node, index)), Some((end_node, end_index))) = (iter.stack.last(), iter.end) {
+            if node.keys[index] > end_node.keys[end_index] {
+                iter.stack.clear();
+            }
+        }
+
+        iter
+    }
+
+    /// Position of the first entry past the end bound, iteration stops when reaching it.
+    fn end_position(&self, bound: Bound<&K>) -> Option<(&Node<K, V>, usize)> {
+        let mut node = &self.root;
+        let mut candidate = None;
+
+        loop {
+            let index = match bound {
+                Bound::Included(key) => node.keys.partition_point(|node_key| node_key <= key),
+                Bound::Excluded(key) => node.keys.partition_point(|node_key| node_key < key),
+                Bound::Unbounded => return None,
+            };
+
+            if index < node.keys.len() {
+                candidate = Some((node, index));
+            }
+
+            match node.children.get(index) {
+                Some(child) => node = child,
+                None => return candidate,
+            }
+        }
+    }
+
+    /// Largest number of entries a tree of the given height can hold, (2B)^(height + 1) - 1.
+    fn max_entries(height: usize) -> usize {
+        (2 * B).saturating_pow(height as u32 + 1) - 1
+    }
+
+    /// Smallest number of entries a non-root subtree of the given height can hold, B^(height + 1) - 1.
+    fn min_entries(height: usize) -> usize {
+        B.saturating_pow(height as u32 + 1) - 1
+    }
+
+    /// Builds a subtree of exactly `height` from the next `count` entries.
+    ///
+    /// Uses as many children as possible (up to 2B) while keeping every child above its minimum, then spreads the
+    /// entries evenly, so all nodes stay within the B-tree bounds.
+    fn build<I: Iterator<Item = (K, V)>>(
+        entries: &mut I,
+        count: usize,
+        height: usize,
+    ) -> Node<K, V> {
+        let mut node = Node::with_capacity(Self::CAPACITY);
+
+        if height == 0 {
+            for (key, value) in entries.take(count) {
+                node.keys.push(key);
+                node.values.push(value);
+            }
+            return node;
+        }
+
+        let children_count = (2 * B).min((count + 1) / (Self::min_entries(height - 1) + 1));
+        let child_entries = count - (children_count - 1);
+        let (base, extra) = (
+            child_entries / children_count,
+            child_entries % children_count,
+        );
+
+        for child_index in 0..children_count {
+            let child_count = base + usize::from(child_index < extra);
+            node.children
+                .push(Self::build(entries, child_count, height - 1));
+
+            if child_index + 1 < children_count {
+                let (key, value) = entries.next().unwrap();
+                node.keys.push(key);
+                node.values.push(value);
+            }
+        }
+
+        node
+    }
+
+    fn insert_non_full(node: &mut Node<K, V>, key: K, value: V) -> Option<V> {
+        let mut index = match node.keys.binary_search(&key) {
+            Ok(index) => return Some(mem::replace(&mut node.values[index], value)),
+            Err(index) => index,
+        };
+
+        if node.is_leaf() {
+            node.keys.insert(index, key);
+            node.values.insert(index, value);
+            return None;
+        }
+
+        // Split a full child before descending, so there is always room for a key pushed up from below.
+        if node.children[index].keys.len() == Self::CAPACITY {
+            Self::split_child(node, index);
+            match key.cmp(&node.keys[index]) {
+                Ordering::Less => {}
+                Ordering::Greater => index += 1,
+                Ordering::Equal => return Some(mem::replace(&mut node.values[index], value)),
+            }
+        }
+
+        Self::insert_non_full(&mut node.children[index], key, value)
+    }
+
+    /// Splits the full child at `index` around its median, which moves up into `node`.
+    fn split_child(node: &mut Node<K, V>, index: usize) {
+        let child = &mut node.children[index];
+
+        let mut right = Node::with_capacity(Self::CAPACITY);
+        right.keys.extend(child.keys.drain(B..));
+        right.values.extend(child.values.drain(B..));
+        if !child.is_leaf() {
+            right.children = child.children.split_off(B);
+        }
+
+        let median_key = child.keys.pop().unwrap();
+        let median_value = child.values.pop().unwrap();
+
+        node.keys.insert(index, median_key);
+        node.values.insert(index, median_value);
+        node.children.insert(index + 1, right);
+    }
+
+    fn remove_from(node: &mut Node<K, V>, key: &K) -> Option<(K, V)> {
+        match node.keys.binary_search(key) {
+            Ok(index) if node.is_leaf() => {
+                Some((node.keys.remove(index), node.values.remove(index)))
+            }
+            Ok(index) => {
+                // Replace the key with its predecessor or successor, whichever child can spare one. If neither can,
+                // merge both children around the key and remove it from the merged node.
+                let replacement = if node.children[index].keys.len() > Self::MIN_KEYS {
+                    Self::remove_last(&mut node.children[index])
+                } else if node.children[index + 1].keys.len() > Self::MIN_KEYS {
+                    Self::remove_first(&mut node.children[index + 1])
+                } else {
+                    Self::merge_children(node, index);
+                    return Self::remove_from(&mut node.children[index], key);
+                };
+
+                let key = mem::replace(&mut node.keys[index], replacement.0);
+                let value = mem::replace(&mut node.values[index], replacement.1);
+                Some((key, value))
+            }
+            Err(_) if node.is_leaf() => None,
+            Err(index) => {
+                let index = Self::fill_child(node, index);
+                Self::remove_from(&mut node.children[index], key)
+            }
+        }
+    }
+
+    fn remove_first(node: &mut Node<K, V>) -> (K, V) {
+        if node.is_leaf() {
+            return (node.keys.remove(0), node.values.remove(0));
+        }
+
+        let index = Self::fill_child(node, 0);
+        Self::remove_first(&mut node.children[index])
+    }
+
+    fn remove_last(node: &mut Node<K, V>) -> (K, V) {
+        if node.is_leaf() {
+            return (node.keys.pop().unwrap(), node.values.pop().unwrap());
+        }
+
+        let index = Self::fill_child(node, node.children.len() - 1);
+        Self::remove_last(&mut node.children[index])
+    }
+
+    /// Makes sure the child at `index` has more than the minimum number of keys before descending into it, so a
+    /// removal below never underflows. Returns the index of the child to descend into (changes after a merge with
+    /// the left sibling).
+    fn fill_child(node: &mut Node<K, V>, index: usize) -> usize {
+        if node.children[index].keys.len() > Self::MIN_KEYS {
+            return index;
+        }
+
+        let has_left = index > 0;
+        let has_right = index + 1 < node.children.len();
+
+        if has_left && node.children[index - 1].keys.len() > Self::MIN_KEYS {
+            Self::borrow_from_left(node, index);
+            index
+        } else if has_right && node.children[index + 1].keys.len() > Self::MIN_KEYS {
+            Self::borrow_from_right(node, index);
+            index
+        } else if has_right {
+            Self::merge_children(node, index);
+            index
+        } else {
+            Self::merge_children(node, index - 1);
+            index - 1
+        }
+    }
+
+    /// Rotates the last entry of the left sibling through the separator into the child at `index`.
+    fn borrow_from_left(node: &mut Node<K, V>, index: usize) {
+        let (left_part, right_part) = node.children.split_at_mut(index);
+        let left = &mut left_part[index - 1];
+        let child = &mut right_part[0];
+
+        let key = mem::replace(&mut node.keys[index - 1], left.keys.pop().unwrap());
+        let value = mem::replace(&mut node.values[index - 1], left.values.pop().unwrap());
+        child.keys.insert(0, key);
+        child.values.insert(0, value);
+
+        if let Some(grandchild) = left.children.pop() {
+            child.children.insert(0, grandchild);
+        }
+    }
+
+    /// Rotates the first entry of the right sibling through the separator into the child at `index`.
+    fn borrow_from_right(node: &mut Node<K, V>, index: usize) {
+        let (left_part, right_part) = node.children.split_at_mut(index + 1);
+        let child = &mut left_part[index];
+        let right = &mut right_part[0];
+
+        let key = mem::replace(&mut node.keys[index], right.keys.remove(0));
+        let value = mem::replace(&mut node.values[index], right.values.remove(0));
+        child.keys.push(key);
+        child.values.push(value);
+
+        if !right.is_leaf() {
+            child.children.push(right.children.remove(0));
+        }
+    }
+
+    /// Merges the children at `index` and `index + 1` together with their separator.
+    fn merge_children(node: &mut Node<K, V>, index: usize) {
+        let right = node.children.remove(index + 1);
+        let key = node.keys.remove(index);
+        let value = node.values.remove(index);
+
+        let child = &mut node.children[index];
+        child.keys.push(key);
+        child.values.push(value);
+        child.keys.extend(right.keys);
+        child.values.extend(right.values);
+        child.children.extend(right.children);
+    }
+
+    /// Cuts the subtree before the first key >= `key`, returns the upper part. Both parts keep the height of the
+    /// original, the nodes along the cut may underflow or even be left without keys.
+    fn split_node(node: &mut Node<K, V>, key: &K) -> Node<K, V> {
+        let index = node.keys.partition_point(|node_key| node_key < key);
+
+        let mut upper = Node::new();
+        upper.keys = node.keys.split_off(index);
+        upper.values = node.values.split_off(index);
+        if !node.is_leaf() {
+            upper.children = node.children.split_off(index + 1);
+            let child = Self::split_node(&mut node.children[index], key);
+            upper.children.insert(0, child);
+        }
+
+        upper
+    }
+
+    /// Drops empty roots left by a split or merge.
+    fn fix_top(root: &mut Node<K, V>) {
+        while root.keys.is_empty() && !root.is_leaf() {
+            *root = root.children.pop().unwrap();
+        }
+    }
+
+    /// Restores the minimum fill along the rightmost path after a split. Going down, the last child gets topped up
+    /// from its left sibling, which the split left untouched, or merged with it. Either way it ends up with a key to
+    /// spare for a merge below.
+    fn fix_right_border(root: &mut Node<K, V>) {
+        Self::fix_top(root);
+
+        let mut node = &mut *root;
+        while !node.is_leaf() {
+            let index = node.children.len() - 1;
+            let child_len = node.children[index].keys.len();
+            let index = if child_len > Self::MIN_KEYS {
+                index
+            } else if node.children[index - 1].keys.len() + child_len < Self::CAPACITY {
+                Self::merge_children(node, index - 1);
+                index - 1
+            } else {
+                Self::steal_from_left(node, index, Self::MIN_KEYS + 1 - child_len);
+                index
+            };
+            node = &mut node.children[index];
+        }
+
+        Self::fix_top(root);
+    }
+
+    /// Mirror image of `fix_right_border` for the leftmost path.
+    fn fix_left_border(root: &mut Node<K, V>) {
+        Self::fix_top(root);
+
+        let mut node = &mut *root;
+        while !node.is_leaf() {
+            let child_len = node.children[0].keys.len();
+            if child_len <= Self::MIN_KEYS {
+                if node.children[1].keys.len() + child_len < Self::CAPACITY {
+                    Self::merge_children(node, 0);
+                } else {
+                    Self::steal_from_right(node, 0, Self::MIN_KEYS + 1 - child_len);
+                }
+            }
+            node = &mut node.children[0];
+        }
+
+        Self::fix_top(root);
+    }
+
+    /// Moves `count` entries from the left sibling through the separator into the child at `index`, in O(B).
+    fn steal_from_left(node: &mut Node<K, V>, index: usize, count: usize) {
+        let (left_part, right_part) = node.children.split_at_mut(index);
+        let left = &mut left_part[index - 1];
+        let child = &mut right_part[0];
+
+        // The first moved entry becomes the separator, the old separator goes down in front of the rest.
+        let at = left.keys.len() - count;
+        let mut keys = left.keys.split_off(at);
+        let mut values = left.values.split_off(at);
+        let key = mem::replace(&mut node.keys[index - 1], keys.remove(0));
+        let value = mem::replace(&mut node.values[index - 1], values.remove(0));
+        keys.push(key);
+        values.push(value);
+
+        child.keys.splice(0..0, keys);
+        child.values.splice(0..0, values);
+        if !left.is_leaf() {
+            let children = left.children.split_off(left.children.len() - count);
+            child.children.splice(0..0, children);
+        }
+    }
+
+    /// Moves `count` entries from the right sibling through the separator into the child at `index`, in O(B).
+    fn steal_from_right(node: &mut Node<K, V>, index: usize, count: usize) {
+        let (left_part, right_part) = node.children.split_at_mut(index + 1);
+        let child = &mut left_part[index];
+        let right = &mut right_part[0];
+
+        // The last moved entry becomes the separator, the old separator goes down behind the child's entries.
+        let mut keys: Vec<K> = right.keys.drain(..count).collect();
+        let mut values: Vec<V> = right.values.drain(..count).collect();
+        let key = mem::replace(&mut node.keys[index], keys.pop().unwrap());
+        let value = mem::replace(&mut node.values[index], values.pop().unwrap());
+
+        child.keys.push(key);
+        child.values.push(value);
+        child.keys.extend(keys);
+        child.values.extend(values);
+        if !right.is_leaf() {
+            child.children.extend(right.children.drain(..count));
+        }
+    }
+
+    /// Shrinks the tree when a merge emptied the root. The tree loses height only here.
+    fn after_remove(&mut self) {
+        self.len -= 1;
+
+        if self.root.keys.is_empty() {
+            if let Some(child) = self.root.children.pop() {
+                self.root = child;
+            }
+        }
+    }
+}
+
+impl<K: Ord, V, const B: usize> Default for BTreeMap<K, V, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V, const B: usize> OrderedMap<K, V> for BTreeMap<K, V, B> {
+    type Iter<'a>
+        = Iter<'a, K, V>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn range<R: RangeBounds<K>>(&self, range: R) -> Self::Iter<'_> {
+        self.range(range)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K: Ord, V, const B: usize> FromIterator<(K, V)> for BTreeMap<K, V, B> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut entries: Vec<(K, V)> = iter.into_iter().collect();
+        // Stable sort keeps the last inserted value last among equal keys, which bulk-loading then keeps.
+        entries.sort_by(|(left, _), (right, _)| left.cmp(right));
+        Self::from_sorted_iter(entries)
+    }
+}
+
+impl<K, V, const B: usize> IntoIterator for BTreeMap<K, V, B> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        fn collect<K, V>(node: Node<K, V>, entries: &mut Vec<(K, V)>) {
+            let mut children = node.children.into_iter();
+            for (key, value) in node.keys.into_iter().zip(node.values) {
+                if let Some(child) = children.next() {
+                    collect(child, entries);
+                }
+                entries.push((key, value));
+            }
+            if let Some(child) = children.next() {
+                collect(child, entries);
+            }
+        }
+
+        let mut entries = Vec::with_capacity(self.len);
+        collect(self.root, &mut entries);
+
+        IntoIter {
+            iterator: entries.into_iter(),
+        }
+    }
+}
+
+pub struct IntoIter<K, V> {
+    iterator: std::vec::IntoIter<(K, V)>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iterator.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iterator.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<(&'a Node<K, V>, usize)>,
+    end: Option<(&'a Node<K, V>, usize)>,
+}
+
+impl<K, V> Iter<'_, K, V> {
+    /// Pops nodes whose keys were all yielded, so the top of the stack points at the next entry.
+    fn skip_exhausted(&mut self) {
+        while let Some(&(node, index)) = self.stack.last() {
+            if index < node.keys.len() {
+                return;
+            }
+            self.stack.pop();
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.skip_exhausted();
+        let (node, index) = *self.stack.last()?;
+
+        if let Some((end_node, end_index)) = self.end {
+            if std::ptr::eq(node, end_node) && index == end_index {
+                self.stack.clear();
+                return None;
+            }
+        }
+
+        // Advance past this key, then descend to the leftmost entry of the subtree right of it.
+        self.stack.last_mut().unwrap().1 += 1;
+        let mut child = node.children.get(index + 1);
+        while let Some(current) = child {
+            self.stack.push((current, 0));
+            child = current.children.first();
+        }
+
+        Some((&node.keys[index], &node.values[index]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::ordered_map::ordered_map_conformance_tests;
+    use crate::random::Random;
+    use std::{collections::BTreeMap as StdBTreeMap, time::Instant};
+
+    /// Checks key order, node occupancy and that all leaves are at the same depth.
+    fn check_invariants<K: Ord, V, const B: usize>(map: &BTreeMap<K, V, B>) {
+        fn check_node<K: Ord, V, const B: usize>(
+            node: &Node<K, V>,
+            is_root: bool,
+            lower: Option<&K>,
+            upper: Option<&K>,
+            count: &mut usize,
+        ) -> usize {
+            *count += node.keys.len();
+
+            assert_eq!(node.keys.len(), node.values.len());
+            assert!(node.keys.len() < 2 * B, "Node overflow.");
+            if !is_root {
+                assert!(node.keys.len() >= B - 1, "Node underflow.");
+            }
+            assert!(
+                node.keys.windows(2).all(|pair| pair[0] < pair[1]),
+                "Keys not sorted."
+            );
+            assert!(node
+                .keys
+                .first()
+                .is_none_or(|first| lower.is_none_or(|lower| lower < first)));
+            assert!(node
+                .keys
+                .last()
+                .is_none_or(|last| upper.is_none_or(|upper| last < upper)));
+
+            if node.is_leaf() {
+                return 0;
+            }
+
+            assert_eq!(
+                node.children.len(),
+                node.keys.len() + 1,
+                "Wrong number of children."
+            );
+            let depths: Vec<usize> = node
+                .children
+                .iter()
+                .enumerate()
+                .map(|(index, child)| {
+                    let lower = if index == 0 {
+                        lower
+                    } else {
+                        Some(&node.keys[index - 1])
+                    };
+                    let upper = node.keys.get(index).or(upper);
+                    check_node::<K, V, B>(child, false, lower, upper, count)
+                })
+                .collect();
+            assert!(
+                depths.windows(2).all(|pair| pair[0] == pair[1]),
+                "Leaves at different depths."
+            );
+
+            depths[0] + 1
+        }
+
+        let mut count = 0;
+        check_node::<K, V, B>(&map.root, true, None, None, &mut count);
+        assert_eq!(count, map.len());
+    }
+
+    #[test]
+    fn gets_first_last() {
+        let mut map = BTreeMap::<i32, &str, 3>::new();
+        assert_eq!(map.first_key_value(), None);
+        assert_eq!(map.pop_first(), None);
+
+        for key in [4, 8, 1, 6] {
+            map.insert(key, "value");
+        }
+
+        assert_eq!(map.first_key_value(), Some((&1, &"value")));
+        assert_eq!(map.last_key_value(), Some((&8, &"value")));
+        assert_eq!(map.pop_first(), Some((1, "value")));
+        assert_eq!(map.pop_last(), Some((8, "value")));
+        assert_eq!(map.len(), 2);
+        check_invariants(&map);
+    }
+
+    #[test]
+    fn bulk_loads_valid_trees() {
+        for count in 0..300 {
+            let map =
+                BTreeMap::<usize, usize, 2>::from_sorted_iter((0..count).map(|key| (key, key)));
+            check_invariants(&map);
+            assert!(map.iter().map(|(key, _)| *key).eq(0..count));
+
+            let map =
+                BTreeMap::<usize, usize, 3>::from_sorted_iter((0..count).map(|key| (key, key)));
+            check_invariants(&map);
+            assert_eq!(map.len(), count);
+        }
+    }
+
+    #[test]
+    fn bulk_load_keeps_last_duplicate() {
+        let map = BTreeMap::<i32, &str>::from_sorted_iter([(1, "a"), (1, "b"), (2, "c")]);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(&"b"));
+
+        let map: BTreeMap<i32, &str> = [(2, "x"), (1, "a"), (2, "y")].into_iter().collect();
+        assert_eq!(map.get(&2), Some(&"y"));
+    }
+
+    #[test]
+    fn bulk_load_unsorted_panics() {
+        let result = std::panic::catch_unwind(|| {
+            BTreeMap::<i32, (), 2>::from_sorted_iter([(2, ()), (1, ())]);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn can_split_off() {
+        let mut map: BTreeMap<i32, i32, 2> = (0..40).map(|key| (key, key)).collect();
+        let upper = map.split_off(&25);
+
+        check_invariants(&map);
+        check_invariants(&upper);
+        assert!(map.iter().map(|(key, _)| *key).eq(0..25));
+        assert!(upper.iter().map(|(key, _)| *key).eq(25..40));
+    }
+
+    #[test]
+    fn split_off_keeps_invariants() {
+        fn check_splits<const B: usize>(random: &mut Random) {
+            for len in [0, 1, 7, 40, 300] {
+                let keys: Vec<usize> = (0..len).map(|_| random.range(0..2 * len + 1)).collect();
+                let expected: std::collections::BTreeMap<usize, usize> =
+                    keys.iter().map(|&key| (key, key)).collect();
+
+                for split in 0..=2 * len + 1 {
+                    let mut map: BTreeMap<usize, usize, B> =
+                        keys.iter().map(|&key| (key, key)).collect();
+                    let mut upper = map.split_off(&split);
+                    check_invariants(&map);
+                    check_invariants(&upper);
+                    assert!(map.iter().eq(expected.range(..split)));
+                    assert!(upper.iter().eq(expected.range(split..)));
+                    assert_eq!(map.len(), expected.range(..split).count());
+                    assert_eq!(upper.len(), expected.range(split..).count());
+
+                    map.append(&mut upper);
+                    check_invariants(&map);
+                    assert!(map.iter().eq(expected.iter()));
+                }
+            }
+        }
+
+        let mut random = Random::new(1972);
+        check_splits::<2>(&mut random);
+        check_splits::<3>(&mut random);
+        check_splits::<6>(&mut random);
+    }
+
+    #[test]
+    fn can_append() {
+        let mut map: BTreeMap<i32, &str, 2> = (0..20).map(|key| (key * 2, "left")).collect();
+        let mut other: BTreeMap<i32, &str, 2> = (0..20).map(|key| (key * 3, "right")).collect();
+        map.append(&mut other);
+
+        check_invariants(&map);
+        assert!(other.is_empty());
+        assert_eq!(map.get(&6), Some(&"right"));
+        assert_eq!(map.get(&4), Some(&"left"));
+        assert_eq!(map.len(), 20 + 20 - 7);
+    }
+
+    #[test]
+    fn keeps_node_fill_on_random_operations() {
+        let mut random = Random::new(27);
+        let mut map = BTreeMap::<usize, usize, 3>::new();
+
+        for step in 0..20_000 {
+            let key = random.range(0..500);
+            match random.range(0..3) {
+                0 | 1 => map.insert(key, step),
+                _ => map.remove(&key),
+            };
+            if step % 500 == 0 {
+                check_invariants(&map);
+            }
+        }
+
+        check_invariants(&map);
+        for key in 0..500 {
+            map.remove(&key);
+        }
+        assert!(map.root.is_leaf());
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_against_std() {
+        const COUNT: usize = 1_000_000;
+
+        let mut random = Random::new(1);
+        let keys: Vec<u64> = (0..COUNT).map(|_| random.next_u64()).collect();
+
+        fn measure(name: &str, run: impl FnOnce() -> usize) {
+            let start = Instant::now();
+            let result = run();
+            println!("{name:<32} {:>10.2?} ({result})", start.elapsed());
+        }
+
+        let mut map = BTreeMap::<u64, u64>::new();
+        let mut std_map = StdBTreeMap::new();
+        measure("insert BTreeMap<B = 6>", || {
+            keys.iter().for_each(|key| _ = map.insert(*key, *key));
+            map.len()
+        });
+        measure("insert std BTreeMap", || {
+            keys.iter().for_each(|key| _ = std_map.insert(*key, *key));
+            std_map.len()
+        });
+        measure("get BTreeMap<B = 6>", || {
+            keys.iter().filter(|key| map.contains_key(key)).count()
+        });
+        measure("get std BTreeMap", || {
+            keys.iter().filter(|key| std_map.contains_key(key)).count()
+        });
+        measure("iterate BTreeMap<B = 6>", || {
+            map.iter()
+                .map(|(key, _)| *key as usize)
+                .fold(0, usize::wrapping_add)
+        });
+        measure("iterate std BTreeMap", || {
+            std_map
+                .keys()
+                .map(|key| *key as usize)
+                .fold(0, usize::wrapping_add)
+        });
+        measure("remove BTreeMap<B = 6>", || {
+            keys.iter().filter(|key| map.remove(key).is_some()).count()
+        });
+        measure("remove std BTreeMap", || {
+            keys.iter()
+                .filter(|key| std_map.remove(key).is_some())
+                .count()
+        });
+
+        let mut sorted = keys.clone();
+        sorted.sort();
+        measure("bulk load BTreeMap<B = 6>", || {
+            BTreeMap::<u64, u64>::from_sorted_iter(sorted.iter().map(|key| (*key, *key))).len()
+        });
+        measure("bulk load std BTreeMap", || {
+            sorted
+                .iter()
+                .map(|key| (*key, *key))
+                .collect::<StdBTreeMap<_, _>>()
+                .len()
+        });
+    }
+
+    ordered_map_conformance_tests!(conformance_order_2, <K, V> BTreeMap<K, V, 2>);
+    ordered_map_conformance_tests!(conformance_order_6, <K, V> BTreeMap<K, V, 6>);
+}
@@ -0,0 +1,769 @@
+use super::dynamic_array::DynamicArray;
+use std::{
+    fmt::{self, Debug},
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign},
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Word index and mask of a bit.
+fn position(index: usize) -> (usize, u64) {
+    (index / WORD_BITS, 1 << (index % WORD_BITS))
+}
+
+/// Growable set of small non-negative integers, one bit per possible member.
+pub struct BitSet {
+    words: DynamicArray<u64>,
+}
+
+impl BitSet {
+    pub fn new() -> Self {
+        Self {
+            words: DynamicArray::new(),
+        }
+    }
+
+    pub fn with_capacity(bits: usize) -> Self {
+        let mut set = Self::new();
+        set.reserve(bits);
+        set
+    }
+
+    /// Number of bits stored without growing.
+    pub fn capacity(&self) -> usize {
+        self.words.len() * WORD_BITS
+    }
+
+    fn reserve(&mut self, bits: usize) {
+        while self.capacity() < bits {
+            self.words.push(0);
+        }
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        let (word, mask) = position(index);
+        self.words
+            .as_slice()
+            .get(word)
+            .is_some_and(|word| word & mask != 0)
+    }
+
+    /// Returns whether the bit was unset before.
+    pub fn set(&mut self, index: usize) -> bool {
+        self.reserve(index + 1);
+        let (word, mask) = position(index);
+        let word = &mut self.words[word];
+        let was_unset = *word & mask == 0;
+        *word |= mask;
+        was_unset
+    }
+
+    /// Returns whether the bit was set before.
+    pub fn clear(&mut self, index: usize) -> bool {
+        let (word, mask) = position(index);
+        match self.words.as_mut_slice().get_mut(word) {
+            Some(word) => {
+                let was_set = *word & mask != 0;
+                *word &= !mask;
+                was_set
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the new state of the bit.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        self.reserve(index + 1);
+        let (word, mask) = position(index);
+        self.words[word] ^= mask;
+        self.words[word] & mask != 0
+    }
+
+    /// Unsets every bit, keeping the capacity.
+    pub fn clear_all(&mut self) {
+        self.words.as_mut_slice().fill(0);
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .as_slice()
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.as_slice().iter().all(|word| *word == 0)
+    }
+
+    /// Set bits in increasing order.
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(self.words.as_slice())
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        self.reserve(other.capacity());
+        self.zip_words(other, |word, other| *word |= other);
+    }
+
+    pub fn intersect_with(&mut self, other: &Self) {
+        let common = other.words.len().min(self.words.len());
+        self.zip_words(other, |word, other| *word &= other);
+        // Words past the end of `other` intersect with zeros.
+        self.words.as_mut_slice()[common..].fill(0);
+    }
+
+    pub fn difference_with(&mut self, other: &Self) {
+        self.zip_words(other, |word, other| *word &= !other);
+    }
+
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.reserve(other.capacity());
+        self.zip_words(other, |word, other| *word ^= other);
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let other = other.words.as_slice();
+        self.words
+            .as_slice()
+            .iter()
+            .enumerate()
+            .all(|(index, word)| word & !other.get(index).copied().unwrap_or(0) == 0)
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.words
+            .as_slice()
+            .iter()
+            .zip(other.words.as_slice())
+            .all(|(word, other)| word & other == 0)
+    }
+
+    /// Applies the operation to the common prefix of words.
+    fn zip_words(&mut self, other: &Self, operation: impl Fn(&mut u64, u64)) {
+        for (word, other) in self
+            .words
+            .as_mut_slice()
+            .iter_mut()
+            .zip(other.words.as_slice())
+        {
+            operation(word, *other);
+        }
+    }
+}
+
+impl Default for BitSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for BitSet {
+    fn clone(&self) -> Self {
+        let mut words = DynamicArray::new();
+        for word in self.words.as_slice() {
+            words.push(*word);
+        }
+        Self { words }
+    }
+}
+
+/// Sets are equal when they have the same members, whatever their capacities.
+impl PartialEq for BitSet {
+    fn eq(&self, other: &Self) -> bool {
+        let (words, other) = (self.words.as_slice(), other.words.as_slice());
+        let common = words.len().min(other.len());
+        words[..common] == other[..common]
+            && words[common..].iter().all(|word| *word == 0)
+            && other[common..].iter().all(|word| *word == 0)
+    }
+}
+
+impl Eq for BitSet {}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<usize> for BitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            self.set(index);
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a BitSet {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+macro_rules! impl_bit_set_operation {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $with:ident) => {
+        impl $assign_trait<&BitSet> for BitSet {
+            fn $assign_method(&mut self, other: &BitSet) {
+                self.$with(other);
+            }
+        }
+
+        impl $trait for &BitSet {
+            type Output = BitSet;
+
+            fn $method(self, other: &BitSet) -> BitSet {
+                let mut result = self.clone();
+                result.$with(other);
+                result
+            }
+        }
+    };
+}
+
+impl_bit_set_operation!(BitOr, bitor, BitOrAssign, bitor_assign, union_with);
+impl_bit_set_operation!(BitAnd, bitand, BitAndAssign, bitand_assign, intersect_with);
+impl_bit_set_operation!(
+    BitXor,
+    bitxor,
+    BitXorAssign,
+    bitxor_assign,
+    symmetric_difference_with
+);
+impl_bit_set_operation!(Sub, sub, SubAssign, sub_assign, difference_with);
+
+/// Fixed array of `64 * N` bits living inline, `N` is the number of words since stable Rust can't size an array by
+/// `N.div_ceil(64)`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitArray<const N: usize> {
+    words: [u64; N],
+}
+
+impl<const N: usize> BitArray<N> {
+    pub const BITS: usize = N * WORD_BITS;
+
+    pub const fn new() -> Self {
+        Self { words: [0; N] }
+    }
+
+    fn position(index: usize) -> (usize, u64) {
+        assert!(index < Self::BITS, "Out of bounds index.");
+        position(index)
+    }
+
+    pub fn contains(&self, index: usize) -> bool {
+        let (word, mask) = Self::position(index);
+        self.words[word] & mask != 0
+    }
+
+    /// Returns whether the bit was unset before.
+    pub fn set(&mut self, index: usize) -> bool {
+        let (word, mask) = Self::position(index);
+        let was_unset = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        was_unset
+    }
+
+    /// Returns whether the bit was set before.
+    pub fn clear(&mut self, index: usize) -> bool {
+        let (word, mask) = Self::position(index);
+        let was_set = self.words[word] & mask != 0;
+        self.words[word] &= !mask;
+        was_set
+    }
+
+    /// Returns the new state of the bit.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        let (word, mask) = Self::position(index);
+        self.words[word] ^= mask;
+        self.words[word] & mask != 0
+    }
+
+    pub fn clear_all(&mut self) {
+        self.words = [0; N];
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Set bits in increasing order.
+    pub fn iter(&self) -> Ones<'_> {
+        Ones::new(&self.words)
+    }
+}
+
+impl<const N: usize> Default for BitArray<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Debug for BitArray<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<const N: usize> FromIterator<usize> for BitArray<N> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut array = Self::new();
+        for index in iter {
+            array.set(index);
+        }
+        array
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a BitArray<N> {
+    type Item = usize;
+    type IntoIter = Ones<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<const N: usize> Not for BitArray<N> {
+    type Output = Self;
+
+    fn not(mut self) -> Self {
+        for word in &mut self.words {
+            *word = !*word;
+        }
+        self
+    }
+}
+
+macro_rules! impl_bit_array_operation {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $operator:tt) => {
+        impl<const N: usize> $assign_trait for BitArray<N> {
+            fn $assign_method(&mut self, other: Self) {
+                for (word, other) in self.words.iter_mut().zip(other.words) {
+                    *word $operator other;
+                }
+            }
+        }
+
+        impl<const N: usize> $trait for BitArray<N> {
+            type Output = Self;
+
+            fn $method(mut self, other: Self) -> Self {
+                self.$assign_method(other);
+                self
+            }
+        }
+    };
+}
+
+impl_bit_array_operation!(BitOr, bitor, BitOrAssign, bitor_assign, |=);
+impl_bit_array_operation!(BitAnd, bitand, BitAndAssign, bitand_assign, &=);
+impl_bit_array_operation!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^=);
+
+/// Iterator over the set bits of a word slice.
+pub struct Ones<'a> {
+    words: &'a [u64],
+    index: usize,
+    current: u64,
+}
+
+impl<'a> Ones<'a> {
+    fn new(words: &'a [u64]) -> Self {
+        Self {
+            words,
+            index: 0,
+            current: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Iterator for Ones<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.current == 0 {
+            self.index += 1;
+            self.current = *self.words.get(self.index)?;
+        }
+
+        let bit = self.current.trailing_zeros() as usize;
+        // Clears the lowest set bit.
+        self.current &= self.current - 1;
+        Some(self.index * WORD_BITS + bit)
+    }
+}
+
+const BLOCK_WORDS: usize = 8;
+const BLOCK_BITS: usize = BLOCK_WORDS * WORD_BITS;
+
+/// Immutable bit vector answering rank (ones before a position) in O(1) and select (position of the k-th one) in
+/// O(log n).
+///
+/// Besides the bits it stores the number of ones before every block of 512 bits, a 1/8 overhead. Rank adds the
+/// popcounts of at most 8 words to a block count, select binary searches the block counts and scans one block.
+pub struct RankSelect {
+    words: Vec<u64>,
+    len: usize,
+    // Ones before every block, plus the total when the words end on a block boundary.
+    blocks: Vec<usize>,
+    ones: usize,
+}
+
+impl RankSelect {
+    fn from_words(words: Vec<u64>, len: usize) -> Self {
+        let mut blocks = Vec::with_capacity(words.len() / BLOCK_WORDS + 1);
+        let mut ones = 0;
+        for (index, word) in words.iter().enumerate() {
+            if index.is_multiple_of(BLOCK_WORDS) {
+                blocks.push(ones);
+            }
+            ones += word.count_ones() as usize;
+        }
+        if words.len().is_multiple_of(BLOCK_WORDS) {
+            blocks.push(ones);
+        }
+
+        Self {
+            words,
+            len,
+            blocks,
+            ones,
+        }
+    }
+
+    /// Number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.ones
+    }
+
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.ones
+    }
+
+    pub fn get(&self, index: usize) -> bool {
+        assert!(index < self.len, "Out of bounds index.");
+        let (word, mask) = position(index);
+        self.words[word] & mask != 0
+    }
+
+    /// Number of ones before the index.
+    pub fn rank1(&self, index: usize) -> usize {
+        assert!(index <= self.len, "Out of bounds index.");
+        let (word, bit) = (index / WORD_BITS, index % WORD_BITS);
+        let block = word / BLOCK_WORDS;
+
+        let mut rank = self.blocks[block];
+        for word in &self.words[block * BLOCK_WORDS..word] {
+            rank += word.count_ones() as usize;
+        }
+        if bit != 0 {
+            rank += (self.words[word] & ((1 << bit) - 1)).count_ones() as usize;
+        }
+        rank
+    }
+
+    /// Number of zeros before the index.
+    pub fn rank0(&self, index: usize) -> usize {
+        index - self.rank1(index)
+    }
+
+    /// Position of the k-th one, counting from 0.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        if k >= self.ones {
+            return None;
+        }
+        Some(self.select(k, |block| self.blocks[block], |word| word))
+    }
+
+    /// Position of the k-th zero, counting from 0.
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        if k >= self.count_zeros() {
+            return None;
+        }
+        Some(self.select(
+            k,
+            |block| block * BLOCK_BITS - self.blocks[block],
+            |word| !word,
+        ))
+    }
+
+    /// Finds the k-th set bit of `bits(word)`, `before(block)` counting them before a block.
+    fn select(
+        &self,
+        k: usize,
+        before: impl Fn(usize) -> usize,
+        bits: impl Fn(u64) -> u64,
+    ) -> usize {
+        // Last block starting with at most `k` bits before it.
+        let (mut low, mut high) = (0, self.blocks.len());
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if before(middle) <= k {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        let mut remaining = k - before(low);
+        for (index, word) in self.words.iter().enumerate().skip(low * BLOCK_WORDS) {
+            let mut word = bits(*word);
+            let count = word.count_ones() as usize;
+            if remaining < count {
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return index * WORD_BITS + word.trailing_zeros() as usize;
+            }
+            remaining -= count;
+        }
+        unreachable!("Select count checked against the totals.")
+    }
+}
+
+impl From<&BitSet> for RankSelect {
+    fn from(set: &BitSet) -> Self {
+        Self::from_words(set.words.as_slice().to_vec(), set.capacity())
+    }
+}
+
+impl<const N: usize> From<&BitArray<N>> for RankSelect {
+    fn from(array: &BitArray<N>) -> Self {
+        Self::from_words(array.words.to_vec(), BitArray::<N>::BITS)
+    }
+}
+
+impl FromIterator<bool> for RankSelect {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut words = Vec::new();
+        let mut len: usize = 0;
+        for bit in iter {
+            if len.is_multiple_of(WORD_BITS) {
+                words.push(0);
+            }
+            if bit {
+                *words.last_mut().unwrap() |= 1 << (len % WORD_BITS);
+            }
+            len += 1;
+        }
+        Self::from_words(words, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn can_set_and_clear() {
+        let mut set = BitSet::new();
+        assert!(set.is_empty());
+        assert!(set.set(3));
+        assert!(!set.set(3));
+        assert!(set.set(130));
+        assert!(set.contains(3));
+        assert!(!set.contains(4));
+        assert!(!set.contains(1000));
+        assert_eq!(set.count_ones(), 2);
+        assert_eq!(set.capacity(), 192);
+
+        assert!(!set.toggle(3));
+        assert!(set.toggle(64));
+        assert!(set.clear(130));
+        assert!(!set.clear(130));
+        assert!(!set.clear(1000));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![64]);
+
+        set.clear_all();
+        assert!(set.is_empty());
+        assert_eq!(set.capacity(), 192);
+    }
+
+    #[test]
+    fn matches_btree_set() {
+        let mut random = Random::new(39);
+        let mut set = BitSet::new();
+        let mut expected = BTreeSet::new();
+
+        for _ in 0..10_000 {
+            let index = random.range(0..1000);
+            match random.range(0..3) {
+                0 => assert_eq!(set.set(index), expected.insert(index)),
+                1 => assert_eq!(set.clear(index), expected.remove(&index)),
+                _ => {
+                    let now_set = expected.insert(index) || !expected.remove(&index);
+                    assert_eq!(set.toggle(index), now_set);
+                }
+            }
+            assert_eq!(set.contains(index), expected.contains(&index));
+        }
+
+        assert_eq!(set.count_ones(), expected.len());
+        assert!(set.iter().eq(expected.iter().copied()));
+    }
+
+    #[test]
+    fn can_combine_sets() {
+        let mut random = Random::new(39);
+
+        for _ in 0..100 {
+            let (len_a, len_b) = (random.range(0..300), random.range(0..300));
+            let a: BTreeSet<usize> = (0..50).map(|_| random.range(0..len_a + 1)).collect();
+            let b: BTreeSet<usize> = (0..50).map(|_| random.range(0..len_b + 1)).collect();
+            let (set_a, set_b): (BitSet, BitSet) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+
+            let collect = |set: BitSet| set.iter().collect::<Vec<_>>();
+            assert_eq!(
+                collect(&set_a | &set_b),
+                a.union(&b).copied().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                collect(&set_a & &set_b),
+                a.intersection(&b).copied().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                collect(&set_a - &set_b),
+                a.difference(&b).copied().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                collect(&set_a ^ &set_b),
+                a.symmetric_difference(&b).copied().collect::<Vec<_>>()
+            );
+            assert_eq!(set_a.is_subset(&set_b), a.is_subset(&b));
+            assert_eq!(set_a.is_superset(&set_b), a.is_superset(&b));
+            assert_eq!(set_a.is_disjoint(&set_b), a.is_disjoint(&b));
+        }
+    }
+
+    #[test]
+    fn ignores_capacity_in_equality() {
+        let mut a = BitSet::with_capacity(1000);
+        let mut b = BitSet::new();
+        assert_eq!(a, b);
+
+        a.set(5);
+        b.set(5);
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), b);
+
+        b.set(6);
+        assert_ne!(a, b);
+        assert_eq!(format!("{:?}", b), "{5, 6}");
+    }
+
+    #[test]
+    fn can_use_bit_array() {
+        let mut array = BitArray::<2>::new();
+        assert_eq!(BitArray::<2>::BITS, 128);
+        assert!(array.set(0));
+        assert!(array.set(127));
+        assert!(!array.toggle(0));
+        assert!(array.toggle(64));
+        assert!(array.clear(127));
+        assert_eq!(array.iter().collect::<Vec<_>>(), vec![64]);
+
+        let a: BitArray<2> = [1, 2, 100].into_iter().collect();
+        let b: BitArray<2> = [2, 3, 100].into_iter().collect();
+        assert_eq!((a | b).iter().collect::<Vec<_>>(), vec![1, 2, 3, 100]);
+        assert_eq!((a & b).iter().collect::<Vec<_>>(), vec![2, 100]);
+        assert_eq!((a ^ b).iter().collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!((!a).count_ones(), 125);
+        assert_eq!(format!("{:?}", a), "{1, 2, 100}");
+
+        let mut c = a;
+        c.clear_all();
+        assert!(c.is_empty());
+        assert_eq!(c, BitArray::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds index.")]
+    fn bit_array_checks_bounds() {
+        BitArray::<1>::new().set(64);
+    }
+
+    #[test]
+    fn rank_and_select_match_scans() {
+        let mut random = Random::new(39);
+
+        for (len, density) in [
+            (0, 0.5),
+            (1, 1.0),
+            (64, 0.5),
+            (513, 0.01),
+            (2000, 0.5),
+            (5000, 0.99),
+        ] {
+            let bits: Vec<bool> = (0..len).map(|_| random.next_bool(density)).collect();
+            let index: RankSelect = bits.iter().copied().collect();
+            assert_eq!(index.len(), len);
+
+            let mut ones = Vec::new();
+            let mut zeros = Vec::new();
+            for (position, bit) in bits.iter().enumerate() {
+                assert_eq!(index.get(position), *bit);
+                assert_eq!(index.rank1(position), ones.len());
+                assert_eq!(index.rank0(position), zeros.len());
+                if *bit { &mut ones } else { &mut zeros }.push(position);
+            }
+            assert_eq!(index.rank1(len), ones.len());
+
+            for (k, position) in ones.iter().enumerate() {
+                assert_eq!(index.select1(k), Some(*position));
+            }
+            for (k, position) in zeros.iter().enumerate() {
+                assert_eq!(index.select0(k), Some(*position));
+            }
+            assert_eq!(index.select1(ones.len()), None);
+            assert_eq!(index.select0(zeros.len()), None);
+        }
+    }
+
+    #[test]
+    fn builds_rank_select_from_sets() {
+        let set: BitSet = [0, 5, 700].into_iter().collect();
+        let index = RankSelect::from(&set);
+        assert_eq!(index.len(), set.capacity());
+        assert_eq!(index.rank1(701), 3);
+        assert_eq!(index.select1(2), Some(700));
+        assert_eq!(index.select0(1), Some(2));
+
+        let array: BitArray<1> = [63].into_iter().collect();
+        let index = RankSelect::from(&array);
+        assert_eq!(index.select1(0), Some(63));
+        assert_eq!(index.rank0(64), 63);
+    }
+}
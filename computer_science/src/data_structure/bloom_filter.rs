@@ -0,0 +1,456 @@
+use super::bitset::BitSet;
+use std::{
+    collections::hash_map::DefaultHasher,
+    f64::consts::LN_2,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+/// Bit positions probed for an item, shared by both filters.
+///
+/// Uses double hashing (`h1 + i * h2`) so one 64-bit hash gives all `hashes` positions. `DefaultHasher::new` has
+/// fixed keys, so positions stay stable between runs and serialized filters can be read back.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct Probes {
+    slots: usize,
+    hashes: u32,
+}
+
+impl Probes {
+    /// Optimal sizes for the expected items and false positive rate: `m = -n ln p / ln² 2` slots and
+    /// `k = m / n ln 2` hashes.
+    fn optimal(expected_items: usize, false_positive_rate: f64) -> Self {
+        assert!(
+            false_positive_rate > 0.0 && false_positive_rate < 1.0,
+            "False positive rate must be in (0, 1)."
+        );
+        let items = expected_items.max(1) as f64;
+        let slots = (-items * false_positive_rate.ln() / (LN_2 * LN_2)).ceil() as usize;
+        let hashes = (slots as f64 / items * LN_2).round().max(1.0) as u32;
+
+        Self::new(slots, hashes)
+    }
+
+    fn new(slots: usize, hashes: u32) -> Self {
+        assert!(slots > 0 && hashes > 0, "Filter needs slots and hashes.");
+        Self { slots, hashes }
+    }
+
+    fn positions<T: Hash + ?Sized>(&self, item: &T) -> impl Iterator<Item = usize> {
+        let mut hasher = DefaultHasher::new();
+        item.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        // Second hash from a splitmix64 finalizer, odd so it never degenerates to a single position.
+        let mut second = hash ^ (hash >> 30);
+        second = second.wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        second ^= second >> 27;
+        second = second.wrapping_mul(0x94D0_49BB_1331_11EB);
+        second ^= second >> 31;
+        second |= 1;
+
+        let slots = self.slots as u64;
+        (0..self.hashes as u64)
+            .map(move |i| (hash.wrapping_add(i.wrapping_mul(second)) % slots) as usize)
+    }
+
+    /// Expected false positive rate after `items` insertions: `(1 - e^(-kn/m))^k`.
+    fn false_positive_rate(&self, items: usize) -> f64 {
+        let hashes = self.hashes as f64;
+        (1.0 - (-hashes * items as f64 / self.slots as f64).exp()).powf(hashes)
+    }
+
+    /// Little endian header of serialized filters: slots, hashes and insertions.
+    fn write_header(&self, insertions: usize, bytes: &mut Vec<u8>) {
+        bytes.extend_from_slice(&(self.slots as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hashes.to_le_bytes());
+        bytes.extend_from_slice(&(insertions as u64).to_le_bytes());
+    }
+
+    fn read_header(bytes: &[u8]) -> Option<(Self, usize, &[u8])> {
+        let (slots, bytes) = bytes.split_first_chunk::<8>()?;
+        let (hashes, bytes) = bytes.split_first_chunk::<4>()?;
+        let (insertions, bytes) = bytes.split_first_chunk::<8>()?;
+
+        let slots = usize::try_from(u64::from_le_bytes(*slots)).ok()?;
+        let hashes = u32::from_le_bytes(*hashes);
+        if slots == 0 || hashes == 0 {
+            return None;
+        }
+        let insertions = usize::try_from(u64::from_le_bytes(*insertions)).ok()?;
+
+        Some((Self { slots, hashes }, insertions, bytes))
+    }
+}
+
+/// Probabilistic set: `contains` never misses an inserted item but may report items that were never inserted.
+pub struct BloomFilter<T: ?Sized> {
+    probes: Probes,
+    bits: BitSet,
+    insertions: usize,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T: Hash + ?Sized> BloomFilter<T> {
+    /// Sized so that after `expected_items` insertions the false positive rate is about `false_positive_rate`.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::from_probes(Probes::optimal(expected_items, false_positive_rate))
+    }
+
+    pub fn with_size(bits: usize, hashes: u32) -> Self {
+        Self::from_probes(Probes::new(bits, hashes))
+    }
+
+    fn from_probes(probes: Probes) -> Self {
+        Self {
+            probes,
+            bits: BitSet::with_capacity(probes.slots),
+            insertions: 0,
+            item: PhantomData,
+        }
+    }
+
+    pub fn bit_count(&self) -> usize {
+        self.probes.slots
+    }
+
+    pub fn hash_count(&self) -> u32 {
+        self.probes.hashes
+    }
+
+    /// Number of `insert` calls, duplicates included.
+    pub fn insertions(&self) -> usize {
+        self.insertions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.insertions == 0
+    }
+
+    pub fn insert(&mut self, item: &T) {
+        for position in self.probes.positions(item) {
+            self.bits.set(position);
+        }
+        self.insertions += 1;
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.probes
+            .positions(item)
+            .all(|position| self.bits.contains(position))
+    }
+
+    /// Expected false positive rate given the insertions so far.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.probes.false_positive_rate(self.insertions)
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.clear_all();
+        self.insertions = 0;
+    }
+
+    /// Adds every item of a filter built with the same size.
+    pub fn union_with(&mut self, other: &Self) {
+        assert!(self.probes == other.probes, "Filters have different sizes.");
+        self.bits.union_with(&other.bits);
+        self.insertions += other.insertions;
+    }
+
+    /// Header followed by the bits packed eight per byte.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.probes.write_header(self.insertions, &mut bytes);
+
+        let header = bytes.len();
+        bytes.resize(header + self.probes.slots.div_ceil(8), 0);
+        for position in &self.bits {
+            bytes[header + position / 8] |= 1 << (position % 8);
+        }
+        bytes
+    }
+
+    /// Reads back `to_bytes` output, `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (probes, insertions, bits) = Probes::read_header(bytes)?;
+        if bits.len() != probes.slots.div_ceil(8) {
+            return None;
+        }
+
+        let mut filter = Self::from_probes(probes);
+        filter.insertions = insertions;
+        for (index, byte) in bits.iter().enumerate() {
+            for bit in 0..8 {
+                if byte & (1 << bit) != 0 {
+                    let position = index * 8 + bit;
+                    if position >= probes.slots {
+                        return None;
+                    }
+                    filter.bits.set(position);
+                }
+            }
+        }
+        Some(filter)
+    }
+}
+
+/// Bloom filter with a small counter per slot instead of a bit, which makes removal possible.
+///
+/// Counters saturate at 255 and then stay there: decrementing them could drop a still present item.
+pub struct CountingBloomFilter<T: ?Sized> {
+    probes: Probes,
+    counters: Vec<u8>,
+    insertions: usize,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T: Hash + ?Sized> CountingBloomFilter<T> {
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::from_probes(Probes::optimal(expected_items, false_positive_rate))
+    }
+
+    pub fn with_size(counters: usize, hashes: u32) -> Self {
+        Self::from_probes(Probes::new(counters, hashes))
+    }
+
+    fn from_probes(probes: Probes) -> Self {
+        Self {
+            probes,
+            counters: vec![0; probes.slots],
+            insertions: 0,
+            item: PhantomData,
+        }
+    }
+
+    pub fn counter_count(&self) -> usize {
+        self.probes.slots
+    }
+
+    pub fn hash_count(&self) -> u32 {
+        self.probes.hashes
+    }
+
+    /// Insertions minus successful removals.
+    pub fn len(&self) -> usize {
+        self.insertions
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.insertions == 0
+    }
+
+    pub fn insert(&mut self, item: &T) {
+        for position in self.probes.positions(item) {
+            let counter = &mut self.counters[position];
+            *counter = counter.saturating_add(1);
+        }
+        self.insertions += 1;
+    }
+
+    pub fn contains(&self, item: &T) -> bool {
+        self.probes
+            .positions(item)
+            .all(|position| self.counters[position] > 0)
+    }
+
+    /// Removes one insertion of the item, returns false if it definitely wasn't present.
+    ///
+    /// Only remove items that were inserted: removing a false positive decrements counters of other items.
+    pub fn remove(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+
+        for position in self.probes.positions(item) {
+            let counter = &mut self.counters[position];
+            if *counter != u8::MAX {
+                *counter -= 1;
+            }
+        }
+        self.insertions = self.insertions.saturating_sub(1);
+        true
+    }
+
+    /// Expected false positive rate given the items present.
+    pub fn false_positive_rate(&self) -> f64 {
+        self.probes.false_positive_rate(self.insertions)
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+        self.insertions = 0;
+    }
+
+    /// Header followed by one byte per counter.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(20 + self.counters.len());
+        self.probes.write_header(self.insertions, &mut bytes);
+        bytes.extend_from_slice(&self.counters);
+        bytes
+    }
+
+    /// Reads back `to_bytes` output, `None` if the bytes are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let (probes, insertions, counters) = Probes::read_header(bytes)?;
+        if counters.len() != probes.slots {
+            return None;
+        }
+
+        Some(Self {
+            probes,
+            counters: counters.to_vec(),
+            insertions,
+            item: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_from_rate() {
+        let filter = BloomFilter::<u32>::new(1000, 0.01);
+        // About 9.6 bits per item and 7 hashes for 1%.
+        assert_eq!(filter.bit_count(), 9586);
+        assert_eq!(filter.hash_count(), 7);
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn never_misses_inserted_items() {
+        let mut filter = BloomFilter::new(1000, 0.01);
+        for item in 0..1000u32 {
+            filter.insert(&item);
+        }
+        assert!((0..1000u32).all(|item| filter.contains(&item)));
+        assert_eq!(filter.insertions(), 1000);
+
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.contains(&1));
+    }
+
+    #[test]
+    fn keeps_false_positive_rate() {
+        for rate in [0.1, 0.01, 0.001] {
+            let mut filter = BloomFilter::new(10_000, rate);
+            for item in 0..10_000u64 {
+                filter.insert(&item);
+            }
+            assert!((filter.false_positive_rate() - rate).abs() < rate * 0.1);
+
+            let trials = 200_000;
+            let false_positives = (10_000..10_000 + trials)
+                .filter(|item| filter.contains(item))
+                .count();
+            let measured = false_positives as f64 / trials as f64;
+            assert!(
+                measured < rate * 1.25 && measured > rate * 0.75,
+                "Measured {} for expected {}.",
+                measured,
+                rate
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_unsized_items() {
+        let mut filter = BloomFilter::<str>::new(10, 0.01);
+        filter.insert("apple");
+        assert!(filter.contains("apple"));
+        assert!(!filter.contains("pear"));
+    }
+
+    #[test]
+    fn can_merge() {
+        let (mut a, mut b) = (BloomFilter::new(100, 0.01), BloomFilter::new(100, 0.01));
+        a.insert(&1);
+        b.insert(&2);
+        a.union_with(&b);
+        assert!(a.contains(&1) && a.contains(&2));
+        assert_eq!(a.insertions(), 2);
+    }
+
+    #[test]
+    fn round_trips_bytes() {
+        let mut filter = BloomFilter::new(500, 0.05);
+        for item in 0..500 {
+            filter.insert(&(item * 3));
+        }
+
+        let bytes = filter.to_bytes();
+        let read = BloomFilter::<i32>::from_bytes(&bytes).unwrap();
+        assert_eq!(read.bit_count(), filter.bit_count());
+        assert_eq!(read.hash_count(), filter.hash_count());
+        assert_eq!(read.insertions(), 500);
+        assert!((0..2000).all(|item| read.contains(&item) == filter.contains(&item)));
+        assert_eq!(read.to_bytes(), bytes);
+
+        assert!(BloomFilter::<i32>::from_bytes(&bytes[..bytes.len() - 1]).is_none());
+        assert!(BloomFilter::<i32>::from_bytes(&bytes[..10]).is_none());
+    }
+
+    #[test]
+    fn counting_filter_can_remove() {
+        let mut filter = CountingBloomFilter::new(1000, 0.01);
+        for item in 0..1000 {
+            filter.insert(&item);
+        }
+        for item in (0..1000).step_by(2) {
+            assert!(filter.remove(&item));
+        }
+        assert_eq!(filter.len(), 500);
+        assert!((1..1000).step_by(2).all(|item| filter.contains(&item)));
+
+        let remaining = (0..1000)
+            .step_by(2)
+            .filter(|item| filter.contains(item))
+            .count();
+        assert!(
+            remaining < 50,
+            "{} removed items still reported.",
+            remaining
+        );
+        assert!(!filter.remove(&5000) || filter.contains(&5000));
+    }
+
+    #[test]
+    fn counting_filter_handles_duplicates() {
+        let mut filter = CountingBloomFilter::with_size(64, 3);
+        filter.insert("a");
+        filter.insert("a");
+        assert!(filter.remove("a"));
+        assert!(filter.contains("a"));
+        assert!(filter.remove("a"));
+        assert!(!filter.contains("a"));
+        assert!(filter.is_empty());
+    }
+
+    #[test]
+    fn counting_filter_saturates() {
+        let mut filter = CountingBloomFilter::with_size(8, 1);
+        for _ in 0..300 {
+            filter.insert(&7);
+        }
+        for _ in 0..300 {
+            assert!(filter.remove(&7));
+        }
+        // The stuck counter keeps reporting the item rather than risking false negatives.
+        assert!(filter.contains(&7));
+    }
+
+    #[test]
+    fn counting_filter_round_trips_bytes() {
+        let mut filter = CountingBloomFilter::new(100, 0.01);
+        for item in ["x", "y", "z"] {
+            filter.insert(item);
+        }
+
+        let read = CountingBloomFilter::<str>::from_bytes(&filter.to_bytes()).unwrap();
+        assert_eq!(read.len(), 3);
+        assert!(read.contains("y"));
+        assert!(CountingBloomFilter::<str>::from_bytes(&[0; 20]).is_none());
+    }
+}
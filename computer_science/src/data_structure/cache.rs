@@ -0,0 +1,604 @@
+use super::{
+    hash_map::OpenHashMap,
+    linked_list::{LinkedList, NodeHandle},
+};
+use std::hash::Hash;
+
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> usize>;
+type EvictionCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+struct Entry<K, V> {
+    key: K,
+    value: V,
+    weight: usize,
+}
+
+/// Capacity bookkeeping shared by the caches: entries weigh 1 each unless a weigher is given.
+struct Capacity<K, V> {
+    limit: usize,
+    used: usize,
+    weigher: Option<Weigher<K, V>>,
+    on_evict: Option<EvictionCallback<K, V>>,
+}
+
+impl<K, V> Capacity<K, V> {
+    fn new(limit: usize, weigher: Option<Weigher<K, V>>) -> Self {
+        Self {
+            limit,
+            used: 0,
+            weigher,
+            on_evict: None,
+        }
+    }
+
+    fn weigh(&self, key: &K, value: &V) -> usize {
+        match &self.weigher {
+            Some(weigher) => weigher(key, value),
+            None => 1,
+        }
+    }
+
+    fn evict(&mut self, entry: Entry<K, V>) {
+        self.used -= entry.weight;
+        self.discard(entry.key, entry.value);
+    }
+
+    /// Hands an entry that doesn't fit (anymore) to the eviction callback.
+    fn discard(&mut self, key: K, value: V) {
+        if let Some(on_evict) = &mut self.on_evict {
+            on_evict(key, value);
+        }
+    }
+}
+
+/// Least recently used cache: once full, makes room by evicting the entry that wasn't accessed for the longest time.
+///
+/// The `LinkedList` keeps entries ordered by recency and the hash map points at their nodes, so `get`, `put` and
+/// eviction are all O(1).
+pub struct LruCache<K, V> {
+    map: OpenHashMap<K, NodeHandle<Entry<K, V>>>,
+    // Most recently used at the front.
+    list: LinkedList<Entry<K, V>>,
+    capacity: Capacity<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V> LruCache<K, V> {
+    /// Holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            map: OpenHashMap::new(),
+            list: LinkedList::new(),
+            capacity: Capacity::new(capacity, None),
+        }
+    }
+
+    /// Holds entries up to a total weight of `capacity`, each weighing `weigher(key, value)`.
+    pub fn with_weigher(capacity: usize, weigher: impl Fn(&K, &V) -> usize + 'static) -> Self {
+        Self {
+            map: OpenHashMap::new(),
+            list: LinkedList::new(),
+            capacity: Capacity::new(capacity, Some(Box::new(weigher))),
+        }
+    }
+
+    /// Calls `on_evict` with every entry evicted to make room, including entries too heavy to be cached at all.
+    /// Removed or replaced entries are not reported.
+    pub fn with_eviction_callback(mut self, on_evict: impl FnMut(K, V) + 'static) -> Self {
+        self.capacity.on_evict = Some(Box::new(on_evict));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity.limit
+    }
+
+    /// Total weight of the cached entries (their count without a weigher).
+    pub fn weight(&self) -> usize {
+        self.capacity.used
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Returns the value and marks it as most recently used.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let handle = *self.map.get(key)?;
+        self.list.move_to_front(handle);
+        Some(&mut self.list.get_mut(handle).value)
+    }
+
+    /// Returns the value without touching its recency.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let handle = *self.map.get(key)?;
+        Some(&self.list.get(handle).value)
+    }
+
+    /// Inserts the entry as most recently used, evicting least recently used ones until it fits. Returns the previous
+    /// value of the key.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let previous = self.remove(&key);
+
+        let weight = self.capacity.weigh(&key, &value);
+        if weight > self.capacity.limit {
+            self.capacity.discard(key, value);
+            return previous;
+        }
+
+        while self.capacity.used + weight > self.capacity.limit {
+            let handle = self.list.back_handle().unwrap();
+            let entry = self.list.remove_handle(handle);
+            self.map.remove(&entry.key);
+            self.capacity.evict(entry);
+        }
+
+        let handle = self.list.push_front_handle(Entry {
+            key: key.clone(),
+            value,
+            weight,
+        });
+        self.map.insert(key, handle);
+        self.capacity.used += weight;
+
+        previous
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let handle = self.map.remove(key)?;
+        let entry = self.list.remove_handle(handle);
+        self.capacity.used -= entry.weight;
+        Some(entry.value)
+    }
+
+    /// Removes the least recently used entry, without calling the eviction callback.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let handle = self.list.back_handle()?;
+        let entry = self.list.remove_handle(handle);
+        self.map.remove(&entry.key);
+        self.capacity.used -= entry.weight;
+        Some((entry.key, entry.value))
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list = LinkedList::new();
+        self.capacity.used = 0;
+    }
+
+    /// Iterates from the most to the least recently used entry.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.list.iter().map(|entry| (&entry.key, &entry.value))
+    }
+}
+
+/// Entries accessed the same number of times.
+struct Frequency<K, V> {
+    count: usize,
+    // Most recently used at the front, ties in count are evicted from the back.
+    entries: LinkedList<Entry<K, V>>,
+}
+
+// Frequency an entry belongs to and its node within it.
+type Location<K, V> = (NodeHandle<Frequency<K, V>>, NodeHandle<Entry<K, V>>);
+
+/// Least frequently used cache: once full, makes room by evicting the entry accessed the fewest times (the least
+/// recently used one among equals).
+///
+/// Entries are grouped into a list of frequencies in ascending order, each with its own list of entries. An access
+/// moves the entry into the neighbouring frequency, so everything stays O(1) (Shah, Mitra & Matani, 2010).
+pub struct LfuCache<K, V> {
+    map: OpenHashMap<K, Location<K, V>>,
+    // Ascending by count, without empty frequencies.
+    frequencies: LinkedList<Frequency<K, V>>,
+    capacity: Capacity<K, V>,
+}
+
+impl<K: Hash + Eq + Clone, V> LfuCache<K, V> {
+    /// Holds at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            map: OpenHashMap::new(),
+            frequencies: LinkedList::new(),
+            capacity: Capacity::new(capacity, None),
+        }
+    }
+
+    /// Holds entries up to a total weight of `capacity`, each weighing `weigher(key, value)`.
+    pub fn with_weigher(capacity: usize, weigher: impl Fn(&K, &V) -> usize + 'static) -> Self {
+        Self {
+            map: OpenHashMap::new(),
+            frequencies: LinkedList::new(),
+            capacity: Capacity::new(capacity, Some(Box::new(weigher))),
+        }
+    }
+
+    /// Calls `on_evict` with every entry evicted to make room, including entries too heavy to be cached at all.
+    /// Removed or replaced entries are not reported.
+    pub fn with_eviction_callback(mut self, on_evict: impl FnMut(K, V) + 'static) -> Self {
+        self.capacity.on_evict = Some(Box::new(on_evict));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity.limit
+    }
+
+    /// Total weight of the cached entries (their count without a weigher).
+    pub fn weight(&self) -> usize {
+        self.capacity.used
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Number of accesses (including the `put`) since the entry was inserted.
+    pub fn frequency(&self, key: &K) -> Option<usize> {
+        let (frequency, _) = *self.map.get(key)?;
+        Some(self.frequencies.get(frequency).count)
+    }
+
+    /// Returns the value and counts the access.
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let (frequency, entry) = self.touch(key)?;
+        Some(
+            &mut self
+                .frequencies
+                .get_mut(frequency)
+                .entries
+                .get_mut(entry)
+                .value,
+        )
+    }
+
+    /// Returns the value without counting the access.
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        let (frequency, entry) = *self.map.get(key)?;
+        Some(&self.frequencies.get(frequency).entries.get(entry).value)
+    }
+
+    /// Inserts the entry with a frequency of 1, evicting least frequently used ones until it fits. Returns the
+    /// previous value of the key (whose frequency is reset).
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        let previous = self.remove(&key);
+
+        let weight = self.capacity.weigh(&key, &value);
+        if weight > self.capacity.limit {
+            self.capacity.discard(key, value);
+            return previous;
+        }
+
+        while self.capacity.used + weight > self.capacity.limit {
+            let entry = self.pop_entry().unwrap();
+            self.capacity.evict(entry);
+        }
+
+        let frequency = match self.frequencies.front_handle() {
+            Some(frequency) if self.frequencies.get(frequency).count == 1 => frequency,
+            _ => self.frequencies.push_front_handle(Frequency {
+                count: 1,
+                entries: LinkedList::new(),
+            }),
+        };
+        let entry = self
+            .frequencies
+            .get_mut(frequency)
+            .entries
+            .push_front_handle(Entry {
+                key: key.clone(),
+                value,
+                weight,
+            });
+        self.map.insert(key, (frequency, entry));
+        self.capacity.used += weight;
+
+        previous
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (frequency, entry) = self.map.remove(key)?;
+        let entry = self.take_entry(frequency, entry);
+        self.capacity.used -= entry.weight;
+        Some(entry.value)
+    }
+
+    /// Removes the least frequently used entry, without calling the eviction callback.
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let entry = self.pop_entry()?;
+        self.capacity.used -= entry.weight;
+        Some((entry.key, entry.value))
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.frequencies = LinkedList::new();
+        self.capacity.used = 0;
+    }
+
+    /// Moves the entry to the frequency one higher, creating it if needed.
+    fn touch(&mut self, key: &K) -> Option<Location<K, V>> {
+        let (frequency, entry) = *self.map.get(key)?;
+        let count = self.frequencies.get(frequency).count;
+
+        let next = match self.frequencies.next_handle(frequency) {
+            Some(next) if self.frequencies.get(next).count == count + 1 => next,
+            _ => self.frequencies.insert_after(
+                frequency,
+                Frequency {
+                    count: count + 1,
+                    entries: LinkedList::new(),
+                },
+            ),
+        };
+
+        let entry = self.take_entry(frequency, entry);
+        let entry = self
+            .frequencies
+            .get_mut(next)
+            .entries
+            .push_front_handle(entry);
+        *self.map.get_mut(key).unwrap() = (next, entry);
+
+        Some((next, entry))
+    }
+
+    /// Takes the least recently used entry of the lowest frequency out of the lists and the map.
+    fn pop_entry(&mut self) -> Option<Entry<K, V>> {
+        let frequency = self.frequencies.front_handle()?;
+        let entry = self
+            .frequencies
+            .get(frequency)
+            .entries
+            .back_handle()
+            .unwrap();
+        let entry = self.take_entry(frequency, entry);
+        self.map.remove(&entry.key);
+        Some(entry)
+    }
+
+    /// Takes the entry out of its frequency, dropping the frequency once empty.
+    fn take_entry(
+        &mut self,
+        frequency: NodeHandle<Frequency<K, V>>,
+        entry: NodeHandle<Entry<K, V>>,
+    ) -> Entry<K, V> {
+        let entries = &mut self.frequencies.get_mut(frequency).entries;
+        let entry = entries.remove_handle(entry);
+        if entries.is_empty() {
+            self.frequencies.remove_handle(frequency);
+        }
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::{cell::RefCell, rc::Rc};
+
+    fn keys<'a>(iter: impl Iterator<Item = (&'a i32, &'a i32)>) -> Vec<i32> {
+        iter.map(|(key, _)| *key).collect()
+    }
+
+    /// Collects evicted keys.
+    fn eviction_log() -> (Rc<RefCell<Vec<i32>>>, impl FnMut(i32, i32)) {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let writer = Rc::clone(&log);
+        (log, move |key, _| writer.borrow_mut().push(key))
+    }
+
+    #[test]
+    fn lru_evicts_least_recently_used() {
+        let mut cache = LruCache::new(3);
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(3, 30);
+        assert_eq!(keys(cache.iter()), [3, 2, 1]);
+
+        // Reading refreshes, peeking doesn't.
+        assert_eq!(cache.get(&1), Some(&10));
+        assert_eq!(cache.peek(&2), Some(&20));
+        cache.put(4, 40);
+
+        assert_eq!(keys(cache.iter()), [4, 1, 3]);
+        assert!(!cache.contains_key(&2));
+        assert_eq!(cache.put(3, 33), Some(30));
+        assert_eq!(keys(cache.iter()), [3, 4, 1]);
+        assert_eq!(cache.pop_lru(), Some((1, 10)));
+        assert_eq!(cache.remove(&4), Some(40));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn lru_respects_weights() {
+        let (log, on_evict) = eviction_log();
+        let mut cache = LruCache::with_weigher(10, |_, value: &i32| *value as usize)
+            .with_eviction_callback(on_evict);
+
+        cache.put(1, 4);
+        cache.put(2, 4);
+        cache.put(3, 2);
+        assert_eq!(cache.weight(), 10);
+
+        // Needs room for 5, evicts the two oldest.
+        cache.put(4, 5);
+        assert_eq!(keys(cache.iter()), [4, 3]);
+        assert_eq!(cache.weight(), 7);
+
+        // Too heavy to be cached at all, the rest stays.
+        cache.put(5, 11);
+        assert_eq!(keys(cache.iter()), [4, 3]);
+        assert_eq!(*log.borrow(), [1, 2, 5]);
+
+        // Replacing is not an eviction.
+        cache.put(3, 1);
+        assert_eq!(cache.weight(), 6);
+        assert_eq!(log.borrow().len(), 3);
+    }
+
+    #[test]
+    fn lfu_evicts_least_frequently_used() {
+        let (log, on_evict) = eviction_log();
+        let mut cache = LfuCache::new(3).with_eviction_callback(on_evict);
+        cache.put(1, 10);
+        cache.put(2, 20);
+        cache.put(3, 30);
+
+        cache.get(&1);
+        cache.get(&1);
+        cache.get(&3);
+        assert_eq!(cache.frequency(&1), Some(3));
+        assert_eq!(cache.frequency(&2), Some(1));
+        assert_eq!(cache.frequency(&3), Some(2));
+
+        cache.put(4, 40);
+        cache.put(5, 50);
+        // 2 is the least used, then 4 is the only entry used just once when 5 arrives.
+        assert_eq!(*log.borrow(), [2, 4]);
+
+        // Among equal counts, the least recently used goes first.
+        cache.get(&5);
+        assert_eq!(cache.pop_lfu(), Some((3, 30)));
+        assert_eq!(cache.pop_lfu(), Some((5, 50)));
+        assert_eq!(cache.pop_lfu(), Some((1, 10)));
+        assert_eq!(cache.pop_lfu(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn lfu_respects_weights() {
+        let mut cache = LfuCache::with_weigher(10, |_, value: &i32| *value as usize);
+        cache.put(1, 3);
+        cache.put(2, 3);
+        cache.put(3, 3);
+        cache.get(&1);
+        cache.get(&3);
+
+        // Needs room for 5, evicts the least used and then the least recently used of the rest.
+        cache.put(4, 5);
+        assert!(!cache.contains_key(&2));
+        assert!(!cache.contains_key(&1));
+        assert!(cache.contains_key(&3));
+        assert_eq!(cache.weight(), 8);
+        assert_eq!(cache.peek(&4), Some(&5));
+        assert_eq!(cache.frequency(&4), Some(1));
+    }
+
+    /// Entry of the naive model caches: key, value, number of uses and time of last use.
+    type ModelEntry = (i32, i32, usize, usize);
+
+    /// Runs a random sequence of operations against a cache and an O(n) model, `victim` picks the model's entry to
+    /// evict.
+    fn check_against_model(
+        seed: u64,
+        mut put: impl FnMut(i32, i32) -> Option<i32>,
+        mut get: impl FnMut(i32) -> Option<i32>,
+        mut remove: impl FnMut(i32) -> Option<i32>,
+        victim: impl Fn(&[ModelEntry]) -> usize,
+    ) {
+        const CAPACITY: usize = 16;
+        let mut random = Random::new(seed);
+        let mut model: Vec<ModelEntry> = Vec::new();
+
+        for time in 0..20_000 {
+            let key = random.range(0..40) as i32;
+            let position = model.iter().position(|entry| entry.0 == key);
+
+            match random.range(0..10) {
+                0..=3 => {
+                    let expected = position.map(|position| model.remove(position).1);
+                    if model.len() == CAPACITY {
+                        model.remove(victim(&model));
+                    }
+                    model.push((key, time as i32, 1, time));
+                    assert_eq!(put(key, time as i32), expected);
+                }
+                4..=8 => {
+                    let expected = position.map(|position| {
+                        model[position].2 += 1;
+                        model[position].3 = time;
+                        model[position].1
+                    });
+                    assert_eq!(get(key), expected);
+                }
+                _ => {
+                    let expected = position.map(|position| model.remove(position).1);
+                    assert_eq!(remove(key), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lru_matches_model() {
+        let cache = RefCell::new(LruCache::new(16));
+        check_against_model(
+            33,
+            |key, value| cache.borrow_mut().put(key, value),
+            |key| cache.borrow_mut().get(&key).copied(),
+            |key| cache.borrow_mut().remove(&key),
+            |model| {
+                (0..model.len())
+                    .min_by_key(|index| model[*index].3)
+                    .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn lfu_matches_model() {
+        let cache = RefCell::new(LfuCache::new(16));
+        check_against_model(
+            34,
+            |key, value| cache.borrow_mut().put(key, value),
+            |key| cache.borrow_mut().get(&key).copied(),
+            |key| cache.borrow_mut().remove(&key),
+            |model| {
+                (0..model.len())
+                    .min_by_key(|index| (model[*index].2, model[*index].3))
+                    .unwrap()
+            },
+        );
+    }
+
+    #[test]
+    fn drops_entries_once() {
+        let value = Rc::new(0);
+        {
+            let mut lru = LruCache::new(4);
+            let mut lfu = LfuCache::new(4);
+            for key in 0..10 {
+                lru.put(key, Rc::clone(&value));
+                lfu.put(key, Rc::clone(&value));
+                lfu.get(&(key / 2));
+            }
+            assert_eq!(Rc::strong_count(&value), 9);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
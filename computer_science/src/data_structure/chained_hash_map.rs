@@ -0,0 +1,432 @@
+use super::{dynamic_array::DynamicArray, linked_list::LinkedList};
+use std::{
+    hash::{BuildHasher, Hash, RandomState},
+    mem,
+};
+
+const MIN_BUCKETS: usize = 8;
+// Number of old buckets moved to the new table by every insert or remove while rehashing. With a table growing to
+// twice its buckets once entries outnumber them, 2 per operation finishes well before the next growth is due.
+const REHASH_STEP: usize = 2;
+
+struct Item<K, V> {
+    key: K,
+    value: V,
+    hash: u64,
+}
+
+type Bucket<K, V> = LinkedList<Item<K, V>>;
+
+/// Hash map with separate chaining: every bucket is a `LinkedList` of the entries hashing into it.
+///
+/// Grows to twice the buckets once there are more entries than buckets, but instead of moving all entries at once the
+/// old table is kept around and drained a few buckets per insert/remove (incremental rehashing, as in Redis). Lookups
+/// meanwhile check both tables. This bounds the worst-case latency of a single operation, at the cost of a slightly
+/// slower average. Only allocating the empty buckets of the new table still takes time proportional to its size.
+pub struct ChainedHashMap<K, V, S = RandomState> {
+    table: DynamicArray<Bucket<K, V>>,
+    // Table being drained, empty when not rehashing. Buckets before `rehash_index` were already moved.
+    old_table: DynamicArray<Bucket<K, V>>,
+    rehash_index: usize,
+    len: usize,
+    hash_builder: S,
+}
+
+impl<K: Hash + Eq, V> ChainedHashMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> ChainedHashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            table: DynamicArray::new(),
+            old_table: DynamicArray::new(),
+            rehash_index: 0,
+            len: 0,
+            hash_builder,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of buckets of the current table.
+    pub fn bucket_count(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_rehashing(&self) -> bool {
+        !self.old_table.is_empty()
+    }
+
+    /// Length of the longest chain, the cost of the slowest lookup.
+    pub fn longest_chain(&self) -> usize {
+        self.buckets().map(|bucket| bucket.len()).max().unwrap_or(0)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let hash = self.hash_builder.hash_one(key);
+        self.buckets_for(hash)
+            .into_iter()
+            .flatten()
+            .find_map(|bucket| {
+                bucket
+                    .iter()
+                    .find(|item| item.hash == hash && item.key == *key)
+            })
+            .map(|item| &item.value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let hash = self.hash_builder.hash_one(key);
+        self.find_mut(hash, key).map(|item| &mut item.value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.rehash_step();
+
+        let hash = self.hash_builder.hash_one(&key);
+        if let Some(item) = self.find_mut(hash, &key) {
+            return Some(mem::replace(&mut item.value, value));
+        }
+
+        self.reserve_one();
+        let index = Self::bucket_index(&self.table, hash);
+        self.table[index].push_front(Item { key, value, hash });
+        self.len += 1;
+
+        None
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.rehash_step();
+
+        let hash = self.hash_builder.hash_one(key);
+        let item = [&mut self.old_table, &mut self.table]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .find_map(|table| {
+                let index = Self::bucket_index(table, hash);
+                let bucket = &mut table[index];
+                let position = bucket
+                    .iter()
+                    .position(|item| item.hash == hash && item.key == *key)?;
+                Some(bucket.remove(position))
+            })?;
+
+        self.len -= 1;
+        Some(item.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.table = DynamicArray::new();
+        self.old_table = DynamicArray::new();
+        self.rehash_index = 0;
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.buckets()
+            .flat_map(|bucket| bucket.iter())
+            .map(|item| (&item.key, &item.value))
+    }
+
+    fn bucket_index(table: &DynamicArray<Bucket<K, V>>, hash: u64) -> usize {
+        hash as usize & (table.len() - 1)
+    }
+
+    /// Buckets a key with the hash can be in: its bucket in the old table (if any) and in the current one.
+    fn buckets_for(&self, hash: u64) -> [Option<&Bucket<K, V>>; 2] {
+        [&self.old_table, &self.table].map(|table| match table.is_empty() {
+            true => None,
+            false => Some(&table[Self::bucket_index(table, hash)]),
+        })
+    }
+
+    fn find_mut(&mut self, hash: u64, key: &K) -> Option<&mut Item<K, V>> {
+        [&mut self.old_table, &mut self.table]
+            .into_iter()
+            .filter(|table| !table.is_empty())
+            .find_map(|table| {
+                let index = Self::bucket_index(table, hash);
+                table[index]
+                    .iter_mut()
+                    .find(|item| item.hash == hash && item.key == *key)
+            })
+    }
+
+    fn buckets(&self) -> impl Iterator<Item = &Bucket<K, V>> {
+        let old_buckets =
+            (self.rehash_index..self.old_table.len()).map(|index| &self.old_table[index]);
+        let buckets = (0..self.table.len()).map(|index| &self.table[index]);
+        old_buckets.chain(buckets)
+    }
+
+    /// Starts rehashing into a table with twice the buckets once entries would outnumber buckets.
+    fn reserve_one(&mut self) {
+        if self.table.is_empty() {
+            self.table = Self::empty_table(MIN_BUCKETS);
+            return;
+        }
+        if self.len < self.table.len() {
+            return;
+        }
+
+        // Never happens with the current step size, but two tables is the most lookups handle.
+        while self.is_rehashing() {
+            self.rehash_step();
+        }
+
+        let new_table = Self::empty_table(self.table.len() * 2);
+        self.old_table = mem::replace(&mut self.table, new_table);
+        self.rehash_index = 0;
+    }
+
+    /// Moves the next few buckets of the old table into the current one.
+    fn rehash_step(&mut self) {
+        if !self.is_rehashing() {
+            return;
+        }
+
+        for _ in 0..REHASH_STEP {
+            let bucket = &mut self.old_table[self.rehash_index];
+            while let Some(item) = bucket.pop_front() {
+                let index = Self::bucket_index(&self.table, item.hash);
+                self.table[index].push_front(item);
+            }
+            self.rehash_index += 1;
+
+            if self.rehash_index == self.old_table.len() {
+                self.old_table = DynamicArray::new();
+                self.rehash_index = 0;
+                return;
+            }
+        }
+    }
+
+    fn empty_table(buckets: usize) -> DynamicArray<Bucket<K, V>> {
+        let mut table = DynamicArray::new();
+        for _ in 0..buckets {
+            table.push(LinkedList::new());
+        }
+        table
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher + Default> Default for ChainedHashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher + Default> FromIterator<(K, V)> for ChainedHashMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Extend<(K, V)> for ChainedHashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data_structure::hash_map::OpenHashMap, random::Random};
+    use std::{collections::HashMap, time::Instant};
+
+    /// Checks that every entry sits in the bucket its hash points to, in whichever table it is.
+    fn check_invariants<K: Hash + Eq, V, S: BuildHasher>(map: &ChainedHashMap<K, V, S>) {
+        assert_eq!(map.iter().count(), map.len());
+        assert!(map.table.len().is_power_of_two());
+
+        for index in 0..map.old_table.len() {
+            for item in map.old_table[index].iter() {
+                assert!(
+                    index >= map.rehash_index,
+                    "Entry left behind in a moved bucket."
+                );
+                assert_eq!(
+                    ChainedHashMap::<K, V, S>::bucket_index(&map.old_table, item.hash),
+                    index
+                );
+            }
+        }
+        for index in 0..map.table.len() {
+            for item in map.table[index].iter() {
+                assert_eq!(
+                    ChainedHashMap::<K, V, S>::bucket_index(&map.table, item.hash),
+                    index
+                );
+                assert_eq!(map.hash_builder.hash_one(&item.key), item.hash);
+            }
+        }
+    }
+
+    #[test]
+    fn can_insert_get() {
+        let mut map = ChainedHashMap::new();
+        assert_eq!(map.insert("one", 1), None);
+        assert_eq!(map.insert("two", 2), None);
+        assert_eq!(map.insert("one", 11), Some(1));
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&"one"), Some(&11));
+        assert_eq!(map.get(&"three"), None);
+        *map.get_mut(&"two").unwrap() += 20;
+        assert_eq!(map.get(&"two"), Some(&22));
+    }
+
+    #[test]
+    fn can_remove() {
+        let mut map = ChainedHashMap::new();
+        for key in 0..100 {
+            map.insert(key, key * 2);
+        }
+
+        for key in (0..100).step_by(3) {
+            assert_eq!(map.remove(&key), Some(key * 2));
+        }
+        assert_eq!(map.remove(&0), None);
+
+        check_invariants(&map);
+        assert_eq!(map.len(), 66);
+        assert!((0..100).all(|key| map.contains_key(&key) == (key % 3 != 0)));
+    }
+
+    #[test]
+    fn rehashes_incrementally() {
+        let mut map = ChainedHashMap::new();
+        for key in 0..8 {
+            map.insert(key, key);
+        }
+        assert_eq!(map.bucket_count(), 8);
+        assert!(!map.is_rehashing());
+
+        // Ninth entry starts the growth, but only a few buckets move per operation.
+        map.insert(8, 8);
+        assert_eq!(map.bucket_count(), 16);
+        assert!(map.is_rehashing());
+        check_invariants(&map);
+        assert!((0..9).all(|key| map.get(&key) == Some(&key)));
+
+        for key in 9..13 {
+            map.insert(key, key);
+            check_invariants(&map);
+        }
+        assert!(!map.is_rehashing());
+        assert!((0..13).all(|key| map.get(&key) == Some(&key)));
+
+        // Growth never has to wait for a previous one to finish.
+        for key in 13..10_000 {
+            map.insert(key, key);
+            assert!(map.len() <= map.bucket_count());
+        }
+        check_invariants(&map);
+    }
+
+    #[test]
+    fn iterates_all_entries() {
+        let map: ChainedHashMap<i32, i32> = (0..50).map(|key| (key, key * key)).collect();
+
+        let mut entries: Vec<(i32, i32)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            (0..50).map(|key| (key, key * key)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn handles_owned_values() {
+        let mut map = ChainedHashMap::new();
+        for key in 0..200 {
+            map.insert(key.to_string(), vec![key; 2]);
+        }
+        for key in (0..200).step_by(2) {
+            assert_eq!(map.remove(&key.to_string()), Some(vec![key; 2]));
+        }
+
+        assert_eq!(map.len(), 100);
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&"1".to_string()), None);
+    }
+
+    #[test]
+    fn matches_hash_map_on_random_operations() {
+        let mut random = Random::new(31);
+        let mut map = ChainedHashMap::new();
+        let mut oracle = HashMap::new();
+
+        for step in 0..20_000 {
+            let key = random.range(0..2000);
+            match random.range(0..3) {
+                0 | 1 => assert_eq!(map.insert(key, step), oracle.insert(key, step)),
+                _ => assert_eq!(map.remove(&key), oracle.remove(&key)),
+            }
+
+            assert_eq!(map.get(&key), oracle.get(&key));
+            if step % 1000 == 0 {
+                check_invariants(&map);
+            }
+        }
+
+        check_invariants(&map);
+        assert_eq!(map.len(), oracle.len());
+        assert!(oracle
+            .iter()
+            .all(|(key, value)| map.get(key) == Some(value)));
+    }
+
+    /// Compares the slowest single insert of both hash map designs.
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn benchmark_worst_case_latency() {
+        const COUNT: usize = 4_000_000;
+
+        fn measure(name: &str, mut insert: impl FnMut(u64)) {
+            let mut latencies: Vec<u128> = (0..COUNT as u64)
+                .map(|key| {
+                    let start = Instant::now();
+                    insert(key);
+                    start.elapsed().as_nanos()
+                })
+                .collect();
+            latencies.sort_unstable();
+
+            let total: u128 = latencies.iter().sum();
+            println!(
+                "{name:<24} total {:>8.2}ms, p99 {:>6}ns, max {:>10}ns",
+                total as f64 / 1e6,
+                latencies[COUNT * 99 / 100],
+                latencies[COUNT - 1]
+            );
+        }
+
+        let mut open = OpenHashMap::new();
+        measure("insert OpenHashMap", |key| _ = open.insert(key, key));
+        let mut chained = ChainedHashMap::new();
+        measure("insert ChainedHashMap", |key| _ = chained.insert(key, key));
+    }
+}
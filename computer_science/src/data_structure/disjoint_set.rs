@@ -0,0 +1,446 @@
+use super::hash_map::OpenHashMap;
+use std::{cmp::Ordering, hash::Hash};
+
+/// Disjoint-set union (union-find) over the elements `0..len`.
+///
+/// Path compression in `find` together with union by rank keeps every operation at O(α(n)) amortized, where α is the
+/// inverse Ackermann function (at most 4 for any practical n).
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    // Upper bound of the tree height, only meaningful for roots.
+    rank: Vec<u8>,
+    // Number of elements in the set, only meaningful for roots.
+    size: Vec<usize>,
+    sets: usize,
+}
+
+impl DisjointSet {
+    /// Creates `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            rank: vec![0; len],
+            size: vec![1; len],
+            sets: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Adds a new singleton set, returns its element.
+    pub fn make_set(&mut self) -> usize {
+        let element = self.parent.len();
+        self.parent.push(element);
+        self.rank.push(0);
+        self.size.push(1);
+        self.sets += 1;
+        element
+    }
+
+    /// Representative of the element's set, the same for all its elements until the next `union`.
+    pub fn find(&mut self, element: usize) -> usize {
+        assert!(element < self.len(), "Out of bounds index.");
+
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+
+        // Path compression: point everything on the way directly to the root.
+        let mut current = element;
+        while self.parent[current] != root {
+            let next = self.parent[current];
+            self.parent[current] = root;
+            current = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets of both elements, returns false if they already were in the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        // Union by rank: the shallower tree goes under the deeper one.
+        let (child, root) = match self.rank[a].cmp(&self.rank[b]) {
+            Ordering::Less => (a, b),
+            Ordering::Greater => (b, a),
+            Ordering::Equal => {
+                self.rank[a] += 1;
+                (b, a)
+            }
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.sets -= 1;
+
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Number of elements in the element's set.
+    pub fn set_size(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.size[root]
+    }
+
+    pub fn count_sets(&self) -> usize {
+        self.sets
+    }
+}
+
+/// `DisjointSet` over arbitrary hashable values, each mapped to an element index on first use.
+pub struct KeyedDisjointSet<T> {
+    elements: OpenHashMap<T, usize>,
+    set: DisjointSet,
+}
+
+impl<T: Hash + Eq> KeyedDisjointSet<T> {
+    pub fn new() -> Self {
+        Self {
+            elements: OpenHashMap::new(),
+            set: DisjointSet::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.elements.contains_key(value)
+    }
+
+    /// Adds the value as a singleton set unless present, returns whether it was added.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.elements.contains_key(&value) {
+            return false;
+        }
+
+        let element = self.set.make_set();
+        self.elements.insert(value, element);
+        true
+    }
+
+    /// Identifier of the value's set, the same for all its values until the next `union`.
+    pub fn find(&mut self, value: &T) -> Option<usize> {
+        let element = *self.elements.get(value)?;
+        Some(self.set.find(element))
+    }
+
+    /// Merges the sets of both values, inserting values seen for the first time. Returns false if they already were
+    /// in the same set.
+    pub fn union(&mut self, a: T, b: T) -> bool {
+        let a = self.element(a);
+        let b = self.element(b);
+        self.set.union(a, b)
+    }
+
+    /// Whether both values are known and in the same set.
+    pub fn connected(&mut self, a: &T, b: &T) -> bool {
+        match (self.find(a), self.find(b)) {
+            (Some(a), Some(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    pub fn set_size(&mut self, value: &T) -> Option<usize> {
+        let element = *self.elements.get(value)?;
+        Some(self.set.set_size(element))
+    }
+
+    pub fn count_sets(&self) -> usize {
+        self.set.count_sets()
+    }
+
+    fn element(&mut self, value: T) -> usize {
+        let set = &mut self.set;
+        *self.elements.entry(value).or_insert_with(|| set.make_set())
+    }
+}
+
+impl<T: Hash + Eq> Default for KeyedDisjointSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Disjoint-set union whose unions can be undone in reverse order, as needed by offline dynamic connectivity
+/// (divide and conquer over time, adding edges on the way down and rolling them back on the way up).
+///
+/// Path compression would change many parents per `find`, so it is left out and union by size alone keeps trees
+/// O(log n) high: `find` and `union` take O(log n), undoing one union takes O(1).
+pub struct RollbackDisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    sets: usize,
+    // Roots attached by each successful union, as (child, root).
+    history: Vec<(usize, usize)>,
+}
+
+impl RollbackDisjointSet {
+    /// Creates `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            sets: len,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&self, element: usize) -> usize {
+        assert!(element < self.len(), "Out of bounds index.");
+
+        let mut root = element;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        root
+    }
+
+    /// Merges the sets of both elements, returns false if they already were in the same set (nothing to undo then).
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+
+        let (child, root) = match self.size[a] < self.size[b] {
+            true => (a, b),
+            false => (b, a),
+        };
+        self.parent[child] = root;
+        self.size[root] += self.size[child];
+        self.sets -= 1;
+        self.history.push((child, root));
+
+        true
+    }
+
+    pub fn connected(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_size(&self, element: usize) -> usize {
+        self.size[self.find(element)]
+    }
+
+    pub fn count_sets(&self) -> usize {
+        self.sets
+    }
+
+    /// Undoes the last successful union, returns false if there was none.
+    pub fn undo(&mut self) -> bool {
+        let Some((child, root)) = self.history.pop() else {
+            return false;
+        };
+
+        self.parent[child] = child;
+        self.size[root] -= self.size[child];
+        self.sets += 1;
+        true
+    }
+
+    /// Marks the current state, see `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undoes all unions made since the snapshot was taken.
+    pub fn rollback(&mut self, snapshot: usize) {
+        assert!(
+            snapshot <= self.history.len(),
+            "Snapshot already rolled back."
+        );
+
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    /// Naive model: every element carries the label of its set, unions relabel.
+    fn union_labels(labels: &mut [usize], a: usize, b: usize) -> bool {
+        let (from, to) = (labels[a], labels[b]);
+        labels
+            .iter_mut()
+            .filter(|label| **label == from)
+            .for_each(|label| *label = to);
+        from != to
+    }
+
+    fn count_labels(labels: &[usize]) -> usize {
+        (0..labels.len())
+            .filter(|label| labels.contains(label))
+            .count()
+    }
+
+    #[test]
+    fn can_union_find() {
+        let mut set = DisjointSet::new(6);
+        assert_eq!(set.count_sets(), 6);
+
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 3));
+        assert!(set.union(1, 3));
+        assert!(!set.union(0, 2));
+
+        assert!(set.connected(0, 3));
+        assert!(!set.connected(0, 4));
+        assert_eq!(set.set_size(2), 4);
+        assert_eq!(set.set_size(5), 1);
+        assert_eq!(set.count_sets(), 3);
+
+        let element = set.make_set();
+        assert_eq!(element, 6);
+        assert!(set.union(element, 5));
+        assert_eq!(set.set_size(5), 2);
+        assert_eq!(set.count_sets(), 3);
+    }
+
+    #[test]
+    fn compresses_paths() {
+        let mut set = DisjointSet::new(5);
+        // Equal ranks, so every union puts the new root on top.
+        set.union(0, 1);
+        set.union(2, 3);
+        set.union(0, 2);
+        set.union(4, 0);
+
+        let root = set.find(3);
+        assert!((0..5).all(|element| set.find(element) == root));
+        assert!((0..5).all(|element| set.parent[element] == root));
+        assert!(set.rank.iter().all(|rank| *rank <= 2));
+    }
+
+    #[test]
+    fn can_use_keys() {
+        let mut set = KeyedDisjointSet::new();
+        assert!(set.insert("prague"));
+        assert!(!set.insert("prague"));
+
+        assert!(set.union("prague", "brno"));
+        assert!(set.union("vienna", "graz"));
+        assert!(!set.union("brno", "prague"));
+
+        assert!(set.connected(&"brno", &"prague"));
+        assert!(!set.connected(&"brno", &"vienna"));
+        assert!(!set.connected(&"brno", &"paris"));
+        assert_eq!(set.find(&"graz"), set.find(&"vienna"));
+        assert_eq!(set.find(&"paris"), None);
+        assert_eq!(set.set_size(&"prague"), Some(2));
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.count_sets(), 2);
+    }
+
+    #[test]
+    fn can_roll_back() {
+        let mut set = RollbackDisjointSet::new(5);
+        set.union(0, 1);
+        let snapshot = set.snapshot();
+
+        set.union(2, 3);
+        set.union(1, 2);
+        assert!(!set.union(0, 3));
+        assert_eq!(set.set_size(3), 4);
+        assert_eq!(set.count_sets(), 2);
+
+        assert!(set.undo());
+        assert!(!set.connected(0, 3));
+        assert!(set.connected(2, 3));
+
+        set.union(3, 4);
+        set.rollback(snapshot);
+        assert!(set.connected(0, 1));
+        assert!(!set.connected(2, 3));
+        assert_eq!(set.set_size(0), 2);
+        assert_eq!(set.count_sets(), 4);
+
+        set.rollback(0);
+        assert!(!set.undo());
+        assert_eq!(set.count_sets(), 5);
+    }
+
+    #[test]
+    fn matches_naive_labels() {
+        const LEN: usize = 60;
+        let mut random = Random::new(35);
+        let mut set = DisjointSet::new(LEN);
+        let mut labels: Vec<usize> = (0..LEN).collect();
+
+        for _ in 0..5000 {
+            let (a, b) = (random.range(0..LEN), random.range(0..LEN));
+            match random.range(0..2) {
+                0 => assert_eq!(set.union(a, b), union_labels(&mut labels, a, b)),
+                _ => assert_eq!(set.connected(a, b), labels[a] == labels[b]),
+            }
+
+            let size = labels.iter().filter(|label| **label == labels[a]).count();
+            assert_eq!(set.set_size(a), size);
+            assert_eq!(set.count_sets(), count_labels(&labels));
+
+            // Restart once everything is connected.
+            if set.count_sets() == 1 {
+                set = DisjointSet::new(LEN);
+                labels = (0..LEN).collect();
+            }
+        }
+    }
+
+    #[test]
+    fn rollback_matches_naive_labels() {
+        const LEN: usize = 60;
+        let mut random = Random::new(36);
+        let mut set = RollbackDisjointSet::new(LEN);
+        let mut labels: Vec<usize> = (0..LEN).collect();
+        let mut snapshots = Vec::new();
+
+        for _ in 0..5000 {
+            let (a, b) = (random.range(0..LEN), random.range(0..LEN));
+
+            match random.range(0..8) {
+                0 => snapshots.push((set.snapshot(), labels.clone())),
+                1 if !snapshots.is_empty() => {
+                    let (snapshot, saved_labels) = snapshots.pop().unwrap();
+                    set.rollback(snapshot);
+                    labels = saved_labels;
+                    assert!((0..LEN).all(|element| set.connected(element, labels[element])));
+                }
+                _ => assert_eq!(set.union(a, b), union_labels(&mut labels, a, b)),
+            }
+
+            assert_eq!(set.connected(a, b), labels[a] == labels[b]);
+            assert_eq!(set.count_sets(), count_labels(&labels));
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::{
-    alloc::{alloc, handle_alloc_error, realloc, Layout},
+    alloc::{alloc, dealloc, handle_alloc_error, realloc, Layout},
+    mem,
+    ops::{Index, IndexMut},
     ptr::NonNull,
 };
 
@@ -105,6 +107,55 @@ impl<T> DynamicArray<T> {
 
         value
     }
+
+    pub fn len(&self) -> usize {
+        self.items_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items_count == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.items_cap
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { std::slice::from_raw_parts(self.pointer.as_ptr(), self.items_count) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { std::slice::from_raw_parts_mut(self.pointer.as_ptr(), self.items_count) }
+    }
+}
+
+impl<T> Index<usize> for DynamicArray<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(index < self.items_count, "Out of bounds index.");
+        unsafe { self.pointer.add(index).as_ref() }
+    }
+}
+
+impl<T> IndexMut<usize> for DynamicArray<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        assert!(index < self.items_count, "Out of bounds index.");
+        unsafe { self.pointer.add(index).as_mut() }
+    }
+}
+
+impl<T> Drop for DynamicArray<T> {
+    fn drop(&mut self) {
+        for index in 0..self.items_count {
+            unsafe { self.pointer.add(index).drop_in_place() };
+        }
+
+        if self.items_cap > 0 && mem::size_of::<T>() > 0 {
+            let layout = Layout::array::<T>(self.items_cap).unwrap();
+            unsafe { dealloc(self.pointer.as_ptr() as *mut u8, layout) };
+        }
+    }
 }
 
 impl<T> IntoIterator for DynamicArray<T> {
@@ -140,6 +191,16 @@ impl<T> Iterator for DynamicArrayIterator<T> {
     }
 }
 
+impl<T> Drop for DynamicArrayIterator<T> {
+    fn drop(&mut self) {
+        // Items before `index` were already moved out, drop only the rest and let the array free its memory.
+        for index in self.index..self.array.items_count {
+            unsafe { self.array.pointer.add(index).drop_in_place() };
+        }
+        self.array.items_count = 0;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +267,40 @@ mod tests {
         assert_eq!(remove_result, 4);
     }
 
+    #[test]
+    fn can_index() {
+        let mut array = DynamicArray::<i32>::new();
+        array.push(7);
+        array.push(8);
+        array[1] += 1;
+
+        assert_eq!(array.len(), 2);
+        assert_eq!(array[0], 7);
+        assert_eq!(array[1], 9);
+
+        let result = std::panic::catch_unwind(|| array[2]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn drops_items_once() {
+        let counter = std::rc::Rc::new(());
+
+        let mut array = DynamicArray::new();
+        for _ in 0..5 {
+            array.push(counter.clone());
+        }
+        array.pop();
+        assert_eq!(std::rc::Rc::strong_count(&counter), 5);
+
+        let mut iterator = array.into_iter();
+        iterator.next();
+        assert_eq!(std::rc::Rc::strong_count(&counter), 4);
+
+        drop(iterator);
+        assert_eq!(std::rc::Rc::strong_count(&counter), 1);
+    }
+
     #[test]
     fn insert_remove_out_of_bounds_panics() {
         let result = std::panic::catch_unwind(|| {
@@ -0,0 +1,519 @@
+use super::segment_tree::bounds;
+use std::ops::RangeBounds;
+
+/// Commutative group under addition: values can be added, subtracted and have a zero.
+///
+/// Fenwick trees only store prefix sums, so unlike segment trees they need subtraction to answer ranges. Integers
+/// wrap around on overflow, which keeps unsigned types a group too.
+pub trait AdditiveGroup: Copy {
+    fn zero() -> Self;
+
+    fn add(self, other: Self) -> Self;
+
+    fn sub(self, other: Self) -> Self;
+
+    /// Adds the value to itself `times` times, by doubling.
+    fn scale(self, times: usize) -> Self {
+        let (mut result, mut power, mut times) = (Self::zero(), self, times);
+        while times > 0 {
+            if times % 2 == 1 {
+                result = result.add(power);
+            }
+            power = power.add(power);
+            times /= 2;
+        }
+        result
+    }
+}
+
+macro_rules! impl_integer_group {
+    ($($number:ty),*) => {
+        $(
+            impl AdditiveGroup for $number {
+                fn zero() -> Self {
+                    0
+                }
+
+                fn add(self, other: Self) -> Self {
+                    self.wrapping_add(other)
+                }
+
+                fn sub(self, other: Self) -> Self {
+                    self.wrapping_sub(other)
+                }
+
+                fn scale(self, times: usize) -> Self {
+                    self.wrapping_mul(times as $number)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_group!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_float_group {
+    ($($number:ty),*) => {
+        $(
+            impl AdditiveGroup for $number {
+                fn zero() -> Self {
+                    0.0
+                }
+
+                fn add(self, other: Self) -> Self {
+                    self + other
+                }
+
+                fn sub(self, other: Self) -> Self {
+                    self - other
+                }
+
+                fn scale(self, times: usize) -> Self {
+                    self * times as $number
+                }
+            }
+        )*
+    };
+}
+
+impl_float_group!(f32, f64);
+
+/// Fenwick tree (binary indexed tree): point updates and prefix sums in O(log n), in a single array of n values.
+///
+/// Stored 1-based, position `i` holds the sum of the `i & -i` elements ending at it (its lowest set bit decides how
+/// far back it reaches).
+pub struct FenwickTree<T> {
+    tree: Vec<T>,
+}
+
+impl<T: AdditiveGroup> FenwickTree<T> {
+    /// Creates a tree over `len` zeros.
+    pub fn new(len: usize) -> Self {
+        Self {
+            tree: vec![T::zero(); len + 1],
+        }
+    }
+
+    /// Creates a tree over the values in O(n).
+    pub fn from_vec(values: Vec<T>) -> Self {
+        let mut tree = vec![T::zero()];
+        tree.extend(values);
+
+        // Every position passes its sum on to the next one covering it.
+        for index in 1..tree.len() {
+            let parent = index + lowest_bit(index);
+            if parent < tree.len() {
+                tree[parent] = tree[parent].add(tree[index]);
+            }
+        }
+
+        Self { tree }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to the element.
+    pub fn add(&mut self, index: usize, delta: T) {
+        assert!(index < self.len(), "Out of bounds index.");
+
+        let mut position = index + 1;
+        while position < self.tree.len() {
+            self.tree[position] = self.tree[position].add(delta);
+            position += lowest_bit(position);
+        }
+    }
+
+    pub fn set(&mut self, index: usize, value: T) {
+        self.add(index, value.sub(self.get(index)));
+    }
+
+    pub fn get(&self, index: usize) -> T {
+        self.range_sum(index..=index)
+    }
+
+    /// Sum of the elements `0..end`.
+    pub fn prefix_sum(&self, end: usize) -> T {
+        assert!(end <= self.len(), "Out of bounds index.");
+
+        let mut sum = T::zero();
+        let mut position = end;
+        while position > 0 {
+            sum = sum.add(self.tree[position]);
+            position -= lowest_bit(position);
+        }
+        sum
+    }
+
+    pub fn range_sum(&self, range: impl RangeBounds<usize>) -> T {
+        let (start, end) = bounds(range, self.len());
+        self.prefix_sum(end).sub(self.prefix_sum(start))
+    }
+
+    /// Smallest `end` such that `prefix_sum(end) >= target`, `None` if even the total is smaller. In O(log n) by
+    /// descending the implicit tree, which only works if no element is negative (prefix sums never decrease).
+    pub fn lower_bound(&self, target: T) -> Option<usize>
+    where
+        T: PartialOrd,
+    {
+        if target <= T::zero() {
+            return Some(0);
+        }
+
+        // Longest prefix with a sum still below the target.
+        let mut position = 0;
+        let mut sum = T::zero();
+        let mut step = match self.len() {
+            0 => 0,
+            len => 1 << len.ilog2(),
+        };
+        while step > 0 {
+            let next = position + step;
+            if next < self.tree.len() && sum.add(self.tree[next]) < target {
+                position = next;
+                sum = sum.add(self.tree[next]);
+            }
+            step /= 2;
+        }
+
+        match position < self.len() {
+            true => Some(position + 1),
+            false => None,
+        }
+    }
+}
+
+fn lowest_bit(position: usize) -> usize {
+    position & position.wrapping_neg()
+}
+
+/// Fenwick tree over the differences of neighbouring elements: adding to a range touches only its two ends, reading
+/// an element sums up the differences before it. Both in O(log n).
+pub struct RangeUpdateFenwickTree<T> {
+    differences: FenwickTree<T>,
+}
+
+impl<T: AdditiveGroup> RangeUpdateFenwickTree<T> {
+    /// Creates a tree over `len` zeros.
+    pub fn new(len: usize) -> Self {
+        Self {
+            differences: FenwickTree::new(len + 1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to every element in the range.
+    pub fn add_range(&mut self, range: impl RangeBounds<usize>, delta: T) {
+        let (start, end) = bounds(range, self.len());
+        self.differences.add(start, delta);
+        self.differences.add(end, T::zero().sub(delta));
+    }
+
+    pub fn get(&self, index: usize) -> T {
+        assert!(index < self.len(), "Out of bounds index.");
+        self.differences.prefix_sum(index + 1)
+    }
+}
+
+/// Fenwick tree answering range sums under range additions, both in O(log n).
+///
+/// With differences `d`, the prefix sum up to `end` is `Σ d[i] * (end - i)`, which two trees over `d[i]` and
+/// `d[i] * i` give as `end * Σ d[i] - Σ d[i] * i`.
+pub struct RangeFenwickTree<T> {
+    differences: FenwickTree<T>,
+    weighted_differences: FenwickTree<T>,
+}
+
+impl<T: AdditiveGroup> RangeFenwickTree<T> {
+    /// Creates a tree over `len` zeros.
+    pub fn new(len: usize) -> Self {
+        Self {
+            differences: FenwickTree::new(len + 1),
+            weighted_differences: FenwickTree::new(len + 1),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.differences.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to every element in the range.
+    pub fn add_range(&mut self, range: impl RangeBounds<usize>, delta: T) {
+        let (start, end) = bounds(range, self.len());
+        self.add_difference(start, delta);
+        self.add_difference(end, T::zero().sub(delta));
+    }
+
+    /// Sum of the elements `0..end`.
+    pub fn prefix_sum(&self, end: usize) -> T {
+        assert!(end <= self.len(), "Out of bounds index.");
+        let sum = self.differences.prefix_sum(end).scale(end);
+        sum.sub(self.weighted_differences.prefix_sum(end))
+    }
+
+    pub fn range_sum(&self, range: impl RangeBounds<usize>) -> T {
+        let (start, end) = bounds(range, self.len());
+        self.prefix_sum(end).sub(self.prefix_sum(start))
+    }
+
+    fn add_difference(&mut self, index: usize, delta: T) {
+        self.differences.add(index, delta);
+        self.weighted_differences.add(index, delta.scale(index));
+    }
+}
+
+/// Fenwick tree over a matrix: point updates and sums of rectangles in O(log rows * log columns).
+pub struct FenwickTree2D<T> {
+    // 1-based in both dimensions, `(rows + 1) * (columns + 1)` values.
+    tree: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl<T: AdditiveGroup> FenwickTree2D<T> {
+    /// Creates a tree over a `rows` x `columns` matrix of zeros.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self {
+            tree: vec![T::zero(); (rows + 1) * (columns + 1)],
+            rows,
+            columns,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Adds `delta` to the element.
+    pub fn add(&mut self, row: usize, column: usize, delta: T) {
+        assert!(
+            row < self.rows && column < self.columns,
+            "Out of bounds index."
+        );
+
+        let mut row_position = row + 1;
+        while row_position <= self.rows {
+            let mut column_position = column + 1;
+            while column_position <= self.columns {
+                let index = self.index(row_position, column_position);
+                self.tree[index] = self.tree[index].add(delta);
+                column_position += lowest_bit(column_position);
+            }
+            row_position += lowest_bit(row_position);
+        }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> T {
+        self.rectangle_sum(row..=row, column..=column)
+    }
+
+    /// Sum of the elements in rows `0..row_end` and columns `0..column_end`.
+    pub fn prefix_sum(&self, row_end: usize, column_end: usize) -> T {
+        assert!(
+            row_end <= self.rows && column_end <= self.columns,
+            "Out of bounds index."
+        );
+
+        let mut sum = T::zero();
+        let mut row_position = row_end;
+        while row_position > 0 {
+            let mut column_position = column_end;
+            while column_position > 0 {
+                sum = sum.add(self.tree[self.index(row_position, column_position)]);
+                column_position -= lowest_bit(column_position);
+            }
+            row_position -= lowest_bit(row_position);
+        }
+        sum
+    }
+
+    /// Sum of the elements in the given rows and columns, by inclusion-exclusion of four prefix sums.
+    pub fn rectangle_sum(
+        &self,
+        rows: impl RangeBounds<usize>,
+        columns: impl RangeBounds<usize>,
+    ) -> T {
+        let (row_start, row_end) = bounds(rows, self.rows);
+        let (column_start, column_end) = bounds(columns, self.columns);
+
+        self.prefix_sum(row_end, column_end)
+            .sub(self.prefix_sum(row_start, column_end))
+            .sub(self.prefix_sum(row_end, column_start))
+            .add(self.prefix_sum(row_start, column_start))
+    }
+
+    fn index(&self, row_position: usize, column_position: usize) -> usize {
+        row_position * (self.columns + 1) + column_position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    fn random_range(random: &mut Random, len: usize) -> (usize, usize) {
+        let (a, b) = (random.range(0..len + 1), random.range(0..len + 1));
+        (a.min(b), a.max(b))
+    }
+
+    #[test]
+    fn can_sum_ranges() {
+        let mut tree = FenwickTree::from_vec(vec![3, 1, 4, 1, 5, 9, 2]);
+        assert_eq!(tree.prefix_sum(0), 0);
+        assert_eq!(tree.prefix_sum(3), 8);
+        assert_eq!(tree.range_sum(..), 25);
+        assert_eq!(tree.range_sum(2..=4), 10);
+
+        tree.add(4, -5);
+        tree.set(0, 10);
+        assert_eq!(tree.range_sum(..5), 16);
+        assert_eq!(tree.get(0), 10);
+        assert_eq!(tree.get(4), 0);
+    }
+
+    #[test]
+    fn finds_lower_bound() {
+        let tree = FenwickTree::from_vec(vec![2u32, 0, 3, 1, 4]);
+        assert_eq!(tree.lower_bound(0), Some(0));
+        assert_eq!(tree.lower_bound(1), Some(1));
+        assert_eq!(tree.lower_bound(2), Some(1));
+        assert_eq!(tree.lower_bound(3), Some(3));
+        assert_eq!(tree.lower_bound(6), Some(4));
+        assert_eq!(tree.lower_bound(10), Some(5));
+        assert_eq!(tree.lower_bound(11), None);
+        assert_eq!(FenwickTree::<u32>::new(0).lower_bound(1), None);
+    }
+
+    #[test]
+    fn handles_unsigned_and_floats() {
+        // Wrapping keeps differences of unsigned prefix sums right.
+        let mut tree = FenwickTree::<u8>::new(4);
+        tree.add(1, 200);
+        tree.add(2, 100);
+        assert_eq!(tree.range_sum(2..), 100);
+        assert_eq!(tree.range_sum(1..3), 44);
+
+        let tree = FenwickTree::from_vec(vec![0.5, 0.25, 0.125]);
+        assert_eq!(tree.range_sum(1..), 0.375);
+        assert_eq!(2.5f64.scale(4), 10.0);
+        assert_eq!(AdditiveGroup::scale(7u16, 3), 21);
+    }
+
+    #[test]
+    fn can_update_ranges() {
+        let mut points = RangeUpdateFenwickTree::new(5);
+        points.add_range(1..4, 3);
+        points.add_range(..2, -1);
+        assert_eq!(
+            (0..5).map(|index| points.get(index)).collect::<Vec<i32>>(),
+            [-1, 2, 3, 3, 0]
+        );
+
+        let mut ranges = RangeFenwickTree::new(5);
+        ranges.add_range(1..4, 3);
+        ranges.add_range(..2, -1);
+        assert_eq!(ranges.range_sum(..), 7);
+        assert_eq!(ranges.range_sum(1..3), 5);
+        assert_eq!(ranges.prefix_sum(1), -1);
+    }
+
+    #[test]
+    fn matches_loops() {
+        let mut random = Random::new(37);
+
+        for len in [0, 1, 2, 7, 64, 100] {
+            let mut values: Vec<i64> = (0..len).map(|_| random.range(0..100) as i64).collect();
+            let mut tree = FenwickTree::from_vec(values.clone());
+            let mut points = RangeUpdateFenwickTree::new(len);
+            let mut ranges = RangeFenwickTree::new(len);
+            let mut range_values = vec![0; len];
+
+            for _ in 0..500 {
+                let (start, end) = random_range(&mut random, len);
+                let delta = random.range(0..100) as i64 - 50;
+
+                if len > 0 {
+                    let index = random.range(0..len);
+                    values[index] += delta;
+                    tree.add(index, delta);
+                    assert_eq!(tree.get(index), values[index]);
+                }
+                assert_eq!(tree.range_sum(start..end), values[start..end].iter().sum());
+
+                range_values[start..end]
+                    .iter_mut()
+                    .for_each(|value| *value += delta);
+                points.add_range(start..end, delta);
+                ranges.add_range(start..end, delta);
+                let (start, end) = random_range(&mut random, len);
+                assert_eq!(
+                    ranges.range_sum(start..end),
+                    range_values[start..end].iter().sum()
+                );
+                assert!((0..len).all(|index| points.get(index) == range_values[index]));
+            }
+        }
+    }
+
+    #[test]
+    fn lower_bound_matches_linear_search() {
+        let mut random = Random::new(38);
+        let values: Vec<u64> = (0..200).map(|_| random.range(0..5) as u64).collect();
+        let tree = FenwickTree::from_vec(values.clone());
+        let total: u64 = values.iter().sum();
+
+        for target in 0..=total + 1 {
+            let expected =
+                (0..=values.len()).find(|end| values[..*end].iter().sum::<u64>() >= target);
+            assert_eq!(tree.lower_bound(target), expected);
+        }
+    }
+
+    #[test]
+    fn sums_rectangles() {
+        let mut random = Random::new(39);
+        let (rows, columns) = (9, 13);
+        let mut matrix = vec![vec![0i32; columns]; rows];
+        let mut tree = FenwickTree2D::new(rows, columns);
+
+        for _ in 0..500 {
+            let (row, column) = (random.range(0..rows), random.range(0..columns));
+            let delta = random.range(0..20) as i32 - 10;
+            matrix[row][column] += delta;
+            tree.add(row, column, delta);
+
+            let (row_start, row_end) = random_range(&mut random, rows);
+            let (column_start, column_end) = random_range(&mut random, columns);
+            let expected: i32 = matrix[row_start..row_end]
+                .iter()
+                .map(|row| row[column_start..column_end].iter().sum::<i32>())
+                .sum();
+            assert_eq!(
+                tree.rectangle_sum(row_start..row_end, column_start..column_end),
+                expected
+            );
+            assert_eq!(tree.get(row, column), matrix[row][column]);
+        }
+    }
+}
@@ -0,0 +1,621 @@
+use std::fmt::{self, Debug, Display};
+
+pub mod adjacency;
+pub mod format;
+
+pub use adjacency::{Adjacency, AdjacencyList};
+
+/// Identifier of a node. Stays valid until the node is removed, removing other nodes doesn't shift it. A node added
+/// later may reuse the slot, but with a new generation, so the old id stays invalid.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct NodeId {
+    index: usize,
+    generation: u32,
+}
+
+/// Identifier of an edge. Stays valid until the edge (or one of its nodes) is removed, like `NodeId` never for a
+/// later edge.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct EdgeId {
+    index: usize,
+    generation: u32,
+}
+
+impl NodeId {
+    /// Dense index below `Graph::node_bound`, usable to index per-node arrays. Shared with removed nodes that had
+    /// the same slot.
+    pub fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl EdgeId {
+    /// Dense index below `Graph::edge_bound`, usable to index per-edge arrays.
+    pub fn index(self) -> usize {
+        self.index
+    }
+}
+
+impl Display for NodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.index)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Directed,
+    /// Every edge connects both ways, `neighbors` lists it from both ends.
+    Undirected,
+}
+
+struct Edge<E> {
+    from: NodeId,
+    to: NodeId,
+    weight: E,
+}
+
+/// Node or edge storage. The generation counts how often the slot was emptied, ids only match the current one.
+struct Slot<T> {
+    value: Option<T>,
+    generation: u32,
+}
+
+impl<T> Slot<T> {
+    fn get(&self, generation: u32) -> Option<&T> {
+        self.value
+            .as_ref()
+            .filter(|_| self.generation == generation)
+    }
+
+    fn get_mut(&mut self, generation: u32) -> Option<&mut T> {
+        self.value
+            .as_mut()
+            .filter(|_| self.generation == generation)
+    }
+
+    fn take(&mut self, generation: u32) -> Option<T> {
+        self.get(generation)?;
+        self.generation = self.generation.wrapping_add(1);
+        self.value.take()
+    }
+}
+
+/// Stores the value in a free slot or a new one, returns its index and generation.
+fn insert_slot<T>(slots: &mut Vec<Slot<T>>, free: &mut Vec<usize>, value: T) -> (usize, u32) {
+    match free.pop() {
+        Some(index) => {
+            slots[index].value = Some(value);
+            (index, slots[index].generation)
+        }
+        None => {
+            slots.push(Slot {
+                value: Some(value),
+                generation: 0,
+            });
+            (slots.len() - 1, 0)
+        }
+    }
+}
+
+/// Edge seen from one of its ends: for undirected graphs `from` is the node it was reached from.
+#[derive(Debug)]
+pub struct EdgeRef<'a, E> {
+    pub id: EdgeId,
+    pub from: NodeId,
+    pub to: NodeId,
+    pub weight: &'a E,
+}
+
+impl<E> Clone for EdgeRef<'_, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for EdgeRef<'_, E> {}
+
+/// Graph with node values `N` and edge weights `E`, parallel edges and self loops allowed.
+///
+/// Nodes and edges live in slot vectors indexed by their ids. Removal frees the slot for reuse instead of shifting
+/// later ones, so ids of everything else stay valid, and bumps its generation, so the removed id doesn't match what
+/// takes the slot next. Which nodes each node links to is kept by the backend `A`: an
+/// `AdjacencyList` suits sparse graphs, an `AdjacencyMatrix` answers `find_edge` in O(1) for dense ones.
+pub struct Graph<N, E, A = AdjacencyList> {
+    kind: Kind,
+    nodes: Vec<Slot<N>>,
+    free_nodes: Vec<usize>,
+    edges: Vec<Slot<Edge<E>>>,
+    free_edges: Vec<usize>,
+    edge_count: usize,
+    adjacency: A,
+}
+
+impl<N, E> Graph<N, E> {
+    pub fn directed() -> Self {
+        Self::new(Kind::Directed)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(Kind::Undirected)
+    }
+}
+
+impl<N, E, A: Adjacency> Graph<N, E, A> {
+    pub fn new(kind: Kind) -> Self {
+        Self {
+            kind,
+            nodes: Vec::new(),
+            free_nodes: Vec::new(),
+            edges: Vec::new(),
+            free_edges: Vec::new(),
+            edge_count: 0,
+            adjacency: A::default(),
+        }
+    }
+
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.kind == Kind::Directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() - self.free_nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.node_count() == 0
+    }
+
+    /// Upper bound of node indices, for arrays indexed by `NodeId::index`.
+    pub fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Upper bound of edge indices, for arrays indexed by `EdgeId::index`.
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn add_node(&mut self, value: N) -> NodeId {
+        let (index, generation) = insert_slot(&mut self.nodes, &mut self.free_nodes, value);
+        let node = NodeId { index, generation };
+        self.adjacency.add_node(node);
+        node
+    }
+
+    /// Removes the node with its edges. O(edges), incoming edges aren't indexed.
+    pub fn remove_node(&mut self, node: NodeId) -> Option<N> {
+        self.node(node)?;
+
+        let incident: Vec<EdgeId> = self
+            .edges()
+            .filter(|edge| edge.from == node || edge.to == node)
+            .map(|edge| edge.id)
+            .collect();
+        for edge in incident {
+            self.remove_edge(edge);
+        }
+
+        self.adjacency.remove_node(node);
+        self.free_nodes.push(node.index);
+        self.nodes[node.index].take(node.generation)
+    }
+
+    pub fn contains_node(&self, node: NodeId) -> bool {
+        self.node(node).is_some()
+    }
+
+    pub fn node(&self, node: NodeId) -> Option<&N> {
+        self.nodes.get(node.index)?.get(node.generation)
+    }
+
+    pub fn node_mut(&mut self, node: NodeId) -> Option<&mut N> {
+        self.nodes.get_mut(node.index)?.get_mut(node.generation)
+    }
+
+    /// Adds an edge, for an `AdjacencyMatrix` there must be none between the nodes yet.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: E) -> EdgeId {
+        assert!(
+            self.contains_node(from) && self.contains_node(to),
+            "Missing node."
+        );
+        assert!(
+            A::PARALLEL_EDGES || self.adjacency.find_edge(from, to).is_none(),
+            "Parallel edges need an adjacency list."
+        );
+
+        let value = Edge { from, to, weight };
+        let (index, generation) = insert_slot(&mut self.edges, &mut self.free_edges, value);
+        let edge = EdgeId { index, generation };
+        self.edge_count += 1;
+
+        self.adjacency.add_edge(from, to, edge);
+        if self.kind == Kind::Undirected && from != to {
+            self.adjacency.add_edge(to, from, edge);
+        }
+        edge
+    }
+
+    /// Sets the weight of the edge between the nodes, adding one if there is none.
+    pub fn update_edge(&mut self, from: NodeId, to: NodeId, weight: E) -> EdgeId {
+        match self.find_edge(from, to) {
+            Some(edge) => {
+                *self.edge_mut(edge).unwrap() = weight;
+                edge
+            }
+            None => self.add_edge(from, to, weight),
+        }
+    }
+
+    pub fn remove_edge(&mut self, edge: EdgeId) -> Option<E> {
+        let Edge { from, to, weight } = self.edges.get_mut(edge.index)?.take(edge.generation)?;
+
+        self.adjacency.remove_edge(from, to, edge);
+        if self.kind == Kind::Undirected && from != to {
+            self.adjacency.remove_edge(to, from, edge);
+        }
+        self.free_edges.push(edge.index);
+        self.edge_count -= 1;
+        Some(weight)
+    }
+
+    pub fn edge(&self, edge: EdgeId) -> Option<&E> {
+        Some(&self.edges.get(edge.index)?.get(edge.generation)?.weight)
+    }
+
+    pub fn edge_mut(&mut self, edge: EdgeId) -> Option<&mut E> {
+        Some(
+            &mut self
+                .edges
+                .get_mut(edge.index)?
+                .get_mut(edge.generation)?
+                .weight,
+        )
+    }
+
+    /// Nodes the edge was added between.
+    pub fn endpoints(&self, edge: EdgeId) -> Option<(NodeId, NodeId)> {
+        let edge = self.edges.get(edge.index)?.get(edge.generation)?;
+        Some((edge.from, edge.to))
+    }
+
+    /// Some edge from `from` to `to`, in either direction for undirected graphs.
+    pub fn find_edge(&self, from: NodeId, to: NodeId) -> Option<EdgeId> {
+        if !self.contains_node(from) || !self.contains_node(to) {
+            return None;
+        }
+        self.adjacency.find_edge(from, to)
+    }
+
+    pub fn contains_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.find_edge(from, to).is_some()
+    }
+
+    /// Nodes reachable over one edge: successors for directed graphs. Parallel edges repeat a neighbor.
+    pub fn neighbors(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges_from(node).map(|edge| edge.to)
+    }
+
+    /// Edges leaving the node, every edge touching it for undirected graphs.
+    pub fn edges_from(&self, node: NodeId) -> impl Iterator<Item = EdgeRef<'_, E>> + '_ {
+        assert!(self.contains_node(node), "Missing node.");
+        self.adjacency.neighbors(node).map(move |(to, id)| EdgeRef {
+            id,
+            from: node,
+            to,
+            weight: &self.edges[id.index].get(id.generation).unwrap().weight,
+        })
+    }
+
+    /// Number of edges leaving the node, a self loop of an undirected graph counts once.
+    pub fn degree(&self, node: NodeId) -> usize {
+        self.edges_from(node).count()
+    }
+
+    pub fn node_ids(&self) -> impl Iterator<Item = NodeId> + '_ {
+        self.nodes().map(|(node, _)| node)
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> + '_ {
+        self.nodes.iter().enumerate().filter_map(|(index, slot)| {
+            let generation = slot.generation;
+            Some((NodeId { index, generation }, slot.value.as_ref()?))
+        })
+    }
+
+    /// Every edge once, in the direction it was added.
+    pub fn edges(&self) -> impl Iterator<Item = EdgeRef<'_, E>> + '_ {
+        self.edges.iter().enumerate().filter_map(|(index, slot)| {
+            let edge = slot.value.as_ref()?;
+            Some(EdgeRef {
+                id: EdgeId {
+                    index,
+                    generation: slot.generation,
+                },
+                from: edge.from,
+                to: edge.to,
+                weight: &edge.weight,
+            })
+        })
+    }
+
+    /// Neighbor lists in the input format of the `algorithm` graph functions. Removed nodes are left out and the
+    /// others renumbered `0..node_count` in id order: the first vector maps those indices back to node ids.
+    pub fn adjacency_lists(&self) -> (Vec<NodeId>, Vec<Vec<usize>>) {
+        let (nodes, indices) = self.compact_indices();
+        let lists = nodes
+            .iter()
+            .map(|node| self.neighbors(*node).map(|to| indices[to.index]).collect())
+            .collect();
+        (nodes, lists)
+    }
+
+    /// Like `adjacency_lists`, with the weight of each edge next to its target.
+    pub fn weighted_adjacency_lists(&self) -> (Vec<NodeId>, Vec<Vec<(usize, E)>>)
+    where
+        E: Clone,
+    {
+        let (nodes, indices) = self.compact_indices();
+        let lists = nodes
+            .iter()
+            .map(|node| {
+                self.edges_from(*node)
+                    .map(|edge| (indices[edge.to.index], edge.weight.clone()))
+                    .collect()
+            })
+            .collect();
+        (nodes, lists)
+    }
+
+    /// Live nodes in id order, and the position of each in that order indexed by `NodeId::index`.
+    fn compact_indices(&self) -> (Vec<NodeId>, Vec<usize>) {
+        let nodes: Vec<NodeId> = self.node_ids().collect();
+        let mut indices = vec![usize::MAX; self.node_bound()];
+        for (index, node) in nodes.iter().enumerate() {
+            indices[node.index] = index;
+        }
+        (nodes, indices)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new(self.kind);
+    }
+}
+
+impl<N: Debug, E: Debug, A: Adjacency> Debug for Graph<N, E, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let edges = || self.edges().map(|edge| (edge.from, edge.to, edge.weight));
+        f.debug_struct("Graph")
+            .field("kind", &self.kind)
+            .field(
+                "nodes",
+                &fmt::from_fn(|f| f.debug_map().entries(self.nodes()).finish()),
+            )
+            .field(
+                "edges",
+                &fmt::from_fn(|f| f.debug_list().entries(edges()).finish()),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::adjacency::AdjacencyMatrix;
+    use super::*;
+    use crate::random::Random;
+    use std::{
+        collections::BTreeSet,
+        panic::{self, AssertUnwindSafe},
+    };
+
+    fn sorted(nodes: impl Iterator<Item = NodeId>) -> Vec<NodeId> {
+        let mut nodes: Vec<_> = nodes.collect();
+        nodes.sort();
+        nodes
+    }
+
+    fn can_build_directed<A: Adjacency>() {
+        let mut graph = Graph::<&str, u32, A>::new(Kind::Directed);
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        let ab = graph.add_edge(a, b, 1);
+        graph.add_edge(b, c, 2);
+        graph.add_edge(c, c, 3);
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.node(b), Some(&"b"));
+        assert_eq!(graph.edge(ab), Some(&1));
+        assert_eq!(graph.endpoints(ab), Some((a, b)));
+        assert_eq!(graph.find_edge(a, b), Some(ab));
+        assert_eq!(graph.find_edge(b, a), None);
+        assert_eq!(sorted(graph.neighbors(c)), vec![c]);
+        assert_eq!(graph.degree(a), 1);
+
+        *graph.node_mut(a).unwrap() = "A";
+        *graph.edge_mut(ab).unwrap() = 10;
+        assert_eq!(graph.node(a), Some(&"A"));
+        assert_eq!(graph.update_edge(a, b, 11), ab);
+        assert_eq!(graph.edge(ab), Some(&11));
+    }
+
+    fn can_build_undirected<A: Adjacency>() {
+        let mut graph = Graph::<u8, (), A>::new(Kind::Undirected);
+        let a = graph.add_node(0);
+        let b = graph.add_node(1);
+        let ab = graph.add_edge(a, b, ());
+        graph.add_edge(b, b, ());
+
+        assert_eq!(graph.find_edge(b, a), Some(ab));
+        assert_eq!(sorted(graph.neighbors(a)), vec![b]);
+        assert_eq!(sorted(graph.neighbors(b)), vec![a, b]);
+        let edge = graph.edges_from(b).find(|edge| edge.id == ab).unwrap();
+        assert_eq!((edge.from, edge.to), (b, a));
+        assert_eq!(graph.edges().count(), 2);
+
+        assert_eq!(graph.remove_edge(ab), Some(()));
+        assert_eq!(graph.remove_edge(ab), None);
+        assert!(!graph.contains_edge(a, b));
+        assert_eq!(graph.degree(b), 1);
+    }
+
+    fn keeps_ids_stable<A: Adjacency>() {
+        let mut graph = Graph::<usize, usize, A>::new(Kind::Directed);
+        let nodes: Vec<_> = (0..5).map(|value| graph.add_node(value)).collect();
+        for i in 0..5 {
+            graph.add_edge(nodes[i], nodes[(i + 1) % 5], i);
+        }
+
+        assert_eq!(graph.remove_node(nodes[2]), Some(2));
+        assert_eq!(graph.remove_node(nodes[2]), None);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.node(nodes[3]), Some(&3));
+        assert_eq!(graph.neighbors(nodes[1]).count(), 0);
+        assert!(!graph.contains_node(nodes[2]));
+
+        // The freed slot is reused, others keep their ids.
+        let new = graph.add_node(7);
+        assert_eq!(new.index(), nodes[2].index());
+        assert_eq!(graph.neighbors(new).count(), 0);
+        assert_eq!(graph.node_bound(), 5);
+        assert_eq!(
+            graph.nodes().map(|(_, value)| *value).collect::<Vec<_>>(),
+            vec![0, 1, 7, 3, 4]
+        );
+    }
+
+    fn rejects_stale_ids<A: Adjacency>() {
+        let mut graph = Graph::<&str, u32, A>::new(Kind::Directed);
+        let (a, b) = (graph.add_node("a"), graph.add_node("b"));
+        let ab = graph.add_edge(a, b, 1);
+        graph.remove_node(b);
+
+        // Both new ones take the freed slots.
+        let c = graph.add_node("c");
+        let ac = graph.add_edge(a, c, 2);
+        assert_eq!((c.index(), ac.index()), (b.index(), ab.index()));
+        assert_ne!((c, ac), (b, ab));
+
+        assert!(!graph.contains_node(b));
+        assert_eq!(graph.node(b), None);
+        assert_eq!(graph.node_mut(b), None);
+        assert_eq!(graph.find_edge(a, b), None);
+        assert_eq!(graph.remove_node(b), None);
+        assert_eq!(graph.edge(ab), None);
+        assert_eq!(graph.endpoints(ab), None);
+        assert_eq!(graph.remove_edge(ab), None);
+
+        assert_eq!(graph.node(c), Some(&"c"));
+        assert_eq!(graph.find_edge(a, c), Some(ac));
+        assert_eq!(graph.edge(ac), Some(&2));
+    }
+
+    fn matches_edge_set<A: Adjacency>(kind: Kind) {
+        let mut random = Random::new(42);
+        let mut graph = Graph::<(), (), A>::new(kind);
+        let nodes: Vec<_> = (0..30).map(|_| graph.add_node(())).collect();
+        let mut expected = BTreeSet::new();
+        let key = |a: NodeId, b: NodeId| match kind {
+            Kind::Directed => (a, b),
+            Kind::Undirected => (a.min(b), a.max(b)),
+        };
+
+        for _ in 0..2000 {
+            let (a, b) = (nodes[random.range(0..30)], nodes[random.range(0..30)]);
+            match graph.find_edge(a, b) {
+                Some(edge) => {
+                    assert!(expected.remove(&key(a, b)));
+                    graph.remove_edge(edge);
+                }
+                None => {
+                    assert!(expected.insert(key(a, b)));
+                    graph.add_edge(a, b, ());
+                }
+            }
+        }
+
+        assert_eq!(graph.edge_count(), expected.len());
+        for &a in &nodes {
+            let neighbors: BTreeSet<_> = graph.neighbors(a).collect();
+            let wanted: BTreeSet<_> = nodes
+                .iter()
+                .copied()
+                .filter(|b| expected.contains(&key(a, *b)))
+                .collect();
+            assert_eq!(neighbors, wanted);
+        }
+    }
+
+    #[test]
+    fn list_backend_works() {
+        can_build_directed::<AdjacencyList>();
+        can_build_undirected::<AdjacencyList>();
+        keeps_ids_stable::<AdjacencyList>();
+        rejects_stale_ids::<AdjacencyList>();
+        matches_edge_set::<AdjacencyList>(Kind::Directed);
+        matches_edge_set::<AdjacencyList>(Kind::Undirected);
+    }
+
+    #[test]
+    fn matrix_backend_works() {
+        can_build_directed::<AdjacencyMatrix>();
+        can_build_undirected::<AdjacencyMatrix>();
+        keeps_ids_stable::<AdjacencyMatrix>();
+        rejects_stale_ids::<AdjacencyMatrix>();
+        matches_edge_set::<AdjacencyMatrix>(Kind::Directed);
+        matches_edge_set::<AdjacencyMatrix>(Kind::Undirected);
+    }
+
+    #[test]
+    fn list_allows_parallel_edges() {
+        let mut graph = Graph::directed();
+        let (a, b) = (graph.add_node(()), graph.add_node(()));
+        graph.add_edge(a, b, 1);
+        graph.add_edge(a, b, 2);
+
+        let weights: Vec<_> = graph.edges_from(a).map(|edge| *edge.weight).collect();
+        assert_eq!(weights, vec![1, 2]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Parallel edges need an adjacency list.")]
+    fn matrix_rejects_parallel_edges() {
+        let mut graph = Graph::<(), (), AdjacencyMatrix>::new(Kind::Directed);
+        let (a, b) = (graph.add_node(()), graph.add_node(()));
+        graph.add_edge(a, b, ());
+        graph.add_edge(a, b, ());
+    }
+
+    #[test]
+    fn rejected_parallel_edge_leaves_graph_intact() {
+        let mut graph = Graph::<(), (), AdjacencyMatrix>::new(Kind::Undirected);
+        let (a, b) = (graph.add_node(()), graph.add_node(()));
+        let edge = graph.add_edge(a, b, ());
+
+        let added = panic::catch_unwind(AssertUnwindSafe(|| graph.add_edge(b, a, ())));
+        assert!(added.is_err());
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.edge_bound(), 1);
+        assert_eq!(graph.find_edge(b, a), Some(edge));
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing node.")]
+    fn rejects_removed_nodes() {
+        let mut graph = Graph::undirected();
+        let a = graph.add_node(());
+        graph.remove_node(a);
+        graph.add_edge(a, a, ());
+    }
+}
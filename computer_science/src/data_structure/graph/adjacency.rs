@@ -0,0 +1,142 @@
+use super::{EdgeId, NodeId};
+use std::{iter::Copied, slice};
+
+/// Storage of which nodes each node links to, the part of a `Graph` that differs between representations.
+///
+/// The graph owns nodes and edges and calls these hooks to keep the backend in sync. It adds undirected edges in both
+/// directions and only removes nodes without edges.
+pub trait Adjacency: Default {
+    type Neighbors<'a>: Iterator<Item = (NodeId, EdgeId)>
+    where
+        Self: 'a;
+
+    /// Whether a node pair can hold several edges.
+    const PARALLEL_EDGES: bool;
+
+    /// Prepares an empty slot for the node, which may reuse the slot of a removed one.
+    fn add_node(&mut self, node: NodeId);
+
+    fn remove_node(&mut self, node: NodeId);
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, edge: EdgeId);
+
+    fn remove_edge(&mut self, from: NodeId, to: NodeId, edge: EdgeId);
+
+    /// Targets and edges leaving the node.
+    fn neighbors(&self, node: NodeId) -> Self::Neighbors<'_>;
+
+    fn find_edge(&self, from: NodeId, to: NodeId) -> Option<EdgeId>;
+}
+
+/// Outgoing edges of every node in insertion order. O(n + m) memory, `find_edge` scans the node's edges.
+#[derive(Default)]
+pub struct AdjacencyList {
+    lists: Vec<Vec<(NodeId, EdgeId)>>,
+}
+
+impl Adjacency for AdjacencyList {
+    type Neighbors<'a> = Copied<slice::Iter<'a, (NodeId, EdgeId)>>;
+
+    const PARALLEL_EDGES: bool = true;
+
+    fn add_node(&mut self, node: NodeId) {
+        if node.index == self.lists.len() {
+            self.lists.push(Vec::new());
+        }
+    }
+
+    fn remove_node(&mut self, node: NodeId) {
+        self.lists[node.index] = Vec::new();
+    }
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, edge: EdgeId) {
+        self.lists[from.index].push((to, edge));
+    }
+
+    fn remove_edge(&mut self, from: NodeId, _: NodeId, edge: EdgeId) {
+        let list = &mut self.lists[from.index];
+        let index = list.iter().position(|(_, id)| *id == edge).unwrap();
+        list.remove(index);
+    }
+
+    fn neighbors(&self, node: NodeId) -> Self::Neighbors<'_> {
+        self.lists[node.index].iter().copied()
+    }
+
+    fn find_edge(&self, from: NodeId, to: NodeId) -> Option<EdgeId> {
+        self.lists[from.index]
+            .iter()
+            .find(|(target, _)| *target == to)
+            .map(|(_, edge)| *edge)
+    }
+}
+
+type MatrixNeighbors<'a> = std::iter::FilterMap<
+    slice::Iter<'a, Option<(NodeId, EdgeId)>>,
+    fn(&Option<(NodeId, EdgeId)>) -> Option<(NodeId, EdgeId)>,
+>;
+
+/// Edge of every ordered node pair in a square table. O(n²) memory and neighbor scans, O(1) `find_edge`, at most one
+/// edge per pair.
+#[derive(Default)]
+pub struct AdjacencyMatrix {
+    size: usize,
+    // Row-major, row `from` column `to`. The target is kept next to the edge, an index alone lacks its generation.
+    cells: Vec<Option<(NodeId, EdgeId)>>,
+}
+
+impl AdjacencyMatrix {
+    fn row(&self, index: usize) -> &[Option<(NodeId, EdgeId)>] {
+        &self.cells[index * self.size..(index + 1) * self.size]
+    }
+
+    fn cell(&self, from: NodeId, to: NodeId) -> usize {
+        from.index * self.size + to.index
+    }
+}
+
+impl Adjacency for AdjacencyMatrix {
+    type Neighbors<'a> = MatrixNeighbors<'a>;
+
+    const PARALLEL_EDGES: bool = false;
+
+    fn add_node(&mut self, node: NodeId) {
+        if node.index < self.size {
+            return;
+        }
+
+        // Doubles so growing one node at a time stays amortized O(n) per node.
+        let size = (node.index + 1).max(2 * self.size);
+        let mut cells = vec![None; size * size];
+        for row in 0..self.size {
+            cells[row * size..row * size + self.size].copy_from_slice(self.row(row));
+        }
+        self.size = size;
+        self.cells = cells;
+    }
+
+    fn remove_node(&mut self, node: NodeId) {
+        // The graph removed every edge first, the row and column are already empty.
+        debug_assert!(self.row(node.index).iter().all(Option::is_none));
+    }
+
+    fn add_edge(&mut self, from: NodeId, to: NodeId, edge: EdgeId) {
+        // The graph rejected parallel edges before changing anything.
+        let cell = self.cell(from, to);
+        debug_assert!(self.cells[cell].is_none());
+        self.cells[cell] = Some((to, edge));
+    }
+
+    fn remove_edge(&mut self, from: NodeId, to: NodeId, _: EdgeId) {
+        let cell = self.cell(from, to);
+        self.cells[cell] = None;
+    }
+
+    fn neighbors(&self, node: NodeId) -> Self::Neighbors<'_> {
+        self.row(node.index).iter().filter_map(|cell| *cell)
+    }
+
+    fn find_edge(&self, from: NodeId, to: NodeId) -> Option<EdgeId> {
+        self.cells[self.cell(from, to)].map(|(_, edge)| edge)
+    }
+}
@@ -0,0 +1,625 @@
+use super::{Adjacency, Graph, Kind, NodeId};
+use crate::data_structure::hash_map::OpenHashMap;
+use std::{
+    error::Error,
+    fmt::{self, Display, Write},
+    str::FromStr,
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Maps textual node names to the nodes created for them.
+struct Names<'a> {
+    nodes: OpenHashMap<&'a str, NodeId>,
+}
+
+impl<'a> Names<'a> {
+    fn new() -> Self {
+        Self {
+            nodes: OpenHashMap::new(),
+        }
+    }
+
+    /// Node named `name`, added with the value parsed from `label` the first time.
+    fn node<N: FromStr, E, A: Adjacency>(
+        &mut self,
+        graph: &mut Graph<N, E, A>,
+        name: &'a str,
+        label: &str,
+        line: usize,
+    ) -> Result<NodeId, ParseError> {
+        if let Some(node) = self.nodes.get(&name) {
+            return Ok(*node);
+        }
+
+        let value = label
+            .parse()
+            .map_err(|_| ParseError::new(line, format!("invalid node `{}`", label)))?;
+        let node = graph.add_node(value);
+        self.nodes.insert(name, node);
+        Ok(node)
+    }
+}
+
+/// Adds the edge unless the backend can't hold another one between the nodes.
+fn add_edge<N, E, A: Adjacency>(
+    graph: &mut Graph<N, E, A>,
+    from: NodeId,
+    to: NodeId,
+    weight: E,
+    line: usize,
+) -> Result<(), ParseError> {
+    if !A::PARALLEL_EDGES && graph.contains_edge(from, to) {
+        return Err(ParseError::new(line, "duplicate edge"));
+    }
+    graph.add_edge(from, to, weight);
+    Ok(())
+}
+
+fn parse_weight<E: FromStr + Default>(weight: Option<&str>, line: usize) -> Result<E, ParseError> {
+    match weight {
+        Some(weight) => weight
+            .parse()
+            .map_err(|_| ParseError::new(line, format!("invalid weight `{}`", weight))),
+        None => Ok(E::default()),
+    }
+}
+
+impl<N: FromStr, E: FromStr + Default, A: Adjacency> Graph<N, E, A> {
+    /// Reads the edge list format, one item per line and `#` starting a comment:
+    ///
+    /// ```text
+    /// directed
+    /// a
+    /// a b 5
+    /// ```
+    ///
+    /// The first line is `directed` or `undirected`, then a single label declares a node and `from to [weight]` an
+    /// edge, declaring its nodes as needed. Node values are parsed from their labels and missing weights default.
+    pub fn from_edge_list(text: &str) -> Result<Self, ParseError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.split('#').next().unwrap().trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let kind = match lines.next() {
+            Some((_, "directed")) => Kind::Directed,
+            Some((_, "undirected")) => Kind::Undirected,
+            Some((line, _)) => {
+                return Err(ParseError::new(line, "expected `directed` or `undirected`"))
+            }
+            None => return Err(ParseError::new(0, "empty input")),
+        };
+
+        let mut graph = Self::new(kind);
+        let mut names = Names::new();
+        for (line, text) in lines {
+            let words: Vec<&str> = text.split_whitespace().collect();
+            match words[..] {
+                [node] => {
+                    names.node(&mut graph, node, node, line)?;
+                }
+                [from, to] | [from, to, _] => {
+                    let from = names.node(&mut graph, from, from, line)?;
+                    let to = names.node(&mut graph, to, to, line)?;
+                    let weight = parse_weight(words.get(2).copied(), line)?;
+                    add_edge(&mut graph, from, to, weight, line)?;
+                }
+                _ => return Err(ParseError::new(line, "expected a node or an edge")),
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Reads a DOT graph: node and edge statements with optional `label` (or `weight` for edges) attributes. Node
+    /// values come from their label, or their id without one. Other attributes are ignored, subgraphs aren't supported.
+    pub fn from_dot(text: &str) -> Result<Self, ParseError> {
+        DotParser::new(text)?.parse()
+    }
+}
+
+impl<N: Display, E: Display, A: Adjacency> Graph<N, E, A> {
+    /// Writes the format read by `from_edge_list`, which reads it back only if labels are unique and free of
+    /// whitespace.
+    pub fn to_edge_list(&self) -> String {
+        let mut text = String::new();
+        writeln!(
+            text,
+            "{}",
+            match self.kind() {
+                Kind::Directed => "directed",
+                Kind::Undirected => "undirected",
+            }
+        )
+        .unwrap();
+
+        for (_, value) in self.nodes() {
+            writeln!(text, "{}", value).unwrap();
+        }
+        for edge in self.edges() {
+            let (from, to) = (self.node(edge.from).unwrap(), self.node(edge.to).unwrap());
+            writeln!(text, "{} {} {}", from, to, edge.weight).unwrap();
+        }
+        text
+    }
+
+    /// DOT with node indices as ids and values as labels.
+    pub fn to_dot(&self) -> String {
+        let (keyword, operator) = match self.kind() {
+            Kind::Directed => ("digraph", "->"),
+            Kind::Undirected => ("graph", "--"),
+        };
+
+        let mut text = format!("{} {{\n", keyword);
+        for (node, value) in self.nodes() {
+            writeln!(text, "    {} [label={}];", node, quote(value)).unwrap();
+        }
+        for edge in self.edges() {
+            writeln!(
+                text,
+                "    {} {} {} [label={}];",
+                edge.from,
+                operator,
+                edge.to,
+                quote(edge.weight)
+            )
+            .unwrap();
+        }
+        text.push_str("}\n");
+        text
+    }
+}
+
+fn quote(value: impl Display) -> String {
+    let value = value.to_string();
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum Token {
+    /// Identifier, numeral or quoted string, quotes removed.
+    Id(String),
+    Symbol(&'static str),
+}
+
+struct DotParser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl DotParser {
+    fn new(text: &str) -> Result<Self, ParseError> {
+        let mut tokens = Vec::new();
+        let mut chars = text.chars().peekable();
+        let mut line = 1;
+
+        while let Some(char) = chars.next() {
+            match char {
+                '\n' => line += 1,
+                char if char.is_whitespace() => {}
+                '#' => while chars.next_if(|char| *char != '\n').is_some() {},
+                '/' if chars.next_if_eq(&'/').is_some() => {
+                    while chars.next_if(|char| *char != '\n').is_some() {}
+                }
+                '/' if chars.next_if_eq(&'*').is_some() => loop {
+                    match chars.next() {
+                        Some('*') if chars.next_if_eq(&'/').is_some() => break,
+                        Some('\n') => line += 1,
+                        Some(_) => {}
+                        None => return Err(ParseError::new(line, "unterminated comment")),
+                    }
+                },
+                '{' | '}' | '[' | ']' | ';' | ',' | '=' => {
+                    let symbol = ["{", "}", "[", "]", ";", ",", "="]
+                        .into_iter()
+                        .find(|symbol| symbol.starts_with(char))
+                        .unwrap();
+                    tokens.push((Token::Symbol(symbol), line));
+                }
+                '-' if chars.next_if_eq(&'>').is_some() => tokens.push((Token::Symbol("->"), line)),
+                '-' if chars.next_if_eq(&'-').is_some() => tokens.push((Token::Symbol("--"), line)),
+                '"' => {
+                    let mut id = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => match chars.next() {
+                                Some(char @ ('"' | '\\')) => id.push(char),
+                                Some(char) => {
+                                    id.push('\\');
+                                    id.push(char);
+                                }
+                                None => return Err(ParseError::new(line, "unterminated string")),
+                            },
+                            Some(char) => {
+                                line += (char == '\n') as usize;
+                                id.push(char);
+                            }
+                            None => return Err(ParseError::new(line, "unterminated string")),
+                        }
+                    }
+                    tokens.push((Token::Id(id), line));
+                }
+                char if char.is_alphanumeric() || matches!(char, '_' | '.' | '-') => {
+                    let mut id = String::from(char);
+                    while let Some(char) =
+                        chars.next_if(|char| char.is_alphanumeric() || matches!(char, '_' | '.'))
+                    {
+                        id.push(char);
+                    }
+                    tokens.push((Token::Id(id), line));
+                }
+                char => return Err(ParseError::new(line, format!("unexpected `{}`", char))),
+            }
+        }
+
+        Ok(Self {
+            tokens,
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position).map(|(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        match self.tokens.get(self.position).or(self.tokens.last()) {
+            Some((_, line)) => *line,
+            None => 1,
+        }
+    }
+
+    fn next_if_symbol(&mut self, symbol: &str) -> bool {
+        let found = self.peek() == Some(&Token::Symbol(symbol_of(symbol)));
+        self.position += found as usize;
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), ParseError> {
+        match self.next_if_symbol(symbol) {
+            true => Ok(()),
+            false => Err(ParseError::new(
+                self.line(),
+                format!("expected `{}`", symbol),
+            )),
+        }
+    }
+
+    fn next_id(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Id(id)) => {
+                let id = id.clone();
+                self.position += 1;
+                Some(id)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect_id(&mut self) -> Result<String, ParseError> {
+        self.next_id()
+            .ok_or_else(|| ParseError::new(self.line(), "expected an id"))
+    }
+
+    /// Any number of `[name = value, ...]` lists.
+    fn attributes(&mut self) -> Result<Vec<(String, String)>, ParseError> {
+        let mut attributes = Vec::new();
+        while self.next_if_symbol("[") {
+            while !self.next_if_symbol("]") {
+                let name = self.expect_id()?;
+                self.expect_symbol("=")?;
+                attributes.push((name, self.expect_id()?));
+                if !self.next_if_symbol(",") {
+                    self.next_if_symbol(";");
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn parse<N: FromStr, E: FromStr + Default, A: Adjacency>(
+        mut self,
+    ) -> Result<Graph<N, E, A>, ParseError> {
+        let mut keyword = self.expect_id()?;
+        if keyword == "strict" {
+            keyword = self.expect_id()?;
+        }
+        let (kind, operator) = match keyword.as_str() {
+            "digraph" => (Kind::Directed, "->"),
+            "graph" => (Kind::Undirected, "--"),
+            _ => {
+                return Err(ParseError::new(
+                    self.line(),
+                    "expected `graph` or `digraph`",
+                ))
+            }
+        };
+        self.next_id();
+        self.expect_symbol("{")?;
+
+        // Names borrow node ids from the tokens, which must outlive them.
+        let mut statements = Vec::new();
+        while !self.next_if_symbol("}") {
+            if self.peek().is_none() {
+                return Err(ParseError::new(self.line(), "expected `}`"));
+            }
+            if self.next_if_symbol(";") {
+                continue;
+            }
+
+            let line = self.line();
+            let first = self.expect_id()?;
+            if self.next_if_symbol("=") {
+                // Graph attribute.
+                self.expect_id()?;
+                continue;
+            }
+
+            let mut chain = vec![first];
+            loop {
+                if self.next_if_symbol(operator) {
+                    chain.push(self.expect_id()?);
+                } else if self.next_if_symbol(other_operator(operator)) {
+                    return Err(ParseError::new(
+                        self.line(),
+                        format!("expected `{}`", operator),
+                    ));
+                } else {
+                    break;
+                }
+            }
+            let attributes = self.attributes()?;
+
+            if chain.len() == 1 && matches!(chain[0].as_str(), "graph" | "node" | "edge") {
+                // Default attributes.
+                continue;
+            }
+            if self.peek() == Some(&Token::Symbol("{")) {
+                return Err(ParseError::new(self.line(), "subgraphs aren't supported"));
+            }
+            statements.push((line, chain, attributes));
+        }
+        if self.peek().is_some() {
+            return Err(ParseError::new(
+                self.line(),
+                "unexpected text after the graph",
+            ));
+        }
+
+        let mut graph = Graph::new(kind);
+        let mut names = Names::new();
+        let attribute = |attributes: &[(String, String)], wanted: &[&str]| {
+            attributes
+                .iter()
+                .find(|(name, _)| wanted.contains(&name.as_str()))
+                .map(|(_, value)| value.clone())
+        };
+
+        // Nodes first so labels of declared nodes win over ids used in earlier edges.
+        for (line, chain, attributes) in &statements {
+            if let [node] = &chain[..] {
+                let label = attribute(attributes, &["label"]).unwrap_or_else(|| node.clone());
+                names.node(&mut graph, node, &label, *line)?;
+            }
+        }
+        for (line, chain, attributes) in &statements {
+            if chain.len() < 2 {
+                continue;
+            }
+            let weight = attribute(attributes, &["label", "weight"]);
+            for pair in chain.windows(2) {
+                let from = names.node(&mut graph, &pair[0], &pair[0], *line)?;
+                let to = names.node(&mut graph, &pair[1], &pair[1], *line)?;
+                let weight = parse_weight(weight.as_deref(), *line)?;
+                add_edge(&mut graph, from, to, weight, *line)?;
+            }
+        }
+        Ok(graph)
+    }
+}
+
+fn symbol_of(symbol: &str) -> &'static str {
+    ["{", "}", "[", "]", ";", ",", "=", "->", "--"]
+        .into_iter()
+        .find(|known| *known == symbol)
+        .unwrap()
+}
+
+fn other_operator(operator: &str) -> &'static str {
+    match operator {
+        "->" => "--",
+        _ => "->",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::graph::adjacency::AdjacencyMatrix;
+
+    fn describe<A: Adjacency>(graph: &Graph<String, i32, A>) -> Vec<(String, String, i32)> {
+        let mut edges: Vec<_> = graph
+            .edges()
+            .map(|edge| {
+                (
+                    graph.node(edge.from).unwrap().clone(),
+                    graph.node(edge.to).unwrap().clone(),
+                    *edge.weight,
+                )
+            })
+            .collect();
+        edges.sort();
+        edges
+    }
+
+    #[test]
+    fn parses_edge_list() {
+        let text = "
+            # Comment.
+            directed
+            lonely
+            a b 5
+            b c   # No weight.
+        ";
+        let graph: Graph<String, i32> = Graph::from_edge_list(text).unwrap();
+
+        assert!(graph.is_directed());
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(
+            describe(&graph),
+            vec![("a".into(), "b".into(), 5), ("b".into(), "c".into(), 0)]
+        );
+    }
+
+    #[test]
+    fn reports_edge_list_errors() {
+        let parse = |text| Graph::<String, i32>::from_edge_list(text).unwrap_err();
+
+        assert_eq!(parse("").line, 0);
+        assert_eq!(
+            parse("sideways\n").message,
+            "expected `directed` or `undirected`"
+        );
+        assert_eq!(
+            parse("directed\na b x\n"),
+            ParseError::new(2, "invalid weight `x`")
+        );
+        assert_eq!(parse("directed\n\na b 1 2\n").line, 3);
+        assert_eq!(
+            Graph::<u8, i32>::from_edge_list("undirected\n300\n")
+                .unwrap_err()
+                .to_string(),
+            "line 2: invalid node `300`"
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_edges_on_matrix() {
+        let edge_list = "undirected\na b\nb a\n";
+        assert_eq!(
+            Graph::<String, i32, AdjacencyMatrix>::from_edge_list(edge_list).unwrap_err(),
+            ParseError::new(3, "duplicate edge")
+        );
+        let dot = "digraph {\n a -> b;\n a -> b;\n}";
+        assert_eq!(
+            Graph::<String, i32, AdjacencyMatrix>::from_dot(dot).unwrap_err(),
+            ParseError::new(3, "duplicate edge")
+        );
+
+        // Adjacency lists keep both.
+        let graph: Graph<String, i32> = Graph::from_edge_list(edge_list).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+        let graph: Graph<String, i32> = Graph::from_dot(dot).unwrap();
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn round_trips_edge_list() {
+        let mut graph = Graph::<String, i32, AdjacencyMatrix>::new(Kind::Undirected);
+        let a = graph.add_node("a".into());
+        let b = graph.add_node("b".into());
+        graph.add_node("c".into());
+        graph.add_edge(a, b, -3);
+        graph.add_edge(b, b, 7);
+
+        let text = graph.to_edge_list();
+        assert_eq!(text, "undirected\na\nb\nc\na b -3\nb b 7\n");
+
+        let read = Graph::<String, i32, AdjacencyMatrix>::from_edge_list(&text).unwrap();
+        assert_eq!(read.kind(), Kind::Undirected);
+        assert_eq!(read.node_count(), 3);
+        assert_eq!(describe(&read), describe(&graph));
+    }
+
+    #[test]
+    fn writes_dot() {
+        let mut graph = Graph::directed();
+        let a = graph.add_node("say \"hi\"");
+        let b = graph.add_node("b");
+        graph.add_edge(a, b, 1.5);
+
+        assert_eq!(
+            graph.to_dot(),
+            "digraph {\n    0 [label=\"say \\\"hi\\\"\"];\n    1 [label=\"b\"];\n    0 -> 1 [label=\"1.5\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn parses_dot() {
+        let text = r#"
+            strict digraph routes {
+                rankdir = LR; // Graph attribute.
+                node [shape=box]
+                x [label="Start here", color=red];
+                /* Chain with a weight. */
+                x -> y -> "z z" [weight=4];
+                y -> x [label="-2"]
+                # Preprocessor-style comment.
+            }
+        "#;
+        let graph: Graph<String, i32> = Graph::from_dot(text).unwrap();
+
+        assert!(graph.is_directed());
+        assert_eq!(
+            describe(&graph),
+            vec![
+                ("Start here".into(), "y".into(), 4),
+                ("y".into(), "Start here".into(), -2),
+                ("y".into(), "z z".into(), 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_dot() {
+        let mut graph = Graph::<String, i32>::undirected();
+        let nodes: Vec<_> = ["a", "b \\ c", "d"]
+            .iter()
+            .map(|label| graph.add_node(label.to_string()))
+            .collect();
+        graph.add_edge(nodes[0], nodes[1], 1);
+        graph.add_edge(nodes[1], nodes[2], 2);
+
+        let read: Graph<String, i32> = Graph::from_dot(&graph.to_dot()).unwrap();
+        assert!(!read.is_directed());
+        assert_eq!(describe(&read), describe(&graph));
+    }
+
+    #[test]
+    fn reports_dot_errors() {
+        let parse = |text| Graph::<String, i32>::from_dot(text).unwrap_err();
+
+        assert_eq!(parse("graph { a -> b }").message, "expected `--`");
+        assert_eq!(parse("digraph {\n a -> \n}").line, 3);
+        assert_eq!(parse("digraph { a").message, "expected `}`");
+        assert_eq!(parse("tree { }").message, "expected `graph` or `digraph`");
+        assert_eq!(parse("digraph { \"a }").message, "unterminated string");
+        assert_eq!(
+            parse("digraph { a -> b [weight=x] }").message,
+            "invalid weight `x`"
+        );
+    }
+}
@@ -0,0 +1,770 @@
+use super::dynamic_array::DynamicArray;
+use std::{
+    hash::{BuildHasher, Hash, RandomState},
+    mem,
+};
+
+const MIN_CAPACITY: usize = 8;
+// Grow when more than 3/4 of the slots would be taken.
+const MAX_LOAD_NUMERATOR: usize = 3;
+const MAX_LOAD_DENOMINATOR: usize = 4;
+
+/// How the next slot is chosen when the preferred one is taken.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Probing {
+    /// home, home + 1, home + 2, ... - best cache locality, but long runs of taken slots (primary clustering).
+    Linear,
+    /// home, home + 1, home + 3, home + 6, ... (triangular numbers) - breaks up clusters, visits every slot of a
+    /// power-of-two table.
+    Quadratic,
+    /// Linear probing where an inserted entry takes the slot of any entry closer to its home ("robs the rich"),
+    /// which keeps probe lengths short and even, and lets unsuccessful lookups stop early.
+    RobinHood,
+}
+
+struct Bucket<K, V> {
+    key: K,
+    value: V,
+    hash: u64,
+    // Number of probe steps from the home slot to the slot the entry lives in.
+    distance: usize,
+}
+
+/// Probe statistics, mostly to compare the probing strategies.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Statistics {
+    /// Average number of slots inspected by a successful lookup.
+    pub average_probe_length: f64,
+    /// Largest number of probe steps between an entry's home slot and its actual slot.
+    pub max_displacement: usize,
+}
+
+/// Hash map with open addressing: entries live directly in one array of slots, collisions are resolved by probing
+/// other slots.
+///
+/// Removal is tombstone-free, following entries are shifted back into the freed slot, so lookups never have to skip
+/// over deleted markers. The slot array is a `DynamicArray` and grows by its doubling once the load factor exceeds
+/// 3/4, which keeps the capacity a power of two.
+pub struct OpenHashMap<K, V, S = RandomState> {
+    slots: DynamicArray<Option<Bucket<K, V>>>,
+    len: usize,
+    /// Upper bound on the distance of any entry, only reset when the slots are rebuilt.
+    max_distance: usize,
+    probing: Probing,
+    hash_builder: S,
+}
+
+impl<K: Hash + Eq, V> OpenHashMap<K, V, RandomState> {
+    pub fn new() -> Self {
+        Self::with_probing_and_hasher(Probing::RobinHood, RandomState::new())
+    }
+
+    pub fn with_probing(probing: Probing) -> Self {
+        Self::with_probing_and_hasher(probing, RandomState::new())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> OpenHashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_probing_and_hasher(Probing::RobinHood, hash_builder)
+    }
+
+    pub fn with_probing_and_hasher(probing: Probing, hash_builder: S) -> Self {
+        Self {
+            slots: DynamicArray::new(),
+            len: 0,
+            max_distance: 0,
+            probing,
+            hash_builder,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn probing(&self) -> Probing {
+        self.probing
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let index = self.find(key)?;
+        self.slots[index].as_ref().map(|bucket| &bucket.value)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let index = self.find(key)?;
+        self.slots[index].as_mut().map(|bucket| &mut bucket.value)
+    }
+
+    pub fn get_key_value(&self, key: &K) -> Option<(&K, &V)> {
+        let index = self.find(key)?;
+        self.slots[index]
+            .as_ref()
+            .map(|bucket| (&bucket.key, &bucket.value))
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(index) = self.find(&key) {
+            let bucket = self.slots[index].as_mut().unwrap();
+            return Some(mem::replace(&mut bucket.value, value));
+        }
+
+        self.reserve_one();
+        let hash = self.hash_builder.hash_one(&key);
+        self.insert_new(hash, key, value);
+
+        None
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    pub fn remove_entry(&mut self, key: &K) -> Option<(K, V)> {
+        let index = self.find(key)?;
+        let bucket = self.remove_at(index);
+        Some((bucket.key, bucket.value))
+    }
+
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        match self.find(&key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { map: self, index }),
+            None => {
+                let hash = self.hash_builder.hash_one(&key);
+                Entry::Vacant(VacantEntry {
+                    map: self,
+                    hash,
+                    key,
+                })
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots = DynamicArray::new();
+        self.len = 0;
+        self.max_distance = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            slots: &self.slots,
+            index: 0,
+            remaining: self.len,
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn statistics(&self) -> Statistics {
+        let mut total_probes = 0;
+        let mut max_displacement = 0;
+
+        for (_, bucket) in self.buckets() {
+            total_probes += bucket.distance + 1;
+            max_displacement = max_displacement.max(bucket.distance);
+        }
+
+        Statistics {
+            average_probe_length: match self.len {
+                0 => 0.0,
+                len => total_probes as f64 / len as f64,
+            },
+            max_displacement,
+        }
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    fn home(&self, hash: u64) -> usize {
+        hash as usize & self.mask()
+    }
+
+    /// Slot visited after `step` probes from `home`.
+    fn probe(&self, home: usize, step: usize) -> usize {
+        match self.probing {
+            Probing::Linear | Probing::RobinHood => (home + step) & self.mask(),
+            Probing::Quadratic => (home + step * (step + 1) / 2) & self.mask(),
+        }
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+
+        let hash = self.hash_builder.hash_one(key);
+        let home = self.home(hash);
+
+        for step in 0..self.slots.len() {
+            let index = self.probe(home, step);
+            let bucket = self.slots[index].as_ref()?;
+
+            // An entry closer to its home than we are to ours means ours would have taken this slot.
+            if self.probing == Probing::RobinHood && bucket.distance < step {
+                return None;
+            }
+            if bucket.hash == hash && bucket.key == *key {
+                return Some(index);
+            }
+        }
+
+        None
+    }
+
+    /// Inserts a key known to be absent, returns the slot it ended up in. Capacity has to be reserved already.
+    fn insert_new(&mut self, hash: u64, key: K, value: V) -> usize {
+        let mut bucket = Bucket {
+            key,
+            value,
+            hash,
+            distance: 0,
+        };
+        let home = self.home(hash);
+        self.len += 1;
+
+        let mut inserted_at = None;
+        let mut index = home;
+        for step in 0.. {
+            index = match self.probing {
+                Probing::RobinHood => index,
+                _ => self.probe(home, step),
+            };
+
+            match &mut self.slots[index] {
+                slot @ None => {
+                    *slot = Some(bucket);
+                    return inserted_at.unwrap_or(index);
+                }
+                Some(resident) => {
+                    // Robin Hood: the entry further from home keeps the slot, the other one moves on.
+                    if self.probing == Probing::RobinHood && resident.distance < bucket.distance {
+                        mem::swap(resident, &mut bucket);
+                        inserted_at.get_or_insert(index);
+                    }
+                }
+            }
+
+            bucket.distance += 1;
+            self.max_distance = self.max_distance.max(bucket.distance);
+            index = (index + 1) & self.mask();
+        }
+
+        unreachable!("Table is never full.")
+    }
+
+    /// Takes the entry out of the slot and closes the gap without tombstones.
+    fn remove_at(&mut self, index: usize) -> Bucket<K, V> {
+        let bucket = self.slots[index].take().unwrap();
+        self.len -= 1;
+
+        match self.probing {
+            Probing::Linear => self.shift_back_linear(index),
+            Probing::Quadratic => self.shift_back_quadratic(index),
+            Probing::RobinHood => self.shift_back_robin_hood(index),
+        }
+
+        bucket
+    }
+
+    /// Moves following entries into the hole as long as the hole lies on their probe path (Knuth's algorithm R).
+    fn shift_back_linear(&mut self, mut hole: usize) {
+        let mut index = (hole + 1) & self.mask();
+
+        while let Some(bucket) = &self.slots[index] {
+            let gap = index.wrapping_sub(hole) & self.mask();
+            if bucket.distance >= gap {
+                let mut bucket = self.slots[index].take().unwrap();
+                bucket.distance -= gap;
+                self.slots[hole] = Some(bucket);
+                hole = index;
+            }
+            index = (index + 1) & self.mask();
+        }
+    }
+
+    /// Robin Hood keeps runs ordered by distance, so everything up to the next empty or home slot shifts back by one.
+    fn shift_back_robin_hood(&mut self, mut hole: usize) {
+        loop {
+            let next = (hole + 1) & self.mask();
+            match &self.slots[next] {
+                Some(bucket) if bucket.distance > 0 => {
+                    let mut bucket = self.slots[next].take().unwrap();
+                    bucket.distance -= 1;
+                    self.slots[hole] = Some(bucket);
+                    hole = next;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Quadratic probe paths don't form contiguous runs, so any entry whose path crosses the hole before reaching its
+    /// own slot is moved into it, which opens a new hole at its old slot. Repeats until no path crosses the hole.
+    /// Every move shortens a path, so it terminates.
+    fn shift_back_quadratic(&mut self, mut hole: usize) {
+        while let Some((index, step)) = self.find_crossing_path(hole) {
+            let mut bucket = self.slots[index].take().unwrap();
+            bucket.distance = step;
+            self.slots[hole] = Some(bucket);
+            hole = index;
+        }
+    }
+
+    /// A path crossing `hole` at `step` starts `step * (step + 1) / 2` slots before it, so only the paths from those
+    /// homes up to the largest distance are walked: O(d²) for a largest distance d instead of a scan of the table.
+    fn find_crossing_path(&self, hole: usize) -> Option<(usize, usize)> {
+        for step in 0..self.max_distance {
+            let home = hole.wrapping_sub(step * (step + 1) / 2) & self.mask();
+
+            for distance in step + 1..=self.max_distance {
+                // Paths never run through an empty slot, so no entry from this home lies further along.
+                let Some(bucket) = &self.slots[self.probe(home, distance)] else {
+                    break;
+                };
+                if bucket.distance == distance && self.home(bucket.hash) == home {
+                    return Some((self.probe(home, distance), step));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Makes room for one more entry, growing the table once the load factor would be exceeded.
+    fn reserve_one(&mut self) {
+        if (self.len + 1) * MAX_LOAD_DENOMINATOR <= self.slots.len() * MAX_LOAD_NUMERATOR {
+            return;
+        }
+
+        let capacity = (self.slots.len() * 2).max(MIN_CAPACITY);
+        let old_slots = mem::replace(&mut self.slots, Self::empty_slots(capacity));
+        self.len = 0;
+        self.max_distance = 0;
+
+        for bucket in old_slots.into_iter().flatten() {
+            self.insert_new(bucket.hash, bucket.key, bucket.value);
+        }
+    }
+
+    /// Pushing into an empty `DynamicArray` doubles its capacity along the way, the slot count stays a power of two.
+    fn empty_slots(capacity: usize) -> DynamicArray<Option<Bucket<K, V>>> {
+        let mut slots = DynamicArray::new();
+        for _ in 0..capacity {
+            slots.push(None);
+        }
+        slots
+    }
+
+    fn buckets(&self) -> impl Iterator<Item = (usize, &Bucket<K, V>)> {
+        (0..self.slots.len())
+            .filter_map(|index| self.slots[index].as_ref().map(|bucket| (index, bucket)))
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher + Default> Default for OpenHashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher + Default> FromIterator<(K, V)> for OpenHashMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> Extend<(K, V)> for OpenHashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V, S>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> Entry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => &entry.key,
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, modify: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            modify(entry.get_mut());
+        }
+        self
+    }
+}
+
+pub struct OccupiedEntry<'a, K, V, S> {
+    map: &'a mut OpenHashMap<K, V, S>,
+    index: usize,
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> OccupiedEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.bucket().key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.bucket().value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.map.slots[self.index].as_mut().unwrap().value
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.map.slots[self.index].as_mut().unwrap().value
+    }
+
+    /// Replaces the value, returns the old one.
+    pub fn insert(&mut self, value: V) -> V {
+        mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.map.remove_at(self.index).value
+    }
+
+    fn bucket(&self) -> &Bucket<K, V> {
+        self.map.slots[self.index].as_ref().unwrap()
+    }
+}
+
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut OpenHashMap<K, V, S>,
+    hash: u64,
+    key: K,
+}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.reserve_one();
+        let index = self.map.insert_new(self.hash, self.key, value);
+        &mut self.map.slots[index].as_mut().unwrap().value
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    slots: &'a DynamicArray<Option<Bucket<K, V>>>,
+    index: usize,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining > 0 {
+            let slots = self.slots;
+            let slot = &slots[self.index];
+            self.index += 1;
+
+            if let Some(bucket) = slot {
+                self.remaining -= 1;
+                return Some((&bucket.key, &bucket.value));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<'a, K: Hash + Eq, V, S: BuildHasher> IntoIterator for &'a OpenHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::{collections::HashMap, hash::BuildHasherDefault, hash::DefaultHasher};
+
+    const ALL_PROBINGS: [Probing; 3] = [Probing::Linear, Probing::Quadratic, Probing::RobinHood];
+
+    /// Checks that every entry is reachable from its home slot with the recorded number of probes, and that no probe
+    /// path runs through an empty slot (which lookups would stop at).
+    fn check_invariants<K: Hash + Eq, V, S: BuildHasher>(map: &OpenHashMap<K, V, S>) {
+        assert_eq!(map.buckets().count(), map.len());
+
+        for (index, bucket) in map.buckets() {
+            let home = map.home(bucket.hash);
+            assert_eq!(map.probe(home, bucket.distance), index, "Wrong distance.");
+            for step in 0..bucket.distance {
+                let slot = &map.slots[map.probe(home, step)];
+                assert!(slot.is_some(), "Probe path crosses an empty slot.");
+                if map.probing == Probing::RobinHood {
+                    assert!(
+                        slot.as_ref().unwrap().distance >= step,
+                        "Robin Hood order violated."
+                    );
+                }
+            }
+            assert!(map.find(&bucket.key) == Some(index));
+        }
+    }
+
+    #[test]
+    fn can_insert_get() {
+        for probing in ALL_PROBINGS {
+            let mut map = OpenHashMap::with_probing(probing);
+            assert_eq!(map.insert("one", 1), None);
+            assert_eq!(map.insert("two", 2), None);
+            assert_eq!(map.insert("one", 11), Some(1));
+
+            assert_eq!(map.len(), 2);
+            assert_eq!(map.get(&"one"), Some(&11));
+            assert_eq!(map.get(&"three"), None);
+            *map.get_mut(&"two").unwrap() += 20;
+            assert_eq!(map.get_key_value(&"two"), Some((&"two", &22)));
+        }
+    }
+
+    #[test]
+    fn can_remove() {
+        for probing in ALL_PROBINGS {
+            let mut map = OpenHashMap::with_probing(probing);
+            for key in 0..100 {
+                map.insert(key, key * 2);
+            }
+
+            for key in (0..100).step_by(3) {
+                assert_eq!(map.remove(&key), Some(key * 2));
+                check_invariants(&map);
+            }
+            assert_eq!(map.remove(&0), None);
+            assert_eq!(map.len(), 66);
+            assert!((0..100).all(|key| map.contains_key(&key) == (key % 3 != 0)));
+        }
+    }
+
+    #[test]
+    fn grows_with_load_factor() {
+        let mut map = OpenHashMap::new();
+        assert_eq!(map.capacity(), 0);
+
+        map.insert(0, ());
+        assert_eq!(map.capacity(), 8);
+        for key in 1..6 {
+            map.insert(key, ());
+        }
+        assert_eq!(map.capacity(), 8);
+        map.insert(6, ());
+        assert_eq!(map.capacity(), 16);
+
+        for key in 7..1000 {
+            map.insert(key, ());
+        }
+        assert!(map.capacity().is_power_of_two());
+        assert!(map.len() * 4 <= map.capacity() * 3);
+        check_invariants(&map);
+    }
+
+    #[test]
+    fn handles_colliding_hashes() {
+        // Every key hashes to the same value, so everything piles up behind one home slot.
+        #[derive(Default, Clone)]
+        struct ConstantHasher;
+        impl std::hash::Hasher for ConstantHasher {
+            fn finish(&self) -> u64 {
+                7
+            }
+            fn write(&mut self, _: &[u8]) {}
+        }
+
+        for probing in ALL_PROBINGS {
+            let mut map = OpenHashMap::with_probing_and_hasher(
+                probing,
+                BuildHasherDefault::<ConstantHasher>::default(),
+            );
+            for key in 0..40 {
+                map.insert(key, key);
+            }
+            for key in (0..40).step_by(2) {
+                assert_eq!(map.remove(&key), Some(key));
+            }
+
+            check_invariants(&map);
+            // Shifting back keeps the survivors packed right behind the home slot.
+            assert_eq!(map.statistics().max_displacement, 19);
+            assert!((0..40).all(|key| map.get(&key) == (key % 2 == 1).then_some(&key)));
+        }
+    }
+
+    #[test]
+    fn uses_entry_api() {
+        let mut map = OpenHashMap::new();
+        for word in "a b a c b a".split(' ') {
+            *map.entry(word).or_insert(0) += 1;
+        }
+        assert_eq!(map.get(&"a"), Some(&3));
+        assert_eq!(map.get(&"c"), Some(&1));
+
+        map.entry("c").and_modify(|count| *count *= 10).or_default();
+        map.entry("d").and_modify(|count| *count *= 10).or_default();
+        assert_eq!(map.get(&"c"), Some(&10));
+        assert_eq!(map.get(&"d"), Some(&0));
+
+        match map.entry("b") {
+            Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+            Entry::Vacant(_) => panic!("Entry should be occupied."),
+        }
+        match map.entry("b") {
+            Entry::Occupied(_) => panic!("Entry should be vacant."),
+            Entry::Vacant(entry) => assert_eq!(entry.key(), &"b"),
+        }
+        assert_eq!(map.len(), 3);
+    }
+
+    #[test]
+    fn iterates_all_entries() {
+        let map: OpenHashMap<i32, i32> = (0..50).map(|key| (key, key * key)).collect();
+
+        let mut entries: Vec<(i32, i32)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        entries.sort();
+        assert_eq!(
+            entries,
+            (0..50).map(|key| (key, key * key)).collect::<Vec<_>>()
+        );
+        assert_eq!(map.iter().len(), 50);
+        assert_eq!(map.keys().count(), 50);
+        assert_eq!(
+            map.values().sum::<i32>(),
+            (0..50).map(|key| key * key).sum()
+        );
+    }
+
+    #[test]
+    fn handles_owned_values() {
+        let mut map = OpenHashMap::new();
+        for key in 0..200 {
+            map.insert(key.to_string(), vec![key; 2]);
+        }
+        for key in (0..200).step_by(2) {
+            assert_eq!(map.remove(&key.to_string()), Some(vec![key; 2]));
+        }
+
+        assert_eq!(map.len(), 100);
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&"1".to_string()), None);
+    }
+
+    #[test]
+    fn robin_hood_keeps_probes_short() {
+        // Fixed hasher keeps the comparison deterministic.
+        let statistics = |probing| {
+            let mut map = OpenHashMap::with_probing_and_hasher(
+                probing,
+                BuildHasherDefault::<DefaultHasher>::default(),
+            );
+            // 1500 entries in 2048 slots, load factor ~0.73.
+            for key in 0..1500 {
+                map.insert(key, ());
+            }
+            map.statistics()
+        };
+
+        let linear = statistics(Probing::Linear);
+        let robin_hood = statistics(Probing::RobinHood);
+
+        // Same slots are taken, only distributed differently, so the average matches and the worst case shrinks.
+        assert!((linear.average_probe_length - robin_hood.average_probe_length).abs() < 1e-9);
+        assert!(robin_hood.max_displacement < linear.max_displacement);
+        assert!(robin_hood.average_probe_length < 3.0);
+    }
+
+    #[test]
+    fn matches_hash_map_on_random_operations() {
+        for probing in ALL_PROBINGS {
+            let mut random = Random::new(30);
+            let mut map = OpenHashMap::with_probing(probing);
+            let mut oracle = HashMap::new();
+
+            for step in 0..20_000 {
+                let key = random.range(0..1000);
+                match random.range(0..3) {
+                    0 | 1 => assert_eq!(map.insert(key, step), oracle.insert(key, step)),
+                    _ => assert_eq!(map.remove(&key), oracle.remove(&key)),
+                }
+
+                assert_eq!(map.get(&key), oracle.get(&key));
+                if step % 1000 == 0 {
+                    check_invariants(&map);
+                }
+            }
+
+            check_invariants(&map);
+            assert_eq!(map.len(), oracle.len());
+            assert!(oracle
+                .iter()
+                .all(|(key, value)| map.get(key) == Some(value)));
+        }
+    }
+}
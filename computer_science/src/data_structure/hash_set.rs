@@ -0,0 +1,336 @@
+use super::hash_map::{self, OpenHashMap};
+use std::{
+    hash::{BuildHasher, Hash, RandomState},
+    iter::Chain,
+};
+
+/// Hash set as an `OpenHashMap` with unit values.
+///
+/// Set operations return lazy iterators borrowing both sets, collect them to get a new set.
+pub struct HashSet<T, S = RandomState> {
+    map: OpenHashMap<T, (), S>,
+}
+
+impl<T: Hash + Eq> HashSet<T, RandomState> {
+    pub fn new() -> Self {
+        Self {
+            map: OpenHashMap::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> HashSet<T, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: OpenHashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.map.contains_key(value)
+    }
+
+    pub fn get(&self, value: &T) -> Option<&T> {
+        self.map.get_key_value(value).map(|(value, _)| value)
+    }
+
+    /// Adds a value, returns whether it was newly inserted.
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Removes a value, returns whether it was present.
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take(&mut self, value: &T) -> Option<T> {
+        self.map.remove_entry(value).map(|(value, _)| value)
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            iter: self.map.iter(),
+        }
+    }
+
+    /// Values in `self` or `other`, each once.
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, S> {
+        Union {
+            iter: self.iter().chain(other.difference(self)),
+        }
+    }
+
+    /// Values in both `self` and `other`.
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, S> {
+        // Probing the larger set keeps the iteration short.
+        let (smaller, larger) = match self.len() <= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+
+        Intersection {
+            iter: smaller.iter(),
+            other: larger,
+        }
+    }
+
+    /// Values in `self` but not in `other`.
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, S> {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Values in exactly one of `self` and `other`.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, S> {
+        SymmetricDifference {
+            iter: self.difference(other).chain(other.difference(self)),
+        }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher + Default> Default for HashSet<T, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher + Default> FromIterator<T> for HashSet<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Hash + Eq, S: BuildHasher> Extend<T> for HashSet<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct Iter<'a, T> {
+    iter: hash_map::Iter<'a, T, ()>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+pub struct Union<'a, T, S> {
+    iter: Chain<Iter<'a, T>, Difference<'a, T, S>>,
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> Iterator for Union<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+pub struct Intersection<'a, T, S> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> Iterator for Intersection<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|value| self.other.contains(value))
+    }
+}
+
+pub struct Difference<'a, T, S> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> Iterator for Difference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|value| !self.other.contains(value))
+    }
+}
+
+pub struct SymmetricDifference<'a, T, S> {
+    iter: Chain<Difference<'a, T, S>, Difference<'a, T, S>>,
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> Iterator for SymmetricDifference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::collections::HashSet as StdHashSet;
+
+    fn sorted<'a>(values: impl Iterator<Item = &'a u32>) -> Vec<u32> {
+        let mut values: Vec<u32> = values.copied().collect();
+        values.sort();
+        values
+    }
+
+    fn random_sets(
+        random: &mut Random,
+        size: usize,
+        bound: u32,
+    ) -> (HashSet<u32>, StdHashSet<u32>) {
+        let values: Vec<u32> = (0..size)
+            .map(|_| random.range(0..bound as usize) as u32)
+            .collect();
+        (
+            values.iter().copied().collect(),
+            values.into_iter().collect(),
+        )
+    }
+
+    #[test]
+    fn can_insert_remove() {
+        let mut set = HashSet::new();
+        assert!(set.insert("a"));
+        assert!(set.insert("b"));
+        assert!(!set.insert("a"));
+
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&"a"));
+        assert!(!set.contains(&"c"));
+
+        assert!(set.remove(&"a"));
+        assert!(!set.remove(&"a"));
+        assert_eq!(set.take(&"b"), Some("b"));
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn can_combine_sets() {
+        let a: HashSet<u32> = [1, 2, 3, 4].into_iter().collect();
+        let b: HashSet<u32> = [3, 4, 5].into_iter().collect();
+
+        assert_eq!(sorted(a.union(&b)), [1, 2, 3, 4, 5]);
+        assert_eq!(sorted(a.intersection(&b)), [3, 4]);
+        assert_eq!(sorted(b.intersection(&a)), [3, 4]);
+        assert_eq!(sorted(a.difference(&b)), [1, 2]);
+        assert_eq!(sorted(b.difference(&a)), [5]);
+        assert_eq!(sorted(a.symmetric_difference(&b)), [1, 2, 5]);
+
+        let empty = HashSet::new();
+        assert_eq!(sorted(a.union(&empty)), [1, 2, 3, 4]);
+        assert_eq!(a.intersection(&empty).next(), None);
+    }
+
+    #[test]
+    fn can_compare_sets() {
+        let a: HashSet<u32> = [1, 2, 3].into_iter().collect();
+        let b: HashSet<u32> = [1, 2].into_iter().collect();
+        let c: HashSet<u32> = [7, 8].into_iter().collect();
+        let empty = HashSet::new();
+
+        assert!(b.is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(a.is_superset(&b));
+        assert!(a.is_subset(&a));
+        assert!(empty.is_subset(&a));
+
+        assert!(a.is_disjoint(&c));
+        assert!(!a.is_disjoint(&b));
+        assert!(empty.is_disjoint(&empty));
+    }
+
+    #[test]
+    fn can_extend() {
+        let mut set: HashSet<String> = ["x", "y"].iter().map(|value| value.to_string()).collect();
+        set.extend(["y", "z"].iter().map(|value| value.to_string()));
+
+        assert_eq!(set.len(), 3);
+        assert_eq!(set.get(&"z".to_string()), Some(&"z".to_string()));
+        assert_eq!(set.iter().len(), 3);
+    }
+
+    #[test]
+    fn matches_std_hash_set() {
+        let mut random = Random::new(32);
+
+        for round in 0..200 {
+            let bound = 10 + round;
+            let (size_a, size_b) = (random.range(0..60), random.range(0..60));
+            let (a, std_a) = random_sets(&mut random, size_a, bound);
+            let (b, std_b) = random_sets(&mut random, size_b, bound);
+
+            assert_eq!(a.len(), std_a.len());
+            assert_eq!(sorted(a.iter()), sorted(std_a.iter()));
+            assert_eq!(sorted(a.union(&b)), sorted(std_a.union(&std_b)));
+            assert_eq!(
+                sorted(a.intersection(&b)),
+                sorted(std_a.intersection(&std_b))
+            );
+            assert_eq!(sorted(a.difference(&b)), sorted(std_a.difference(&std_b)));
+            assert_eq!(
+                sorted(a.symmetric_difference(&b)),
+                sorted(std_a.symmetric_difference(&std_b))
+            );
+            assert_eq!(a.is_subset(&b), std_a.is_subset(&std_b));
+            assert_eq!(a.is_superset(&b), std_a.is_superset(&std_b));
+            assert_eq!(a.is_disjoint(&b), std_a.is_disjoint(&std_b));
+
+            // Subsets are rare among random sets, check a guaranteed one too.
+            let subset: HashSet<u32> = a.intersection(&b).copied().collect();
+            assert!(subset.is_subset(&a) && subset.is_subset(&b));
+        }
+    }
+}
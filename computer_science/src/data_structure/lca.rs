@@ -0,0 +1,208 @@
+use super::sparse_table::SparseTable;
+
+// Tour entries as (depth, node), the minimum of a tour range is its shallowest node.
+type TourEntry = (usize, usize);
+type TourTable = SparseTable<TourEntry, fn(&TourEntry, &TourEntry) -> TourEntry>;
+
+/// Lowest common ancestor queries on a rooted tree in O(1), after O(n log n) preprocessing.
+///
+/// The Euler tour lists nodes as a DFS enters and returns to them. Between the first visits of two nodes the tour
+/// passes their lowest common ancestor but nothing above it, so the LCA is the shallowest node in that range: a range
+/// minimum query on a `SparseTable`.
+pub struct Lca {
+    root: usize,
+    depth: Vec<usize>,
+    // Position of every node's first visit in the tour.
+    first_visit: Vec<usize>,
+    tour: TourTable,
+}
+
+impl Lca {
+    /// Builds from the parent of every node, `None` for the root.
+    pub fn from_parents(parents: &[Option<usize>]) -> Self {
+        let mut children = vec![Vec::new(); parents.len()];
+        let mut roots = Vec::new();
+        for (node, parent) in parents.iter().enumerate() {
+            match parent {
+                Some(parent) => children[*parent].push(node),
+                None => roots.push(node),
+            }
+        }
+        assert!(roots.len() == 1, "Expected exactly one root.");
+
+        Self::from_children(&children, roots[0])
+    }
+
+    /// Builds from the children of every node, starting at `root`.
+    pub fn from_children(children: &[Vec<usize>], root: usize) -> Self {
+        let len = children.len();
+        assert!(root < len, "Out of bounds index.");
+
+        let mut depth = vec![0; len];
+        let mut first_visit = vec![usize::MAX; len];
+        let mut tour = Vec::with_capacity(2 * len - 1);
+
+        // Iterative DFS, every stack entry is a node and the index of its next child to visit.
+        let mut stack = vec![(root, 0)];
+        first_visit[root] = 0;
+        tour.push((0, root));
+        while let Some((node, next_child)) = stack.last_mut() {
+            let node = *node;
+            match children[node].get(*next_child) {
+                Some(&child) => {
+                    *next_child += 1;
+                    assert!(first_visit[child] == usize::MAX, "Not a tree.");
+
+                    depth[child] = depth[node] + 1;
+                    first_visit[child] = tour.len();
+                    tour.push((depth[child], child));
+                    stack.push((child, 0));
+                }
+                None => {
+                    stack.pop();
+                    if let Some((parent, _)) = stack.last() {
+                        tour.push((depth[*parent], *parent));
+                    }
+                }
+            }
+        }
+        assert!(tour.len() == 2 * len - 1, "Not a tree.");
+
+        Self {
+            root,
+            depth,
+            first_visit,
+            tour: SparseTable::new(tour, |a, b| *a.min(b)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.depth.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.depth.is_empty()
+    }
+
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// Number of edges between the node and the root.
+    pub fn depth(&self, node: usize) -> usize {
+        self.depth[node]
+    }
+
+    /// Deepest node having both nodes in its subtree.
+    pub fn lca(&self, a: usize, b: usize) -> usize {
+        let (a, b) = (self.first_visit[a], self.first_visit[b]);
+        let (start, end) = (a.min(b), a.max(b));
+        let (_, node) = self.tour.query(start..=end).unwrap();
+        node
+    }
+
+    /// Number of edges on the path between the nodes.
+    pub fn distance(&self, a: usize, b: usize) -> usize {
+        self.depth[a] + self.depth[b] - 2 * self.depth[self.lca(a, b)]
+    }
+
+    pub fn is_ancestor(&self, ancestor: usize, node: usize) -> bool {
+        self.lca(ancestor, node) == ancestor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    /// Walks up from both nodes until they meet.
+    fn naive_lca(parents: &[Option<usize>], depth: &[usize], mut a: usize, mut b: usize) -> usize {
+        while depth[a] > depth[b] {
+            a = parents[a].unwrap();
+        }
+        while depth[b] > depth[a] {
+            b = parents[b].unwrap();
+        }
+        while a != b {
+            (a, b) = (parents[a].unwrap(), parents[b].unwrap());
+        }
+        a
+    }
+
+    #[test]
+    fn finds_lowest_common_ancestor() {
+        //        0
+        //      /   \
+        //     1     2
+        //    / \     \
+        //   3   4     5
+        //       |
+        //       6
+        let children = vec![
+            vec![1, 2],
+            vec![3, 4],
+            vec![5],
+            vec![],
+            vec![6],
+            vec![],
+            vec![],
+        ];
+        let lca = Lca::from_children(&children, 0);
+
+        assert_eq!(lca.lca(3, 6), 1);
+        assert_eq!(lca.lca(6, 3), 1);
+        assert_eq!(lca.lca(6, 5), 0);
+        assert_eq!(lca.lca(4, 6), 4);
+        assert_eq!(lca.lca(2, 2), 2);
+        assert_eq!(lca.depth(6), 3);
+        assert_eq!(lca.distance(3, 5), 4);
+        assert!(lca.is_ancestor(1, 6));
+        assert!(!lca.is_ancestor(2, 6));
+    }
+
+    #[test]
+    fn builds_from_parents() {
+        let parents = [Some(2), Some(2), None, Some(0)];
+        let lca = Lca::from_parents(&parents);
+
+        assert_eq!(lca.root(), 2);
+        assert_eq!(lca.lca(3, 1), 2);
+        assert_eq!(lca.lca(3, 0), 0);
+        assert_eq!(lca.len(), 4);
+    }
+
+    #[test]
+    fn handles_single_node() {
+        let lca = Lca::from_parents(&[None]);
+        assert_eq!(lca.lca(0, 0), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Not a tree.")]
+    fn rejects_cycles() {
+        Lca::from_children(&[vec![1], vec![2], vec![1]], 0);
+    }
+
+    #[test]
+    fn matches_naive_walk() {
+        let mut random = Random::new(38);
+
+        for len in [2, 10, 100, 1000] {
+            // Random tree, every node hangs below an earlier one.
+            let parents: Vec<Option<usize>> = (0..len)
+                .map(|node| match node {
+                    0 => None,
+                    _ => Some(random.range(0..node)),
+                })
+                .collect();
+            let lca = Lca::from_parents(&parents);
+            let depth: Vec<usize> = (0..len).map(|node| lca.depth(node)).collect();
+
+            for _ in 0..1000 {
+                let (a, b) = (random.range(0..len), random.range(0..len));
+                assert_eq!(lca.lca(a, b), naive_lca(&parents, &depth, a, b));
+            }
+        }
+    }
+}
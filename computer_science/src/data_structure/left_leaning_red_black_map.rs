@@ -0,0 +1,474 @@
+use super::ordered_map::OrderedMap;
+use std::{
+    cmp::Ordering,
+    mem,
+    ops::{Bound, RangeBounds},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Color {
+    Red,
+    Black,
+}
+
+type Link<K, V> = Option<Box<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+/// Left-leaning red-black tree (Sedgewick), an isometry of a 2-3 tree where red links always lean left.
+///
+/// The extra restriction removes most of the symmetric cases of the classic red-black tree (see `RedBlackMap`), so
+/// it can be written recursively without parent pointers, at the cost of more rotations.
+pub struct LeftLeaningRedBlackMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+}
+
+impl<K: Ord, V> LeftLeaningRedBlackMap<K, V> {
+    pub fn new() -> Self {
+        Self { root: None, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut current = self.root.as_deref();
+
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+
+        None
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut current = self.root.as_deref_mut();
+
+        while let Some(node) = current {
+            current = match key.cmp(&node.key) {
+                Ordering::Less => node.left.as_deref_mut(),
+                Ordering::Greater => node.right.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+            };
+        }
+
+        None
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(left) = node.left.as_deref() {
+            node = left;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        let mut node = self.root.as_deref()?;
+        while let Some(right) = node.right.as_deref() {
+            node = right;
+        }
+        Some((&node.key, &node.value))
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let mut previous = None;
+        let mut root = Self::insert_at(self.root.take(), key, value, &mut previous);
+        root.color = Color::Black;
+        self.root = Some(root);
+
+        if previous.is_none() {
+            self.len += 1;
+        }
+
+        previous
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        // The recursive deletion assumes the key is present.
+        if !self.contains_key(key) {
+            return None;
+        }
+
+        let mut root = self.root.take().unwrap();
+        if !is_red(&root.left) && !is_red(&root.right) {
+            root.color = Color::Red;
+        }
+
+        let mut removed = None;
+        self.root = Self::remove_at(root, key, &mut removed);
+        if let Some(root) = self.root.as_mut() {
+            root.color = Color::Black;
+        }
+
+        self.len -= 1;
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.root = None;
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Iterates over entries whose keys fall into the range, in ascending order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        // Stack holds the path of nodes whose left part is already consumed, the top is the next one to yield.
+        let mut stack = Vec::new();
+        let mut current = self.root.as_deref();
+        while let Some(node) = current {
+            let goes_left = match range.start_bound() {
+                Bound::Included(key) => node.key >= *key,
+                Bound::Excluded(key) => node.key > *key,
+                Bound::Unbounded => true,
+            };
+
+            if goes_left {
+                stack.push(node);
+                current = node.left.as_deref();
+            } else {
+                current = node.right.as_deref();
+            }
+        }
+
+        // Start past the end (e.g. `5..3`) yields nothing instead of running to the last entry.
+        let end = self.end_bound(range.end_bound());
+        if let (Some(first), Some(end)) = (stack.last(), end) {
+            if first.key > end.key {
+                stack.clear();
+            }
+        }
+
+        Iter { stack, end }
+    }
+
+    /// First node past the end bound, iteration stops when reaching it.
+    fn end_bound(&self, bound: Bound<&K>) -> Option<&Node<K, V>> {
+        let mut current = self.root.as_deref();
+        let mut candidate = None;
+
+        while let Some(node) = current {
+            let is_past_end = match bound {
+                Bound::Included(key) => node.key > *key,
+                Bound::Excluded(key) => node.key >= *key,
+                Bound::Unbounded => return None,
+            };
+
+            if is_past_end {
+                candidate = Some(node);
+                current = node.left.as_deref();
+            } else {
+                current = node.right.as_deref();
+            }
+        }
+
+        candidate
+    }
+
+    fn insert_at(link: Link<K, V>, key: K, value: V, previous: &mut Option<V>) -> Box<Node<K, V>> {
+        let Some(mut node) = link else {
+            return Box::new(Node {
+                key,
+                value,
+                color: Color::Red,
+                left: None,
+                right: None,
+            });
+        };
+
+        match key.cmp(&node.key) {
+            Ordering::Less => {
+                node.left = Some(Self::insert_at(node.left.take(), key, value, previous))
+            }
+            Ordering::Greater => {
+                node.right = Some(Self::insert_at(node.right.take(), key, value, previous))
+            }
+            Ordering::Equal => *previous = Some(mem::replace(&mut node.value, value)),
+        }
+
+        balance(node)
+    }
+
+    fn remove_at(mut node: Box<Node<K, V>>, key: &K, removed: &mut Option<V>) -> Link<K, V> {
+        if *key < node.key {
+            // Make sure the left child is not a 2-node before descending into it.
+            if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+                node = move_red_left(node);
+            }
+            node.left = Self::remove_at(node.left.take().unwrap(), key, removed);
+        } else {
+            if is_red(&node.left) {
+                node = rotate_right(node);
+            }
+
+            if *key == node.key && node.right.is_none() {
+                *removed = Some(node.value);
+                return None;
+            }
+
+            if !is_red(&node.right) && !is_red(&node.right.as_ref().unwrap().left) {
+                node = move_red_right(node);
+            }
+
+            if *key == node.key {
+                // Replace with the successor, which is removed from the right subtree instead.
+                let (right, successor) = remove_min(node.right.take().unwrap());
+                node.right = right;
+
+                let (successor_key, successor_value) = successor;
+                node.key = successor_key;
+                *removed = Some(mem::replace(&mut node.value, successor_value));
+            } else {
+                node.right = Self::remove_at(node.right.take().unwrap(), key, removed);
+            }
+        }
+
+        Some(balance(node))
+    }
+}
+
+impl<K: Ord, V> Default for LeftLeaningRedBlackMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> for LeftLeaningRedBlackMap<K, V> {
+    type Iter<'a>
+        = Iter<'a, K, V>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn range<R: RangeBounds<K>>(&self, range: R) -> Self::Iter<'_> {
+        self.range(range)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+fn is_red<K, V>(link: &Link<K, V>) -> bool {
+    link.as_ref().is_some_and(|node| node.color == Color::Red)
+}
+
+fn flip(color: Color) -> Color {
+    match color {
+        Color::Red => Color::Black,
+        Color::Black => Color::Red,
+    }
+}
+
+fn rotate_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut right = node.right.take().unwrap();
+    node.right = right.left.take();
+    right.color = node.color;
+    node.color = Color::Red;
+    right.left = Some(node);
+    right
+}
+
+fn rotate_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut left = node.left.take().unwrap();
+    node.left = left.right.take();
+    left.color = node.color;
+    node.color = Color::Red;
+    left.right = Some(node);
+    left
+}
+
+fn flip_colors<K, V>(node: &mut Node<K, V>) {
+    node.color = flip(node.color);
+    for child in [node.left.as_mut(), node.right.as_mut()]
+        .into_iter()
+        .flatten()
+    {
+        child.color = flip(child.color);
+    }
+}
+
+/// Restores the left-leaning invariants on the way up.
+fn balance<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    if is_red(&node.right) && !is_red(&node.left) {
+        node = rotate_left(node);
+    }
+    if is_red(&node.left) && is_red(&node.left.as_ref().unwrap().left) {
+        node = rotate_right(node);
+    }
+    if is_red(&node.left) && is_red(&node.right) {
+        flip_colors(&mut node);
+    }
+    node
+}
+
+fn move_red_left<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    flip_colors(&mut node);
+    if is_red(&node.right.as_ref().unwrap().left) {
+        node.right = Some(rotate_right(node.right.take().unwrap()));
+        node = rotate_left(node);
+        flip_colors(&mut node);
+    }
+    node
+}
+
+fn move_red_right<K, V>(mut node: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    flip_colors(&mut node);
+    if is_red(&node.left.as_ref().unwrap().left) {
+        node = rotate_right(node);
+        flip_colors(&mut node);
+    }
+    node
+}
+
+fn remove_min<K, V>(mut node: Box<Node<K, V>>) -> (Link<K, V>, (K, V)) {
+    if node.left.is_none() {
+        return (None, (node.key, node.value));
+    }
+
+    if !is_red(&node.left) && !is_red(&node.left.as_ref().unwrap().left) {
+        node = move_red_left(node);
+    }
+
+    let (left, min) = remove_min(node.left.take().unwrap());
+    node.left = left;
+
+    (Some(balance(node)), min)
+}
+
+pub struct Iter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+    end: Option<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = *self.stack.last()?;
+        if self.end.is_some_and(|end| std::ptr::eq(end, node)) {
+            self.stack.clear();
+            return None;
+        }
+
+        self.stack.pop();
+        let mut current = node.right.as_deref();
+        while let Some(child) = current {
+            self.stack.push(child);
+            current = child.left.as_deref();
+        }
+
+        Some((&node.key, &node.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::ordered_map::ordered_map_conformance_tests;
+    use crate::random::Random;
+
+    /// Checks BST order, left-leaning red links, no red-red and equal black height. Returns the black height.
+    fn check_invariants<K: Ord, V>(map: &LeftLeaningRedBlackMap<K, V>) -> usize {
+        fn check_node<K: Ord, V>(
+            link: &Link<K, V>,
+            lower: Option<&K>,
+            upper: Option<&K>,
+            count: &mut usize,
+        ) -> usize {
+            let Some(node) = link else {
+                return 1;
+            };
+            *count += 1;
+
+            assert!(
+                lower.is_none_or(|lower| *lower < node.key),
+                "BST order violated."
+            );
+            assert!(
+                upper.is_none_or(|upper| node.key < *upper),
+                "BST order violated."
+            );
+            assert!(!is_red(&node.right), "Red link leans right.");
+            if node.color == Color::Red {
+                assert!(!is_red(&node.left), "Red node with red child.");
+            }
+
+            let left_height = check_node(&node.left, lower, Some(&node.key), count);
+            let right_height = check_node(&node.right, Some(&node.key), upper, count);
+            assert_eq!(left_height, right_height, "Black height differs.");
+
+            left_height + usize::from(node.color == Color::Black)
+        }
+
+        assert!(!is_red(&map.root), "Red root.");
+
+        let mut count = 0;
+        let black_height = check_node(&map.root, None, None, &mut count);
+        assert_eq!(count, map.len());
+
+        black_height
+    }
+
+    #[test]
+    fn keeps_invariants_on_random_operations() {
+        let mut random = Random::new(2008);
+        let mut map = LeftLeaningRedBlackMap::new();
+
+        for step in 0..20_000 {
+            let key = random.range(0..500);
+            match random.range(0..3) {
+                0 | 1 => map.insert(key, step),
+                _ => map.remove(&key),
+            };
+            if step % 500 == 0 {
+                check_invariants(&map);
+            }
+        }
+
+        check_invariants(&map);
+        while let Some(&key) = map.first_key_value().map(|(key, _)| key) {
+            map.remove(&key);
+        }
+        assert!(map.root.is_none());
+    }
+
+    ordered_map_conformance_tests!(conformance, <K, V> LeftLeaningRedBlackMap<K, V>);
+}
@@ -1,5 +1,6 @@
 use std::{
-    alloc::{alloc, handle_alloc_error, Layout},
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    marker::PhantomData,
     ptr::NonNull,
 };
 
@@ -24,6 +25,14 @@ impl<T> LinkedList<T> {
         }
     }
 
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn push_front(&mut self, item: T) {
         self.insert(item, 0);
     }
@@ -56,10 +65,27 @@ impl<T> LinkedList<T> {
             (self.tail, None)
         } else {
             let node_after = self.node_at(index);
-            let node_before = unsafe { node_after.read().prev };
+            // Read just the pointer, a copy of the whole node would drop a duplicate of its value.
+            let node_before = unsafe { (*node_after.as_ptr()).prev };
             (node_before, Some(node_after))
         };
 
+        self.link(item, node_before, node_after);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        // Find node
+        let pointer = self.node_at(index);
+        self.unlink(pointer)
+    }
+
+    /// Creates a node between the two given neighbours (or at the head/tail).
+    fn link(
+        &mut self,
+        item: T,
+        node_before: Option<NonNull<Node<T>>>,
+        node_after: Option<NonNull<Node<T>>>,
+    ) -> NonNull<Node<T>> {
         // Create new node.
         let new_node = Node {
             value: item,
@@ -74,71 +100,140 @@ impl<T> LinkedList<T> {
         };
 
         unsafe { new_pointer.write(new_node) };
+        self.attach(new_pointer);
+
+        new_pointer
+    }
+
+    /// Takes the node out of the list and frees it.
+    fn unlink(&mut self, pointer: NonNull<Node<T>>) -> T {
+        self.detach(pointer);
+        let node = unsafe { pointer.read() };
+
+        // Free the node, its value was moved out above.
+        unsafe { dealloc(pointer.as_ptr() as *mut u8, Layout::new::<Node<T>>()) };
+
+        // Return removed value
+        node.value
+    }
+
+    /// Points the neighbours stored in the node (or head/tail) at it.
+    fn attach(&mut self, pointer: NonNull<Node<T>>) {
+        let (node_before, node_after) =
+            unsafe { ((*pointer.as_ptr()).prev, (*pointer.as_ptr()).next) };
 
         // Update pointers of surrounding nodes.
         match node_before {
-            Some(pointer) => {
-                let mut node = unsafe { pointer.read() };
-                node.next = Some(new_pointer);
-                unsafe { pointer.write(node) };
-            }
-            None => {
-                self.head = Some(new_pointer);
-            }
+            Some(before) => unsafe { (*before.as_ptr()).next = Some(pointer) },
+            None => self.head = Some(pointer),
         }
 
         match node_after {
-            Some(pointer) => {
-                let mut node = unsafe { pointer.read() };
-                node.prev = Some(new_pointer);
-                unsafe { pointer.write(node) };
-            }
-            None => {
-                self.tail = Some(new_pointer);
-            }
+            Some(after) => unsafe { (*after.as_ptr()).prev = Some(pointer) },
+            None => self.tail = Some(pointer),
         }
 
         // Update length.
         self.len += 1;
     }
 
-    pub fn remove(&mut self, index: usize) -> T {
-        // Find node
-        let node = unsafe { self.node_at(index).read() };
+    /// Connects the node's neighbours (or head/tail) to each other, the node itself is left untouched.
+    fn detach(&mut self, pointer: NonNull<Node<T>>) {
+        let (node_before, node_after) =
+            unsafe { ((*pointer.as_ptr()).prev, (*pointer.as_ptr()).next) };
 
         // Update pointers of surrounding nodes (or head/tail).
-        match node.prev {
-            Some(pointer) => {
-                let mut prev_node = unsafe { pointer.read() };
-                prev_node.next = node.next;
-                unsafe { pointer.write(prev_node) };
-            }
-            None => {
-                self.head = node.next;
-            }
+        match node_before {
+            Some(before) => unsafe { (*before.as_ptr()).next = node_after },
+            None => self.head = node_after,
         }
 
-        match node.next {
-            Some(pointer) => {
-                let mut next_node = unsafe { pointer.read() };
-                next_node.prev = node.prev;
-                unsafe { pointer.write(next_node) };
-            }
-            None => {
-                self.tail = node.prev;
-            }
-        };
+        match node_after {
+            Some(after) => unsafe { (*after.as_ptr()).prev = node_before },
+            None => self.tail = node_before,
+        }
 
         // Update length.
         self.len -= 1;
-
-        // Return removed value
-        node.value
     }
 
-    pub fn at(&self, index: usize) -> T {
+    pub fn at(&self, index: usize) -> T
+    where
+        T: Clone,
+    {
         let node = self.node_at(index);
-        unsafe { node.read().value }
+        unsafe { (*node.as_ptr()).value.clone() }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            node_pointer: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut {
+            node_pointer: self.head,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn push_front_handle(&mut self, item: T) -> NodeHandle<T> {
+        NodeHandle {
+            pointer: self.link(item, None, self.head),
+        }
+    }
+
+    pub fn push_back_handle(&mut self, item: T) -> NodeHandle<T> {
+        NodeHandle {
+            pointer: self.link(item, self.tail, None),
+        }
+    }
+
+    pub fn insert_after(&mut self, handle: NodeHandle<T>, item: T) -> NodeHandle<T> {
+        let node_after = unsafe { (*handle.pointer.as_ptr()).next };
+        NodeHandle {
+            pointer: self.link(item, Some(handle.pointer), node_after),
+        }
+    }
+
+    pub fn front_handle(&self) -> Option<NodeHandle<T>> {
+        self.head.map(|pointer| NodeHandle { pointer })
+    }
+
+    pub fn back_handle(&self) -> Option<NodeHandle<T>> {
+        self.tail.map(|pointer| NodeHandle { pointer })
+    }
+
+    pub fn next_handle(&self, handle: NodeHandle<T>) -> Option<NodeHandle<T>> {
+        unsafe { (*handle.pointer.as_ptr()).next }.map(|pointer| NodeHandle { pointer })
+    }
+
+    pub fn get(&self, handle: NodeHandle<T>) -> &T {
+        unsafe { &(*handle.pointer.as_ptr()).value }
+    }
+
+    pub fn get_mut(&mut self, handle: NodeHandle<T>) -> &mut T {
+        unsafe { &mut (*handle.pointer.as_ptr()).value }
+    }
+
+    pub fn remove_handle(&mut self, handle: NodeHandle<T>) -> T {
+        self.unlink(handle.pointer)
+    }
+
+    /// Moves the item to the front, the handle stays valid.
+    pub fn move_to_front(&mut self, handle: NodeHandle<T>) {
+        if self.head == Some(handle.pointer) {
+            return;
+        }
+
+        self.detach(handle.pointer);
+        unsafe {
+            (*handle.pointer.as_ptr()).prev = None;
+            (*handle.pointer.as_ptr()).next = self.head;
+        }
+        self.attach(handle.pointer);
     }
 
     fn node_at(&self, index: usize) -> NonNull<Node<T>> {
@@ -160,8 +255,8 @@ impl<T> LinkedList<T> {
                 return current_node.unwrap();
             }
             current_node = match search_from_tail {
-                true => unsafe { current_node.unwrap().read().prev },
-                false => unsafe { current_node.unwrap().read().next },
+                true => unsafe { (*current_node.unwrap().as_ptr()).prev },
+                false => unsafe { (*current_node.unwrap().as_ptr()).next },
             };
             current_index = match search_from_tail {
                 true => current_index - 1,
@@ -171,33 +266,85 @@ impl<T> LinkedList<T> {
     }
 }
 
+/// Position of an item in a `LinkedList`, valid until the item is removed.
+///
+/// Gives O(1) access, removal and reordering anywhere in the list, which indices can't. Like the rest of the list it is
+/// naive: using a handle after its item was removed, or with another list, is not detected.
+pub struct NodeHandle<T> {
+    pointer: NonNull<Node<T>>,
+}
+
+impl<T> Clone for NodeHandle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NodeHandle<T> {}
+
+impl<T> PartialEq for NodeHandle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pointer == other.pointer
+    }
+}
+
+impl<T> Eq for NodeHandle<T> {}
+
+impl<T> Drop for LinkedList<T> {
+    fn drop(&mut self) {
+        while self.pop_front().is_some() {}
+    }
+}
+
 impl<T> IntoIterator for LinkedList<T> {
     type Item = T;
     type IntoIter = LinkedListIterator<T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        LinkedListIterator::<T> {
-            node_pointer: self.head,
-        }
+        LinkedListIterator::<T> { list: self }
     }
 }
 
+/// Takes items from the front, whatever is left is freed together with the list.
 pub struct LinkedListIterator<T> {
-    node_pointer: Option<NonNull<Node<T>>>,
+    list: LinkedList<T>,
 }
 
 impl<T> Iterator for LinkedListIterator<T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        match self.node_pointer {
-            Some(node_pointer) => {
-                let node = unsafe { node_pointer.read() };
-                self.node_pointer = node.next;
-                Some(node.value)
-            }
-            None => None,
-        }
+        self.list.pop_front()
+    }
+}
+
+pub struct Iter<'a, T> {
+    node_pointer: Option<NonNull<Node<T>>>,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { &*self.node_pointer?.as_ptr() };
+        self.node_pointer = node.next;
+        Some(&node.value)
+    }
+}
+
+pub struct IterMut<'a, T> {
+    node_pointer: Option<NonNull<Node<T>>>,
+    marker: PhantomData<&'a mut T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = unsafe { &mut *self.node_pointer?.as_ptr() };
+        self.node_pointer = node.next;
+        Some(&mut node.value)
     }
 }
 
@@ -323,4 +470,69 @@ mod tests {
         // Pop empty
         assert_eq!(list.pop_front(), None);
     }
+
+    #[test]
+    fn can_iterate() {
+        let mut list = LinkedList::<i32>::new();
+        list.push_back(1);
+        list.push_back(2);
+        list.push_back(3);
+
+        for item in list.iter_mut() {
+            *item *= 10;
+        }
+
+        let should_be_array = [10, 20, 30];
+        assert!(list.iter().eq(should_be_array.iter()));
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn can_use_handles() {
+        let mut list = LinkedList::<i32>::new();
+        let two = list.push_back_handle(2);
+        let one = list.push_front_handle(1);
+        let four = list.push_back_handle(4);
+        let three = list.insert_after(two, 3);
+
+        assert!(list.iter().eq([1, 2, 3, 4].iter()));
+        assert!(list.front_handle() == Some(one));
+        assert!(list.back_handle() == Some(four));
+        assert!(list.next_handle(two) == Some(three));
+        assert!(list.next_handle(four).is_none());
+
+        // Move middle, then last
+        list.move_to_front(three);
+        list.move_to_front(four);
+        *list.get_mut(three) *= 10;
+        assert!(list.iter().eq([4, 30, 1, 2].iter()));
+
+        // Remove middle, then first
+        assert_eq!(list.remove_handle(one), 1);
+        assert_eq!(list.remove_handle(four), 4);
+        assert_eq!(*list.get(two), 2);
+        assert!(list.iter().eq([30, 2].iter()));
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn drops_items_once() {
+        use std::rc::Rc;
+
+        let item = Rc::new(0);
+        let mut list = LinkedList::new();
+        for _ in 0..5 {
+            list.push_back(Rc::clone(&item));
+        }
+
+        list.remove(2);
+        assert_eq!(Rc::strong_count(&item), 5);
+
+        // Partially consumed iterator drops the rest.
+        let mut iterator = list.into_iter();
+        iterator.next();
+        assert_eq!(Rc::strong_count(&item), 4);
+        drop(iterator);
+        assert_eq!(Rc::strong_count(&item), 1);
+    }
 }
@@ -0,0 +1,251 @@
+use std::ops::RangeBounds;
+
+/// Common interface of the ordered (sorted by key) maps in `data_structure`.
+///
+/// Lets algorithms, benchmarks and the conformance tests below work with any of them interchangeably.
+pub trait OrderedMap<K: Ord, V> {
+    type Iter<'a>: Iterator<Item = (&'a K, &'a V)>
+    where
+        Self: 'a,
+        K: 'a,
+        V: 'a;
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    fn get(&self, key: &K) -> Option<&V>;
+
+    /// Removes a key, returns its value if it was present.
+    fn remove(&mut self, key: &K) -> Option<V>;
+
+    /// Iterates over entries whose keys fall into the range, in ascending order. A range whose start lies past its
+    /// end yields nothing.
+    fn range<R: RangeBounds<K>>(&self, range: R) -> Self::Iter<'_>;
+
+    fn len(&self) -> usize;
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.range(..)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+/// Generates a test module running the whole `conformance` suite against an `OrderedMap` implementation.
+///
+/// The map type is given as a template over key and value types, e.g.
+/// `ordered_map_conformance_tests!(order_2, <K, V> BTreeMap<K, V, 2>);`
+#[cfg(test)]
+macro_rules! ordered_map_conformance_tests {
+    ($name:ident, <$key:ident, $value:ident> $map:ty) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::data_structure::ordered_map::conformance;
+
+            type Map<$key, $value> = $map;
+
+            #[test]
+            fn handles_empty() {
+                conformance::handles_empty::<Map<i32, i32>>();
+            }
+
+            #[test]
+            fn can_insert_get() {
+                conformance::can_insert_get::<Map<i32, i32>>();
+            }
+
+            #[test]
+            fn can_remove() {
+                conformance::can_remove::<Map<i32, i32>>();
+            }
+
+            #[test]
+            fn iterates_in_order() {
+                conformance::iterates_in_order::<Map<i32, i32>>();
+            }
+
+            #[test]
+            fn iterates_range() {
+                conformance::iterates_range::<Map<i32, i32>>();
+            }
+
+            #[test]
+            fn handles_owned_values() {
+                conformance::handles_owned_values::<Map<String, Vec<usize>>>();
+            }
+
+            #[test]
+            fn matches_btree_map() {
+                conformance::matches_btree_map::<Map<u32, u32>>();
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+pub(crate) use ordered_map_conformance_tests;
+
+/// Behaviour every `OrderedMap` implementation has to share, instantiated per implementation with
+/// `ordered_map_conformance_tests!`.
+#[cfg(test)]
+pub mod conformance {
+    use super::OrderedMap;
+    use crate::random::Random;
+    use std::{collections::BTreeMap, ops::Bound};
+
+    fn keys<'a, M: OrderedMap<i32, i32> + 'a>(iter: M::Iter<'a>) -> Vec<i32> {
+        iter.map(|(key, _)| *key).collect()
+    }
+
+    pub fn handles_empty<M: OrderedMap<i32, i32> + Default>() {
+        let mut map = M::default();
+
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+        assert_eq!(map.get(&1), None);
+        assert_eq!(map.remove(&1), None);
+        assert_eq!(map.iter().next(), None);
+        assert_eq!(map.range(0..10).next(), None);
+    }
+
+    pub fn can_insert_get<M: OrderedMap<i32, i32> + Default>() {
+        let mut map = M::default();
+
+        for key in [50, 20, 80, 10, 30, 70, 90] {
+            assert_eq!(map.insert(key, key * 10), None);
+        }
+        assert_eq!(map.insert(30, 333), Some(300));
+
+        assert_eq!(map.len(), 7);
+        assert_eq!(map.get(&30), Some(&333));
+        assert_eq!(map.get(&80), Some(&800));
+        assert_eq!(map.get(&40), None);
+        assert!(map.contains_key(&10));
+        assert!(!map.contains_key(&11));
+    }
+
+    pub fn can_remove<M: OrderedMap<i32, i32> + Default>() {
+        let mut map = M::default();
+        for key in 0..100 {
+            map.insert(key, -key);
+        }
+
+        for key in (0..100).step_by(2) {
+            assert_eq!(map.remove(&key), Some(-key));
+        }
+        assert_eq!(map.remove(&0), None);
+        assert_eq!(map.remove(&100), None);
+
+        assert_eq!(map.len(), 50);
+        assert_eq!(
+            keys::<M>(map.iter()),
+            (1..100).step_by(2).collect::<Vec<_>>()
+        );
+
+        for key in (1..100).step_by(2) {
+            assert_eq!(map.remove(&key), Some(-key));
+        }
+        assert!(map.is_empty());
+    }
+
+    pub fn iterates_in_order<M: OrderedMap<i32, i32> + Default>() {
+        let mut map = M::default();
+        for key in [5, -3, 12, 0, 7, -8, 1] {
+            map.insert(key, key * 2);
+        }
+
+        let entries: Vec<(i32, i32)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        assert_eq!(
+            entries,
+            [
+                (-8, -16),
+                (-3, -6),
+                (0, 0),
+                (1, 2),
+                (5, 10),
+                (7, 14),
+                (12, 24)
+            ]
+        );
+    }
+
+    pub fn iterates_range<M: OrderedMap<i32, i32> + Default>() {
+        let mut map = M::default();
+        for key in (0..20).step_by(2) {
+            map.insert(key, key);
+        }
+
+        assert_eq!(keys::<M>(map.range(3..9)), [4, 6, 8]);
+        assert_eq!(keys::<M>(map.range(4..8)), [4, 6]);
+        assert_eq!(keys::<M>(map.range(4..=8)), [4, 6, 8]);
+        assert_eq!(keys::<M>(map.range(..3)), [0, 2]);
+        assert_eq!(keys::<M>(map.range(..=2)), [0, 2]);
+        assert_eq!(keys::<M>(map.range(17..)), [18]);
+        assert_eq!(keys::<M>(map.range(-10..-1)), []);
+        assert_eq!(keys::<M>(map.range(30..40)), []);
+        assert_eq!(keys::<M>(map.range(6..6)), []);
+        assert_eq!(
+            keys::<M>(map.range((Bound::Excluded(4), Bound::Included(10)))),
+            [6, 8, 10]
+        );
+        assert_eq!(
+            keys::<M>(map.range((Bound::Excluded(12), Bound::Unbounded))),
+            [14, 16, 18]
+        );
+        assert_eq!(
+            keys::<M>(map.range((Bound::Included(9), Bound::Excluded(3)))),
+            []
+        );
+    }
+
+    pub fn handles_owned_values<M: OrderedMap<String, Vec<usize>> + Default>() {
+        let mut map = M::default();
+        for key in 0..100 {
+            map.insert(format!("{key:03}"), vec![key; key % 5]);
+        }
+        for key in (0..100).step_by(3) {
+            assert_eq!(map.remove(&format!("{key:03}")), Some(vec![key; key % 5]));
+        }
+        map.insert("050".to_string(), Vec::new());
+
+        assert_eq!(map.len(), 66);
+        assert_eq!(map.get(&"050".to_string()), Some(&Vec::new()));
+        assert!(map.iter().map(|(key, _)| key).is_sorted());
+    }
+
+    /// Differential test against `std::collections::BTreeMap` on a seeded random sequence of operations.
+    pub fn matches_btree_map<M: OrderedMap<u32, u32> + Default>() {
+        let mut random = Random::new(29);
+        let mut map = M::default();
+        let mut oracle = BTreeMap::new();
+
+        for step in 0..10_000 {
+            let key = random.range(0..300) as u32;
+            match random.range(0..10) {
+                0..=4 => assert_eq!(map.insert(key, step), oracle.insert(key, step)),
+                5..=7 => assert_eq!(map.remove(&key), oracle.remove(&key)),
+                8 => assert_eq!(map.get(&key), oracle.get(&key)),
+                _ => {
+                    let end = random.range(0..300) as u32;
+                    let (start, end) = (key.min(end), key.max(end));
+                    assert!(map.range(start..end).eq(oracle.range(start..end)));
+                    assert!(map.range(start..=end).eq(oracle.range(start..=end)));
+                }
+            }
+
+            assert_eq!(map.len(), oracle.len());
+            if step % 1000 == 0 {
+                assert!(map.iter().eq(oracle.iter()));
+            }
+        }
+
+        assert!(map.iter().eq(oracle.iter()));
+    }
+}
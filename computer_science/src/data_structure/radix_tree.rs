@@ -0,0 +1,434 @@
+use std::mem;
+
+struct Node<V> {
+    // Bytes on the edge leading into this node, empty only for the root.
+    prefix: Vec<u8>,
+    value: Option<V>,
+    // Sorted by the first byte of their prefixes, which are all distinct.
+    children: Vec<Node<V>>,
+}
+
+impl<V> Node<V> {
+    fn new(prefix: Vec<u8>, value: Option<V>) -> Self {
+        Self {
+            prefix,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// Index of the child whose prefix starts with `byte`, or where it would be inserted.
+    fn find_child(&self, byte: u8) -> Result<usize, usize> {
+        self.children
+            .binary_search_by_key(&byte, |child| child.prefix[0])
+    }
+
+    /// Child whose whole prefix is a prefix of `key`, with the rest of the key.
+    fn descend<'k>(&self, key: &'k [u8]) -> Option<(&Node<V>, &'k [u8])> {
+        let child = &self.children[self.find_child(*key.first()?).ok()?];
+        let rest = key.strip_prefix(child.prefix.as_slice())?;
+        Some((child, rest))
+    }
+
+    /// A node without a value and a single child is just part of an edge, so it is merged into the child.
+    fn compress(&mut self) {
+        if self.value.is_none() && self.children.len() == 1 {
+            let child = self.children.pop().unwrap();
+            self.prefix.extend(child.prefix);
+            self.value = child.value;
+            self.children = child.children;
+        }
+    }
+}
+
+/// Radix tree (compressed trie) over byte strings: like `Trie`, but chains of nodes with a single child and no value
+/// are merged into one node whose edge holds the whole byte sequence.
+///
+/// Stores at most 2n nodes for n keys, no matter how long they are, and lookups compare edge slices instead of
+/// following a pointer per byte.
+pub struct RadixTree<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> RadixTree<V> {
+    pub fn new() -> Self {
+        Self {
+            root: Node::new(Vec::new(), None),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&V> {
+        let mut node = &self.root;
+        let mut key = key.as_ref();
+        while !key.is_empty() {
+            (node, key) = node.descend(key)?;
+        }
+        node.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        let mut key = key.as_ref();
+        while let Some(byte) = key.first() {
+            let index = node.find_child(*byte).ok()?;
+            let child = &mut node.children[index];
+            key = key.strip_prefix(child.prefix.as_slice())?;
+            node = child;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: impl AsRef<[u8]>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        let mut key = key.as_ref();
+
+        while let Some(byte) = key.first() {
+            let index = match node.find_child(*byte) {
+                Ok(index) => index,
+                Err(index) => {
+                    // Nothing shares the next byte, the rest of the key becomes a new edge.
+                    node.children
+                        .insert(index, Node::new(key.to_vec(), Some(value)));
+                    self.len += 1;
+                    return None;
+                }
+            };
+
+            let child = &mut node.children[index];
+            let common = common_prefix_length(&child.prefix, key);
+            if common < child.prefix.len() {
+                // Key leaves the edge midway, split it.
+                let tail = Node {
+                    prefix: child.prefix.split_off(common),
+                    value: child.value.take(),
+                    children: mem::take(&mut child.children),
+                };
+                child.children.push(tail);
+            }
+
+            key = &key[common..];
+            node = child;
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Removes a key, returns its value if it was present. The tree is compressed again afterwards.
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> Option<V> {
+        let value = Self::remove_from(&mut self.root, key.as_ref())?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Longest key that is a prefix of `key`, with its value. E.g. the route matching a request path.
+    pub fn longest_prefix_match<'k>(&self, key: &'k [u8]) -> Option<(&'k [u8], &V)> {
+        let mut node = &self.root;
+        let mut rest = key;
+        let mut longest = node.value.as_ref().map(|value| (0, value));
+
+        while let Some((child, child_rest)) = node.descend(rest) {
+            (node, rest) = (child, child_rest);
+            if let Some(value) = &node.value {
+                longest = Some((key.len() - rest.len(), value));
+            }
+        }
+
+        longest.map(|(length, value)| (&key[..length], value))
+    }
+
+    /// Iterates over all entries in lexicographic order of keys.
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.prefix_iter([])
+    }
+
+    /// Iterates over entries whose keys start with `prefix`, in lexicographic order.
+    pub fn prefix_iter(&self, prefix: impl AsRef<[u8]>) -> Iter<'_, V> {
+        let prefix = prefix.as_ref();
+        let mut node = &self.root;
+        let mut rest = prefix;
+        let mut stack = Vec::new();
+
+        loop {
+            if rest.is_empty() {
+                stack.push((prefix.to_vec(), node));
+                break;
+            }
+
+            let Ok(index) = node.find_child(rest[0]) else {
+                break;
+            };
+            let child = &node.children[index];
+
+            if child.prefix.starts_with(rest) {
+                // Prefix ends inside the edge, the whole subtree matches.
+                let mut key = prefix[..prefix.len() - rest.len()].to_vec();
+                key.extend(&child.prefix);
+                stack.push((key, child));
+                break;
+            }
+            match rest.strip_prefix(child.prefix.as_slice()) {
+                Some(child_rest) => (node, rest) = (child, child_rest),
+                None => break,
+            }
+        }
+
+        Iter { stack }
+    }
+
+    fn remove_from(node: &mut Node<V>, key: &[u8]) -> Option<V> {
+        let Some(byte) = key.first() else {
+            return node.value.take();
+        };
+
+        let index = node.find_child(*byte).ok()?;
+        let child = &mut node.children[index];
+        let rest = key.strip_prefix(child.prefix.as_slice())?;
+        let value = Self::remove_from(child, rest)?;
+
+        if child.value.is_none() && child.children.is_empty() {
+            node.children.remove(index);
+        } else {
+            child.compress();
+        }
+        Some(value)
+    }
+}
+
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+impl<V> Default for RadixTree<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for RadixTree<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut tree = Self::new();
+        for (key, value) in iter {
+            tree.insert(key, value);
+        }
+        tree
+    }
+}
+
+/// Depth-first, the key of every node is rebuilt on the way down.
+pub struct Iter<'a, V> {
+    stack: Vec<(Vec<u8>, &'a Node<V>)>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key, node)) = self.stack.pop() {
+            // Reversed, so the smallest prefix is popped first.
+            for child in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.extend(&child.prefix);
+                self.stack.push((child_key, child));
+            }
+
+            if let Some(value) = &node.value {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{data_structure::trie::Trie, random::Random};
+    use std::collections::BTreeMap;
+
+    fn keys<'a, V: 'a>(iter: impl Iterator<Item = (Vec<u8>, &'a V)>) -> Vec<String> {
+        iter.map(|(key, _)| String::from_utf8(key).unwrap())
+            .collect()
+    }
+
+    /// Checks the compression invariants, returns the number of nodes.
+    fn check_invariants<V>(node: &Node<V>, is_root: bool) -> usize {
+        if !is_root {
+            assert!(!node.prefix.is_empty(), "Empty edge.");
+            assert!(
+                node.value.is_some() || node.children.len() >= 2,
+                "Uncompressed node."
+            );
+        }
+        assert!(
+            node.children
+                .windows(2)
+                .all(|pair| pair[0].prefix[0] < pair[1].prefix[0]),
+            "Children not sorted by distinct first bytes."
+        );
+
+        1 + node
+            .children
+            .iter()
+            .map(|child| check_invariants(child, false))
+            .sum::<usize>()
+    }
+
+    #[test]
+    fn can_insert_get() {
+        let mut tree = RadixTree::new();
+        assert_eq!(tree.insert("romane", 1), None);
+        assert_eq!(tree.insert("romanus", 2), None);
+        assert_eq!(tree.insert("romulus", 3), None);
+        assert_eq!(tree.insert("rom", 4), None);
+        assert_eq!(tree.insert("romane", 5), Some(1));
+
+        assert_eq!(tree.len(), 4);
+        assert_eq!(tree.get("romane"), Some(&5));
+        assert_eq!(tree.get("rom"), Some(&4));
+        assert_eq!(tree.get("roman"), None);
+        assert_eq!(tree.get("ro"), None);
+        assert_eq!(tree.get("romanes"), None);
+        *tree.get_mut("romulus").unwrap() += 30;
+        assert_eq!(tree.get("romulus"), Some(&33));
+
+        // root -> "rom" -> ("an" -> ("e", "us"), "ulus")
+        assert_eq!(check_invariants(&tree.root, true), 6);
+    }
+
+    #[test]
+    fn can_remove() {
+        let mut tree: RadixTree<i32> = [("test", 1), ("team", 2), ("toast", 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(check_invariants(&tree.root, true), 6);
+
+        assert_eq!(tree.remove("te"), None);
+        assert_eq!(tree.remove("tea"), None);
+        assert_eq!(tree.remove("team"), Some(2));
+
+        // "te" is left with a single child and merges into "test".
+        assert_eq!(check_invariants(&tree.root, true), 4);
+        assert_eq!(keys(tree.iter()), ["test", "toast"]);
+
+        assert_eq!(tree.remove("test"), Some(1));
+        assert_eq!(tree.remove("toast"), Some(3));
+        assert_eq!(check_invariants(&tree.root, true), 1);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn iterates_by_prefix() {
+        let tree: RadixTree<()> = ["car", "cat", "cart", "dog", "ca", "do"]
+            .into_iter()
+            .map(|key| (key, ()))
+            .collect();
+
+        assert_eq!(keys(tree.iter()), ["ca", "car", "cart", "cat", "do", "dog"]);
+        assert_eq!(keys(tree.prefix_iter("car")), ["car", "cart"]);
+        assert_eq!(keys(tree.prefix_iter("c")), ["ca", "car", "cart", "cat"]);
+        assert_eq!(keys(tree.prefix_iter("d")), ["do", "dog"]);
+        assert_eq!(keys(tree.prefix_iter("cx")), Vec::<String>::new());
+        assert_eq!(keys(tree.prefix_iter("")).len(), 6);
+    }
+
+    #[test]
+    fn finds_longest_prefix_match() {
+        let routes: RadixTree<&str> = [("/", "root"), ("/api", "api"), ("/api/users", "users")]
+            .into_iter()
+            .collect();
+
+        let route = |path: &'static str| routes.longest_prefix_match(path.as_bytes());
+        assert_eq!(route("/api/users/7"), Some((&b"/api/users"[..], &"users")));
+        assert_eq!(route("/api/user"), Some((&b"/api"[..], &"api")));
+        assert_eq!(route("/index.html"), Some((&b"/"[..], &"root")));
+        assert_eq!(route("index.html"), None);
+    }
+
+    #[test]
+    fn uses_fewer_nodes_than_trie() {
+        let words = ["interstellar", "internal", "internet", "interval", "into"];
+        let tree: RadixTree<()> = words.iter().map(|word| (word, ())).collect();
+        let trie: Trie<()> = words.iter().map(|word| (word, ())).collect();
+
+        assert!(tree
+            .iter()
+            .map(|(key, _)| key)
+            .eq(trie.iter().map(|(key, _)| key)));
+        // root, "int", "er", "o", "stellar", "n", "al", "et", "val"
+        assert_eq!(check_invariants(&tree.root, true), 9);
+    }
+
+    #[test]
+    fn matches_btree_map() {
+        let mut random = Random::new(34);
+        let mut tree = RadixTree::new();
+        let mut oracle = BTreeMap::new();
+
+        // Short keys over a small alphabet, so they share plenty of prefixes.
+        let random_key = |random: &mut Random| -> Vec<u8> {
+            let length = random.range(0..6);
+            (0..length)
+                .map(|_| b'a' + random.range(0..3) as u8)
+                .collect()
+        };
+
+        for step in 0..20_000 {
+            let key = random_key(&mut random);
+            match random.range(0..10) {
+                0..=4 => assert_eq!(tree.insert(&key, step), oracle.insert(key, step)),
+                5..=7 => assert_eq!(tree.remove(&key), oracle.remove(&key)),
+                8 => {
+                    let expected = (0..=key.len())
+                        .rev()
+                        .find_map(|length| oracle.get_key_value(&key[..length]));
+                    let found = tree.longest_prefix_match(&key);
+                    assert_eq!(
+                        found.map(|(prefix, value)| (prefix.to_vec(), value)),
+                        expected.map(|(key, value)| (key.clone(), value))
+                    );
+                }
+                _ => {
+                    let expected = oracle
+                        .range(key.clone()..)
+                        .take_while(|(other, _)| other.starts_with(&key));
+                    assert!(tree
+                        .prefix_iter(&key)
+                        .eq(expected.map(|(key, value)| (key.clone(), value))));
+                }
+            }
+
+            assert_eq!(tree.len(), oracle.len());
+            if step % 1000 == 0 {
+                assert!(check_invariants(&tree.root, true) <= 2 * tree.len() + 1);
+            }
+        }
+
+        assert!(tree
+            .iter()
+            .eq(oracle.iter().map(|(key, value)| (key.clone(), value))));
+    }
+}
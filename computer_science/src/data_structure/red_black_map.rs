@@ -0,0 +1,668 @@
+use super::ordered_map::OrderedMap;
+use std::{
+    cmp::Ordering,
+    marker::PhantomData,
+    mem,
+    ops::{Bound, RangeBounds},
+    ptr::NonNull,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Color {
+    Red,
+    Black,
+}
+
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    color: Color,
+    parent: Link<K, V>,
+    left: Link<K, V>,
+    right: Link<K, V>,
+}
+
+/// Ordered map implemented as a classic red-black tree (CLRS) with parent pointers.
+///
+/// Invariants: the root is black, a red node never has a red child and every path from a node down to its leaves
+/// contains the same number of black nodes. Together they keep the height below 2 * log2(n + 1).
+pub struct RedBlackMap<K, V> {
+    root: Link<K, V>,
+    len: usize,
+    marker: PhantomData<Box<Node<K, V>>>,
+}
+
+impl<K: Ord, V> RedBlackMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            root: None,
+            len: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key)
+            .map(|node| unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.find(key)
+            .map(|node| unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(|root| unsafe {
+            let node = Self::minimum(root).as_ptr();
+            (&(*node).key, &(*node).value)
+        })
+    }
+
+    pub fn last_key_value(&self) -> Option<(&K, &V)> {
+        self.root.map(|root| unsafe {
+            let node = Self::maximum(root).as_ptr();
+            (&(*node).key, &(*node).value)
+        })
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        // Standard BST descent, remembering where the new leaf hangs.
+        let mut parent = None;
+        let mut current = self.root;
+        let mut is_left_child = false;
+
+        while let Some(node) = current {
+            let node = unsafe { &mut *node.as_ptr() };
+            match key.cmp(&node.key) {
+                Ordering::Less => {
+                    parent = current;
+                    current = node.left;
+                    is_left_child = true;
+                }
+                Ordering::Greater => {
+                    parent = current;
+                    current = node.right;
+                    is_left_child = false;
+                }
+                Ordering::Equal => return Some(mem::replace(&mut node.value, value)),
+            }
+        }
+
+        let new_node = NonNull::from(Box::leak(Box::new(Node {
+            key,
+            value,
+            color: Color::Red,
+            parent,
+            left: None,
+            right: None,
+        })));
+
+        match parent {
+            Some(parent) => unsafe {
+                match is_left_child {
+                    true => (*parent.as_ptr()).left = Some(new_node),
+                    false => (*parent.as_ptr()).right = Some(new_node),
+                }
+            },
+            None => self.root = Some(new_node),
+        }
+
+        self.len += 1;
+        self.insert_fixup(new_node);
+
+        None
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let node = self.find(key)?;
+
+        unsafe {
+            let z = node.as_ptr();
+
+            // `x` takes the place of the node which is physically unlinked from the tree. It can be a leaf (None),
+            // so its parent has to be tracked separately.
+            let x;
+            let x_parent;
+            let removed_color;
+
+            if (*z).left.is_none() {
+                removed_color = (*z).color;
+                x = (*z).right;
+                x_parent = (*z).parent;
+                self.transplant(node, x);
+            } else if (*z).right.is_none() {
+                removed_color = (*z).color;
+                x = (*z).left;
+                x_parent = (*z).parent;
+                self.transplant(node, x);
+            } else {
+                // Two children: the successor takes over the removed node's position and colour.
+                let successor = Self::minimum((*z).right.unwrap());
+                let y = successor.as_ptr();
+                removed_color = (*y).color;
+                x = (*y).right;
+
+                if (*y).parent == Some(node) {
+                    x_parent = Some(successor);
+                } else {
+                    x_parent = (*y).parent;
+                    self.transplant(successor, x);
+                    (*y).right = (*z).right;
+                    Self::set_parent((*y).right, Some(successor));
+                }
+
+                self.transplant(node, Some(successor));
+                (*y).left = (*z).left;
+                Self::set_parent((*y).left, Some(successor));
+                (*y).color = (*z).color;
+            }
+
+            if removed_color == Color::Black {
+                self.remove_fixup(x, x_parent);
+            }
+
+            self.len -= 1;
+
+            let removed = Box::from_raw(z);
+            Some(removed.value)
+        }
+    }
+
+    pub fn clear(&mut self) {
+        Self::free(self.root.take());
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Iterates over entries whose keys fall into the range, in ascending order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        let next = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key, true),
+            Bound::Excluded(key) => self.lower_bound(key, false),
+            Bound::Unbounded => self.root.map(Self::minimum),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.lower_bound(key, false),
+            Bound::Excluded(key) => self.lower_bound(key, true),
+            Bound::Unbounded => None,
+        };
+
+        // Start past the end (e.g. `5..3`) yields nothing instead of running to the last entry.
+        let next = match (next, end) {
+            (Some(first), Some(end)) if unsafe { (*first.as_ptr()).key > (*end.as_ptr()).key } => {
+                None
+            }
+            _ => next,
+        };
+
+        Iter {
+            next,
+            end,
+            marker: PhantomData,
+        }
+    }
+
+    fn find(&self, key: &K) -> Link<K, V> {
+        let mut current = self.root;
+
+        while let Some(node) = current {
+            let node_ref = unsafe { &*node.as_ptr() };
+            current = match key.cmp(&node_ref.key) {
+                Ordering::Less => node_ref.left,
+                Ordering::Greater => node_ref.right,
+                Ordering::Equal => return Some(node),
+            };
+        }
+
+        None
+    }
+
+    /// First node with key >= `key` (or > `key` when not inclusive).
+    fn lower_bound(&self, key: &K, inclusive: bool) -> Link<K, V> {
+        let mut current = self.root;
+        let mut candidate = None;
+
+        while let Some(node) = current {
+            let node_ref = unsafe { &*node.as_ptr() };
+            let goes_left = match inclusive {
+                true => node_ref.key >= *key,
+                false => node_ref.key > *key,
+            };
+
+            if goes_left {
+                candidate = Some(node);
+                current = node_ref.left;
+            } else {
+                current = node_ref.right;
+            }
+        }
+
+        candidate
+    }
+
+    fn insert_fixup(&mut self, mut z: NonNull<Node<K, V>>) {
+        unsafe {
+            while let Some(parent) = (*z.as_ptr()).parent {
+                if (*parent.as_ptr()).color == Color::Black {
+                    break;
+                }
+
+                // A red parent is never the root, so the grandparent exists.
+                let grandparent = (*parent.as_ptr()).parent.unwrap();
+                let parent_is_left = (*grandparent.as_ptr()).left == Some(parent);
+                let uncle = match parent_is_left {
+                    true => (*grandparent.as_ptr()).right,
+                    false => (*grandparent.as_ptr()).left,
+                };
+
+                // Case 1: red uncle, recolour and continue from the grandparent.
+                if Self::color(uncle) == Color::Red {
+                    (*parent.as_ptr()).color = Color::Black;
+                    (*uncle.unwrap().as_ptr()).color = Color::Black;
+                    (*grandparent.as_ptr()).color = Color::Red;
+                    z = grandparent;
+                    continue;
+                }
+
+                // Case 2: `z` is an inner grandchild, rotate it to the outside.
+                let mut parent = parent;
+                if parent_is_left && (*parent.as_ptr()).right == Some(z) {
+                    z = parent;
+                    self.rotate_left(z);
+                    parent = (*z.as_ptr()).parent.unwrap();
+                } else if !parent_is_left && (*parent.as_ptr()).left == Some(z) {
+                    z = parent;
+                    self.rotate_right(z);
+                    parent = (*z.as_ptr()).parent.unwrap();
+                }
+
+                // Case 3: outer grandchild, rotate the grandparent.
+                (*parent.as_ptr()).color = Color::Black;
+                (*grandparent.as_ptr()).color = Color::Red;
+                match parent_is_left {
+                    true => self.rotate_right(grandparent),
+                    false => self.rotate_left(grandparent),
+                }
+            }
+
+            (*self.root.unwrap().as_ptr()).color = Color::Black;
+        }
+    }
+
+    /// `x` carries an extra black after a black node was removed, push it up until it can be absorbed.
+    fn remove_fixup(&mut self, mut x: Link<K, V>, mut x_parent: Link<K, V>) {
+        unsafe {
+            while x != self.root && Self::color(x) == Color::Black {
+                // `x` is not the root, so it has a parent, and its sibling is a real node (black height >= 1).
+                let parent = x_parent.unwrap();
+                let x_is_left = (*parent.as_ptr()).left == x;
+                let mut sibling = match x_is_left {
+                    true => (*parent.as_ptr()).right.unwrap(),
+                    false => (*parent.as_ptr()).left.unwrap(),
+                };
+
+                // Case 1: red sibling, rotate so the sibling becomes black.
+                if (*sibling.as_ptr()).color == Color::Red {
+                    (*sibling.as_ptr()).color = Color::Black;
+                    (*parent.as_ptr()).color = Color::Red;
+                    match x_is_left {
+                        true => {
+                            self.rotate_left(parent);
+                            sibling = (*parent.as_ptr()).right.unwrap();
+                        }
+                        false => {
+                            self.rotate_right(parent);
+                            sibling = (*parent.as_ptr()).left.unwrap();
+                        }
+                    }
+                }
+
+                let (near_nephew, far_nephew) = match x_is_left {
+                    true => ((*sibling.as_ptr()).left, (*sibling.as_ptr()).right),
+                    false => ((*sibling.as_ptr()).right, (*sibling.as_ptr()).left),
+                };
+
+                // Case 2: both nephews black, recolour the sibling and move the extra black up.
+                if Self::color(near_nephew) == Color::Black
+                    && Self::color(far_nephew) == Color::Black
+                {
+                    (*sibling.as_ptr()).color = Color::Red;
+                    x = Some(parent);
+                    x_parent = (*parent.as_ptr()).parent;
+                    continue;
+                }
+
+                // Case 3: only the near nephew is red, rotate it into the far position.
+                if Self::color(far_nephew) == Color::Black {
+                    (*near_nephew.unwrap().as_ptr()).color = Color::Black;
+                    (*sibling.as_ptr()).color = Color::Red;
+                    match x_is_left {
+                        true => {
+                            self.rotate_right(sibling);
+                            sibling = (*parent.as_ptr()).right.unwrap();
+                        }
+                        false => {
+                            self.rotate_left(sibling);
+                            sibling = (*parent.as_ptr()).left.unwrap();
+                        }
+                    }
+                }
+
+                // Case 4: far nephew red, one rotation at the parent absorbs the extra black.
+                let far_nephew = match x_is_left {
+                    true => (*sibling.as_ptr()).right,
+                    false => (*sibling.as_ptr()).left,
+                };
+                (*sibling.as_ptr()).color = (*parent.as_ptr()).color;
+                (*parent.as_ptr()).color = Color::Black;
+                (*far_nephew.unwrap().as_ptr()).color = Color::Black;
+                match x_is_left {
+                    true => self.rotate_left(parent),
+                    false => self.rotate_right(parent),
+                }
+
+                x = self.root;
+                break;
+            }
+
+            if let Some(x) = x {
+                (*x.as_ptr()).color = Color::Black;
+            }
+        }
+    }
+
+    fn rotate_left(&mut self, x: NonNull<Node<K, V>>) {
+        unsafe {
+            let y = (*x.as_ptr()).right.unwrap();
+
+            (*x.as_ptr()).right = (*y.as_ptr()).left;
+            Self::set_parent((*x.as_ptr()).right, Some(x));
+
+            self.replace_child((*x.as_ptr()).parent, x, Some(y));
+            (*y.as_ptr()).parent = (*x.as_ptr()).parent;
+
+            (*y.as_ptr()).left = Some(x);
+            (*x.as_ptr()).parent = Some(y);
+        }
+    }
+
+    fn rotate_right(&mut self, x: NonNull<Node<K, V>>) {
+        unsafe {
+            let y = (*x.as_ptr()).left.unwrap();
+
+            (*x.as_ptr()).left = (*y.as_ptr()).right;
+            Self::set_parent((*x.as_ptr()).left, Some(x));
+
+            self.replace_child((*x.as_ptr()).parent, x, Some(y));
+            (*y.as_ptr()).parent = (*x.as_ptr()).parent;
+
+            (*y.as_ptr()).right = Some(x);
+            (*x.as_ptr()).parent = Some(y);
+        }
+    }
+
+    /// Replaces the subtree rooted at `old` with the subtree rooted at `new`.
+    fn transplant(&mut self, old: NonNull<Node<K, V>>, new: Link<K, V>) {
+        unsafe {
+            let parent = (*old.as_ptr()).parent;
+            self.replace_child(parent, old, new);
+            Self::set_parent(new, parent);
+        }
+    }
+
+    fn replace_child(&mut self, parent: Link<K, V>, old: NonNull<Node<K, V>>, new: Link<K, V>) {
+        match parent {
+            Some(parent) => unsafe {
+                if (*parent.as_ptr()).left == Some(old) {
+                    (*parent.as_ptr()).left = new;
+                } else {
+                    (*parent.as_ptr()).right = new;
+                }
+            },
+            None => self.root = new,
+        }
+    }
+}
+
+impl<K, V> RedBlackMap<K, V> {
+    // Leaves (None) count as black.
+    fn color(link: Link<K, V>) -> Color {
+        match link {
+            Some(node) => unsafe { (*node.as_ptr()).color },
+            None => Color::Black,
+        }
+    }
+
+    fn set_parent(link: Link<K, V>, parent: Link<K, V>) {
+        if let Some(node) = link {
+            unsafe { (*node.as_ptr()).parent = parent };
+        }
+    }
+
+    fn minimum(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+        while let Some(left) = unsafe { (*node.as_ptr()).left } {
+            node = left;
+        }
+        node
+    }
+
+    fn maximum(mut node: NonNull<Node<K, V>>) -> NonNull<Node<K, V>> {
+        while let Some(right) = unsafe { (*node.as_ptr()).right } {
+            node = right;
+        }
+        node
+    }
+
+    fn successor(node: NonNull<Node<K, V>>) -> Link<K, V> {
+        unsafe {
+            if let Some(right) = (*node.as_ptr()).right {
+                return Some(Self::minimum(right));
+            }
+
+            // Walk up until we arrive from a left child.
+            let mut child = node;
+            let mut parent = (*node.as_ptr()).parent;
+            while let Some(current) = parent {
+                if (*current.as_ptr()).left == Some(child) {
+                    return Some(current);
+                }
+                child = current;
+                parent = (*current.as_ptr()).parent;
+            }
+
+            None
+        }
+    }
+
+    fn free(link: Link<K, V>) {
+        if let Some(node) = link {
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            Self::free(node.left);
+            Self::free(node.right);
+        }
+    }
+}
+
+impl<K: Ord, V> Default for RedBlackMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> for RedBlackMap<K, V> {
+    type Iter<'a>
+        = Iter<'a, K, V>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn range<R: RangeBounds<K>>(&self, range: R) -> Self::Iter<'_> {
+        self.range(range)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Drop for RedBlackMap<K, V> {
+    fn drop(&mut self) {
+        Self::free(self.root.take());
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    next: Link<K, V>,
+    end: Link<K, V>,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        if self.next == self.end {
+            return None;
+        }
+
+        self.next = RedBlackMap::successor(node);
+
+        let node = unsafe { &*node.as_ptr() };
+        Some((&node.key, &node.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::ordered_map::ordered_map_conformance_tests;
+    use crate::random::Random;
+
+    /// Checks BST order, parent links, no red-red and equal black height. Returns the black height.
+    fn check_invariants<K: Ord, V>(map: &RedBlackMap<K, V>) -> usize {
+        fn check_node<K: Ord, V>(
+            link: Link<K, V>,
+            parent: Link<K, V>,
+            lower: Option<&K>,
+            upper: Option<&K>,
+            count: &mut usize,
+        ) -> usize {
+            let Some(node) = link else {
+                return 1;
+            };
+            let node_ref = unsafe { &*node.as_ptr() };
+            *count += 1;
+
+            assert!(node_ref.parent == parent, "Broken parent link.");
+            assert!(
+                lower.is_none_or(|lower| *lower < node_ref.key),
+                "BST order violated."
+            );
+            assert!(
+                upper.is_none_or(|upper| node_ref.key < *upper),
+                "BST order violated."
+            );
+
+            if node_ref.color == Color::Red {
+                assert_eq!(
+                    RedBlackMap::color(node_ref.left),
+                    Color::Black,
+                    "Red node with red child."
+                );
+                assert_eq!(
+                    RedBlackMap::color(node_ref.right),
+                    Color::Black,
+                    "Red node with red child."
+                );
+            }
+
+            let left_height = check_node(node_ref.left, link, lower, Some(&node_ref.key), count);
+            let right_height = check_node(node_ref.right, link, Some(&node_ref.key), upper, count);
+            assert_eq!(left_height, right_height, "Black height differs.");
+
+            left_height + usize::from(node_ref.color == Color::Black)
+        }
+
+        assert_eq!(RedBlackMap::color(map.root), Color::Black, "Red root.");
+
+        let mut count = 0;
+        let black_height = check_node(map.root, None, None, None, &mut count);
+        assert_eq!(count, map.len());
+
+        black_height
+    }
+
+    #[test]
+    fn handles_sequential_keys() {
+        let mut map = RedBlackMap::new();
+        for key in 0..1024 {
+            map.insert(key, key);
+        }
+
+        // Height of a red-black tree is at most twice its black height.
+        let black_height = check_invariants(&map);
+        assert!(black_height <= 11);
+        assert_eq!(map.first_key_value(), Some((&0, &0)));
+        assert_eq!(map.last_key_value(), Some((&1023, &1023)));
+
+        for key in (0..1024).rev() {
+            assert_eq!(map.remove(&key), Some(key));
+        }
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn keeps_invariants_on_random_operations() {
+        let mut random = Random::new(26);
+        let mut map = RedBlackMap::new();
+
+        for step in 0..20_000 {
+            let key = random.range(0..500);
+            match random.range(0..3) {
+                0 | 1 => map.insert(key, step),
+                _ => map.remove(&key),
+            };
+            if step % 500 == 0 {
+                check_invariants(&map);
+            }
+        }
+
+        check_invariants(&map);
+        map.clear();
+        assert!(map.root.is_none());
+    }
+
+    ordered_map_conformance_tests!(conformance, <K, V> RedBlackMap<K, V>);
+}
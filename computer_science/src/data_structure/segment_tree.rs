@@ -0,0 +1,622 @@
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+/// Associative operation with an identity element, the aggregate a segment tree maintains over ranges.
+///
+/// Need not be commutative, ranges are always combined left to right.
+pub trait Monoid {
+    type Value: Clone;
+
+    fn identity() -> Self::Value;
+
+    fn combine(left: &Self::Value, right: &Self::Value) -> Self::Value;
+}
+
+/// Monoid whose aggregate can be updated for a whole range at once, which `LazySegmentTree` needs to defer updates.
+pub trait RangeMonoid: Monoid {
+    /// Aggregate of `len` elements all equal to `value`.
+    fn repeat(value: &Self::Value, len: usize) -> Self::Value;
+
+    /// Aggregate of `len` elements after adding `delta` to each of them.
+    fn shift(aggregate: &Self::Value, delta: &Self::Value, len: usize) -> Self::Value;
+
+    /// Combines two deltas into one, adding both.
+    fn add(a: &Self::Value, b: &Self::Value) -> Self::Value;
+}
+
+pub struct Sum<T>(PhantomData<T>);
+pub struct Min<T>(PhantomData<T>);
+pub struct Max<T>(PhantomData<T>);
+/// Greatest common divisor, with 0 as the identity. Only for unsigned integers, the gcd of signed values is the gcd of
+/// their `unsigned_abs`, which doesn't overflow on `MIN`.
+pub struct Gcd<T>(PhantomData<T>);
+
+macro_rules! impl_number_monoids {
+    ($($number:ty: $min:expr, $max:expr);* $(;)?) => {
+        $(
+            impl Monoid for Sum<$number> {
+                type Value = $number;
+
+                fn identity() -> $number {
+                    0 as $number
+                }
+
+                fn combine(left: &$number, right: &$number) -> $number {
+                    left + right
+                }
+            }
+
+            impl RangeMonoid for Sum<$number> {
+                fn repeat(value: &$number, len: usize) -> $number {
+                    value * len as $number
+                }
+
+                fn shift(aggregate: &$number, delta: &$number, len: usize) -> $number {
+                    aggregate + delta * len as $number
+                }
+
+                fn add(a: &$number, b: &$number) -> $number {
+                    a + b
+                }
+            }
+
+            impl Monoid for Min<$number> {
+                type Value = $number;
+
+                fn identity() -> $number {
+                    $max
+                }
+
+                fn combine(left: &$number, right: &$number) -> $number {
+                    (*left).min(*right)
+                }
+            }
+
+            impl RangeMonoid for Min<$number> {
+                fn repeat(value: &$number, _: usize) -> $number {
+                    *value
+                }
+
+                fn shift(aggregate: &$number, delta: &$number, _: usize) -> $number {
+                    aggregate + delta
+                }
+
+                fn add(a: &$number, b: &$number) -> $number {
+                    a + b
+                }
+            }
+
+            impl Monoid for Max<$number> {
+                type Value = $number;
+
+                fn identity() -> $number {
+                    $min
+                }
+
+                fn combine(left: &$number, right: &$number) -> $number {
+                    (*left).max(*right)
+                }
+            }
+
+            impl RangeMonoid for Max<$number> {
+                fn repeat(value: &$number, _: usize) -> $number {
+                    *value
+                }
+
+                fn shift(aggregate: &$number, delta: &$number, _: usize) -> $number {
+                    aggregate + delta
+                }
+
+                fn add(a: &$number, b: &$number) -> $number {
+                    a + b
+                }
+            }
+        )*
+    };
+}
+
+impl_number_monoids!(
+    i8: i8::MIN, i8::MAX;
+    i16: i16::MIN, i16::MAX;
+    i32: i32::MIN, i32::MAX;
+    i64: i64::MIN, i64::MAX;
+    i128: i128::MIN, i128::MAX;
+    isize: isize::MIN, isize::MAX;
+    u8: u8::MIN, u8::MAX;
+    u16: u16::MIN, u16::MAX;
+    u32: u32::MIN, u32::MAX;
+    u64: u64::MIN, u64::MAX;
+    u128: u128::MIN, u128::MAX;
+    usize: usize::MIN, usize::MAX;
+    f32: f32::NEG_INFINITY, f32::INFINITY;
+    f64: f64::NEG_INFINITY, f64::INFINITY;
+);
+
+macro_rules! impl_gcd {
+    ($($number:ty),* $(,)?) => {
+        $(
+            impl Monoid for Gcd<$number> {
+                type Value = $number;
+
+                fn identity() -> $number {
+                    0
+                }
+
+                /// Euclid's algorithm.
+                fn combine(left: &$number, right: &$number) -> $number {
+                    let (mut a, mut b) = (*left, *right);
+                    while b != 0 {
+                        (a, b) = (b, a % b);
+                    }
+                    a
+                }
+            }
+        )*
+    };
+}
+
+impl_gcd!(u8, u16, u32, u64, u128, usize);
+
+/// Converts range bounds into a `start..end` within `0..len`.
+pub(crate) fn bounds(range: impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(start) => *start,
+        Bound::Excluded(start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end + 1,
+        Bound::Excluded(end) => *end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end && end <= len, "Out of bounds range.");
+
+    (start, end)
+}
+
+/// Segment tree: aggregates of the monoid over an array, queried for any range and updated per element in
+/// O(log n).
+///
+/// Bottom-up layout in one array of 2n values: leaves at `n..2n`, node `i` aggregates its children `2i` and `2i + 1`.
+pub struct SegmentTree<M: Monoid> {
+    tree: Vec<M::Value>,
+    len: usize,
+}
+
+impl<M: Monoid> SegmentTree<M> {
+    /// Creates a tree over `len` identity elements.
+    pub fn new(len: usize) -> Self {
+        Self {
+            tree: vec![M::identity(); 2 * len],
+            len,
+        }
+    }
+
+    /// Creates a tree over the values in O(n).
+    pub fn from_vec(values: Vec<M::Value>) -> Self {
+        let len = values.len();
+        let mut tree = vec![M::identity(); len];
+        tree.extend(values);
+
+        for index in (1..len).rev() {
+            tree[index] = M::combine(&tree[2 * index], &tree[2 * index + 1]);
+        }
+
+        Self { tree, len }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, index: usize) -> &M::Value {
+        assert!(index < self.len, "Out of bounds index.");
+        &self.tree[self.len + index]
+    }
+
+    pub fn set(&mut self, index: usize, value: M::Value) {
+        assert!(index < self.len, "Out of bounds index.");
+
+        let mut index = self.len + index;
+        self.tree[index] = value;
+        while index > 1 {
+            index /= 2;
+            self.tree[index] = M::combine(&self.tree[2 * index], &self.tree[2 * index + 1]);
+        }
+    }
+
+    /// Aggregate of the range, the identity for an empty one.
+    pub fn query(&self, range: impl RangeBounds<usize>) -> M::Value {
+        let (start, end) = bounds(range, self.len);
+        let (mut start, mut end) = (start + self.len, end + self.len);
+
+        // Aggregates of the left and right borders, kept apart so the order is preserved.
+        let mut left = M::identity();
+        let mut right = M::identity();
+        while start < end {
+            if start % 2 == 1 {
+                left = M::combine(&left, &self.tree[start]);
+                start += 1;
+            }
+            if end % 2 == 1 {
+                end -= 1;
+                right = M::combine(&self.tree[end], &right);
+            }
+            start /= 2;
+            end /= 2;
+        }
+
+        M::combine(&left, &right)
+    }
+}
+
+/// Pending update of a whole range.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Update<T> {
+    Assign(T),
+    Add(T),
+}
+
+/// Segment tree with lazy propagation: besides queries, assigns or adds to any range in O(log n).
+///
+/// An update covering a whole node is applied to its aggregate and stored as pending, to be pushed down to the
+/// children only once something inside the node is accessed.
+pub struct LazySegmentTree<M: RangeMonoid> {
+    // Recursive layout: node 1 is the root, node `i` has children `2i` and `2i + 1`.
+    tree: Vec<M::Value>,
+    pending: Vec<Option<Update<M::Value>>>,
+    len: usize,
+}
+
+impl<M: RangeMonoid> LazySegmentTree<M> {
+    pub fn from_vec(values: Vec<M::Value>) -> Self {
+        let len = values.len();
+        let mut tree = Self {
+            tree: vec![M::identity(); 4 * len.max(1)],
+            pending: vec![None; 4 * len.max(1)],
+            len,
+        };
+        if len > 0 {
+            tree.build(&values, 1, 0, len);
+        }
+        tree
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&mut self, index: usize) -> M::Value {
+        assert!(index < self.len, "Out of bounds index.");
+        self.query(index..=index)
+    }
+
+    /// Aggregate of the range, the identity for an empty one.
+    pub fn query(&mut self, range: impl RangeBounds<usize>) -> M::Value {
+        let (start, end) = bounds(range, self.len);
+        match start < end {
+            true => self.query_node(1, 0, self.len, start, end),
+            false => M::identity(),
+        }
+    }
+
+    pub fn update(&mut self, range: impl RangeBounds<usize>, update: Update<M::Value>) {
+        let (start, end) = bounds(range, self.len);
+        if start < end {
+            self.update_node(1, 0, self.len, start, end, &update);
+        }
+    }
+
+    pub fn assign(&mut self, range: impl RangeBounds<usize>, value: M::Value) {
+        self.update(range, Update::Assign(value));
+    }
+
+    pub fn add(&mut self, range: impl RangeBounds<usize>, delta: M::Value) {
+        self.update(range, Update::Add(delta));
+    }
+
+    fn build(&mut self, values: &[M::Value], node: usize, start: usize, end: usize) {
+        if end - start == 1 {
+            self.tree[node] = values[start].clone();
+            return;
+        }
+
+        let middle = (start + end) / 2;
+        self.build(values, 2 * node, start, middle);
+        self.build(values, 2 * node + 1, middle, end);
+        self.tree[node] = M::combine(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    /// Node covering `start..end` answers for `query_start..query_end`.
+    fn query_node(
+        &mut self,
+        node: usize,
+        start: usize,
+        end: usize,
+        query_start: usize,
+        query_end: usize,
+    ) -> M::Value {
+        if query_start <= start && end <= query_end {
+            return self.tree[node].clone();
+        }
+
+        self.push_down(node, start, end);
+        let middle = (start + end) / 2;
+        let left = match query_start < middle {
+            true => self.query_node(2 * node, start, middle, query_start, query_end),
+            false => M::identity(),
+        };
+        let right = match middle < query_end {
+            true => self.query_node(2 * node + 1, middle, end, query_start, query_end),
+            false => M::identity(),
+        };
+
+        M::combine(&left, &right)
+    }
+
+    fn update_node(
+        &mut self,
+        node: usize,
+        start: usize,
+        end: usize,
+        update_start: usize,
+        update_end: usize,
+        update: &Update<M::Value>,
+    ) {
+        if update_start <= start && end <= update_end {
+            self.apply(node, end - start, update);
+            return;
+        }
+
+        self.push_down(node, start, end);
+        let middle = (start + end) / 2;
+        if update_start < middle {
+            self.update_node(2 * node, start, middle, update_start, update_end, update);
+        }
+        if middle < update_end {
+            self.update_node(2 * node + 1, middle, end, update_start, update_end, update);
+        }
+        self.tree[node] = M::combine(&self.tree[2 * node], &self.tree[2 * node + 1]);
+    }
+
+    /// Applies the update to the aggregate of a node covering `len` elements and merges it into its pending one.
+    fn apply(&mut self, node: usize, len: usize, update: &Update<M::Value>) {
+        self.tree[node] = match update {
+            Update::Assign(value) => M::repeat(value, len),
+            Update::Add(delta) => M::shift(&self.tree[node], delta, len),
+        };
+
+        self.pending[node] = Some(match (self.pending[node].take(), update) {
+            // Assignment overrides anything before it.
+            (_, Update::Assign(value)) => Update::Assign(value.clone()),
+            (Some(Update::Assign(value)), Update::Add(delta)) => {
+                Update::Assign(M::add(&value, delta))
+            }
+            (Some(Update::Add(previous)), Update::Add(delta)) => {
+                Update::Add(M::add(&previous, delta))
+            }
+            (None, Update::Add(delta)) => Update::Add(delta.clone()),
+        });
+    }
+
+    fn push_down(&mut self, node: usize, start: usize, end: usize) {
+        if let Some(update) = self.pending[node].take() {
+            let middle = (start + end) / 2;
+            self.apply(2 * node, middle - start, &update);
+            self.apply(2 * node + 1, end - middle, &update);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    /// Non-commutative monoid, catches ranges combined out of order.
+    struct Concat;
+
+    impl Monoid for Concat {
+        type Value = String;
+
+        fn identity() -> String {
+            String::new()
+        }
+
+        fn combine(left: &String, right: &String) -> String {
+            format!("{left}{right}")
+        }
+    }
+
+    fn random_range(random: &mut Random, len: usize) -> (usize, usize) {
+        let (a, b) = (random.range(0..len + 1), random.range(0..len + 1));
+        (a.min(b), a.max(b))
+    }
+
+    /// Random point updates and range queries against folding the plain array.
+    fn check_against_loops<M: Monoid>(seed: u64, random_value: impl Fn(&mut Random) -> M::Value)
+    where
+        M::Value: PartialEq + std::fmt::Debug,
+    {
+        let mut random = Random::new(seed);
+
+        for len in [0, 1, 2, 3, 7, 64, 100] {
+            let mut values: Vec<M::Value> = (0..len).map(|_| random_value(&mut random)).collect();
+            let mut tree = SegmentTree::<M>::from_vec(values.clone());
+
+            for _ in 0..300 {
+                if len > 0 && random.next_bool(0.5) {
+                    let index = random.range(0..len);
+                    let value = random_value(&mut random);
+                    values[index] = value.clone();
+                    tree.set(index, value);
+                }
+
+                let (start, end) = random_range(&mut random, len);
+                let expected = values[start..end]
+                    .iter()
+                    .fold(M::identity(), |aggregate, value| {
+                        M::combine(&aggregate, value)
+                    });
+                assert_eq!(tree.query(start..end), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn can_query_sum() {
+        let mut tree = SegmentTree::<Sum<i64>>::from_vec(vec![5, 3, -2, 8, 1]);
+        assert_eq!(tree.query(..), 15);
+        assert_eq!(tree.query(1..4), 9);
+        assert_eq!(tree.query(2..=2), -2);
+        assert_eq!(tree.query(3..3), 0);
+
+        tree.set(2, 10);
+        assert_eq!(tree.query(1..4), 21);
+        assert_eq!(*tree.get(2), 10);
+    }
+
+    #[test]
+    fn can_query_min_max_gcd() {
+        let values = vec![12, 18, 7, 30, 24];
+        let min = SegmentTree::<Min<u32>>::from_vec(values.clone());
+        let max = SegmentTree::<Max<u32>>::from_vec(values.clone());
+        let gcd = SegmentTree::<Gcd<u32>>::from_vec(values);
+
+        assert_eq!(min.query(..2), 12);
+        assert_eq!(min.query(..), 7);
+        assert_eq!(max.query(..3), 18);
+        assert_eq!(max.query(0..0), u32::MIN);
+        assert_eq!(gcd.query(..2), 6);
+        assert_eq!(gcd.query(3..), 6);
+        assert_eq!(gcd.query(..), 1);
+    }
+
+    #[test]
+    fn gcd_of_signed_magnitudes_matches_brute_force() {
+        let mut random = Random::new(36);
+
+        for len in [1, 2, 7, 30] {
+            let values: Vec<i8> = (0..len)
+                .map(|_| match random.range(0..4) {
+                    0 => i8::MIN,
+                    1 => 0,
+                    _ => random.range(0..256) as u8 as i8,
+                })
+                .collect();
+            let magnitudes: Vec<u8> = values.iter().map(|value| value.unsigned_abs()).collect();
+            let tree = SegmentTree::<Gcd<u8>>::from_vec(magnitudes.clone());
+
+            for start in 0..len {
+                for end in start..=len {
+                    let range = &magnitudes[start..end];
+                    let expected = (1..=u8::MAX)
+                        .rev()
+                        .find(|divisor| range.iter().all(|magnitude| magnitude % divisor == 0))
+                        .filter(|_| range.iter().any(|&magnitude| magnitude != 0))
+                        .unwrap_or(0);
+                    assert_eq!(tree.query(start..end), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn starts_with_identities() {
+        let mut tree = SegmentTree::<Min<f64>>::new(4);
+        assert_eq!(tree.query(..), f64::INFINITY);
+        tree.set(3, 1.5);
+        assert_eq!(tree.query(..), 1.5);
+    }
+
+    #[test]
+    #[should_panic(expected = "Out of bounds range.")]
+    fn panics_out_of_bounds() {
+        SegmentTree::<Sum<i32>>::new(3).query(1..4);
+    }
+
+    #[test]
+    fn matches_loops() {
+        check_against_loops::<Sum<i64>>(1, |random| random.range(0..2000) as i64 - 1000);
+        check_against_loops::<Min<i32>>(2, |random| random.range(0..2000) as i32 - 1000);
+        check_against_loops::<Max<u8>>(3, |random| random.range(0..256) as u8);
+        check_against_loops::<Gcd<u64>>(4, |random| 6 * random.range(0..50) as u64);
+        check_against_loops::<Concat>(5, |random| {
+            ((b'a' + random.range(0..26) as u8) as char).to_string()
+        });
+    }
+
+    #[test]
+    fn can_update_lazily() {
+        let mut tree = LazySegmentTree::<Sum<i64>>::from_vec(vec![1, 2, 3, 4, 5]);
+        tree.add(1..4, 10);
+        assert_eq!(tree.query(..), 45);
+        assert_eq!(tree.query(3..), 19);
+
+        tree.assign(..2, 0);
+        assert_eq!(tree.query(..3), 13);
+        tree.add(..=1, 1);
+        assert_eq!(tree.get(1), 1);
+        assert_eq!(tree.get(4), 5);
+
+        let mut tree = LazySegmentTree::<Min<i32>>::from_vec(vec![4, 1, 7]);
+        tree.add(.., 2);
+        tree.assign(1..2, 9);
+        assert_eq!(tree.query(..), 6);
+        assert_eq!(tree.query(1..), 9);
+    }
+
+    /// Random range updates and queries against updating the plain array element by element.
+    fn check_lazy_against_loops<M: RangeMonoid<Value = i64>>(seed: u64) {
+        let mut random = Random::new(seed);
+
+        for len in [1, 2, 5, 33, 100] {
+            let mut values: Vec<i64> = (0..len).map(|_| random.range(0..100) as i64).collect();
+            let mut tree = LazySegmentTree::<M>::from_vec(values.clone());
+
+            for _ in 0..500 {
+                let (start, end) = random_range(&mut random, len);
+                let value = random.range(0..100) as i64 - 50;
+                match random.range(0..3) {
+                    0 => {
+                        tree.assign(start..end, value);
+                        values[start..end]
+                            .iter_mut()
+                            .for_each(|element| *element = value);
+                    }
+                    1 => {
+                        tree.add(start..end, value);
+                        values[start..end]
+                            .iter_mut()
+                            .for_each(|element| *element += value);
+                    }
+                    _ => {
+                        let expected = values[start..end]
+                            .iter()
+                            .fold(M::identity(), |aggregate, value| {
+                                M::combine(&aggregate, value)
+                            });
+                        assert_eq!(tree.query(start..end), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn lazy_matches_loops() {
+        check_lazy_against_loops::<Sum<i64>>(6);
+        check_lazy_against_loops::<Min<i64>>(7);
+        check_lazy_against_loops::<Max<i64>>(8);
+    }
+}
@@ -0,0 +1,17 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+pub mod count_min_sketch;
+pub mod heavy_hitters;
+pub mod hyper_log_log;
+pub mod reservoir;
+
+/// Seeded 64-bit hash. `DefaultHasher::new` has fixed keys, so sketches built separately hash alike and can be merged.
+fn hash<T: Hash + ?Sized>(item: &T, seed: u64) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
@@ -0,0 +1,165 @@
+use super::hash;
+use std::{f64::consts::E, hash::Hash, marker::PhantomData};
+
+/// Count-Min sketch: approximate item frequencies in O(width * depth) memory.
+///
+/// Every row hashes each item to one counter. Collisions only add, so the smallest of an item's counters never
+/// underestimates its count, and with `width = e / epsilon` and `depth = ln(1 / delta)` it overestimates by more than
+/// `epsilon * total` with probability at most `delta`.
+pub struct CountMinSketch<T: ?Sized> {
+    width: usize,
+    depth: usize,
+    // Rows one after another.
+    counters: Vec<u64>,
+    total: u64,
+    item: PhantomData<fn(&T)>,
+}
+
+impl<T: Hash + ?Sized> CountMinSketch<T> {
+    pub fn new(epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0.0 && delta > 0.0 && delta < 1.0,
+            "Invalid error bounds."
+        );
+        let width = (E / epsilon).ceil() as usize;
+        let depth = (1.0 / delta).ln().ceil().max(1.0) as usize;
+        Self::with_size(width, depth)
+    }
+
+    pub fn with_size(width: usize, depth: usize) -> Self {
+        assert!(width > 0 && depth > 0, "Sketch needs rows and columns.");
+        Self {
+            width,
+            depth,
+            counters: vec![0; width * depth],
+            total: 0,
+            item: PhantomData,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Sum of all counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn insert(&mut self, item: &T) {
+        self.add(item, 1);
+    }
+
+    pub fn add(&mut self, item: &T, count: u64) {
+        for row in 0..self.depth {
+            let index = self.index(item, row);
+            self.counters[index] = self.counters[index].saturating_add(count);
+        }
+        self.total = self.total.saturating_add(count);
+    }
+
+    /// Estimated count, never below the true one.
+    pub fn estimate(&self, item: &T) -> u64 {
+        (0..self.depth)
+            .map(|row| self.counters[self.index(item, row)])
+            .min()
+            .unwrap()
+    }
+
+    /// Adds the counts of a sketch with the same size.
+    pub fn merge(&mut self, other: &Self) {
+        assert!(
+            self.width == other.width && self.depth == other.depth,
+            "Sketches have different sizes."
+        );
+        for (counter, other) in self.counters.iter_mut().zip(&other.counters) {
+            *counter = counter.saturating_add(*other);
+        }
+        self.total = self.total.saturating_add(other.total);
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.fill(0);
+        self.total = 0;
+    }
+
+    fn index(&self, item: &T, row: usize) -> usize {
+        row * self.width + (hash(item, row as u64) % self.width as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::collections::HashMap;
+
+    #[test]
+    fn sizes_from_bounds() {
+        let sketch = CountMinSketch::<u32>::new(0.01, 0.01);
+        assert_eq!(sketch.width(), 272);
+        assert_eq!(sketch.depth(), 5);
+    }
+
+    #[test]
+    fn counts_exactly_without_collisions() {
+        let mut sketch = CountMinSketch::new(0.001, 0.01);
+        sketch.add("a", 5);
+        sketch.insert("b");
+        assert_eq!(sketch.estimate("a"), 5);
+        assert_eq!(sketch.estimate("b"), 1);
+        assert_eq!(sketch.estimate("c"), 0);
+        assert_eq!(sketch.total(), 6);
+
+        sketch.clear();
+        assert_eq!(sketch.estimate("a"), 0);
+    }
+
+    #[test]
+    fn stays_within_error_bounds() {
+        let mut random = Random::new(41);
+        let (epsilon, delta) = (0.005, 0.01);
+        let mut sketch = CountMinSketch::new(epsilon, delta);
+        let mut counts = HashMap::new();
+
+        // Skewed stream: small keys are much more frequent.
+        for _ in 0..100_000 {
+            let bound = random.range(1..5000);
+            let key = random.range(0..bound);
+            sketch.insert(&key);
+            *counts.entry(key).or_insert(0) += 1;
+        }
+
+        let bound = (epsilon * sketch.total() as f64) as u64;
+        let mut violations = 0;
+        for (key, count) in &counts {
+            let estimate = sketch.estimate(key);
+            assert!(estimate >= *count);
+            if estimate > count + bound {
+                violations += 1;
+            }
+        }
+        assert!((violations as f64) <= delta * counts.len() as f64);
+    }
+
+    #[test]
+    fn merges_like_one_stream() {
+        let (mut a, mut b, mut both) = (
+            CountMinSketch::with_size(100, 4),
+            CountMinSketch::with_size(100, 4),
+            CountMinSketch::with_size(100, 4),
+        );
+        for key in 0..1000u32 {
+            if key % 3 == 0 { &mut a } else { &mut b }.insert(&(key % 70));
+            both.insert(&(key % 70));
+        }
+
+        a.merge(&b);
+        assert_eq!(a.total(), both.total());
+        assert!((0..70u32).all(|key| a.estimate(&key) == both.estimate(&key)));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::data_structure::hash_map::OpenHashMap;
+use std::{cmp::Reverse, hash::Hash};
+
+/// Counter of a monitored item: the true count lies in `count - error..=count`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Counter {
+    pub count: u64,
+    pub error: u64,
+}
+
+/// Top-k heavy hitters with the Space-Saving algorithm: monitors at most `capacity` items.
+///
+/// An unmonitored item replaces the one with the smallest count and inherits that count as its error. Every item
+/// occurring more than `total / capacity` times is guaranteed to be monitored. Finding the smallest counter is a scan,
+/// so evictions cost O(capacity).
+pub struct HeavyHitters<T> {
+    capacity: usize,
+    counters: OpenHashMap<T, Counter>,
+    total: u64,
+}
+
+impl<T: Hash + Eq + Clone> HeavyHitters<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be positive.");
+        Self {
+            capacity,
+            counters: OpenHashMap::new(),
+            total: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of monitored items.
+    pub fn len(&self) -> usize {
+        self.counters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counters.is_empty()
+    }
+
+    /// Sum of all counts added.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn insert(&mut self, item: T) {
+        self.add(item, 1);
+    }
+
+    pub fn add(&mut self, item: T, count: u64) {
+        self.total += count;
+
+        if let Some(counter) = self.counters.get_mut(&item) {
+            counter.count += count;
+            return;
+        }
+
+        let mut error = 0;
+        if self.counters.len() == self.capacity {
+            let (smallest, counter) = self
+                .counters
+                .iter()
+                .min_by_key(|(_, counter)| counter.count)
+                .map(|(item, counter)| (item.clone(), *counter))
+                .unwrap();
+            self.counters.remove(&smallest);
+            error = counter.count;
+        }
+        self.counters.insert(
+            item,
+            Counter {
+                count: error + count,
+                error,
+            },
+        );
+    }
+
+    /// Counter of a monitored item.
+    pub fn get(&self, item: &T) -> Option<Counter> {
+        self.counters.get(item).copied()
+    }
+
+    /// The `k` monitored items with the highest counts, highest first.
+    pub fn top(&self, k: usize) -> Vec<(&T, Counter)> {
+        let mut top: Vec<_> = self
+            .counters
+            .iter()
+            .map(|(item, counter)| (item, *counter))
+            .collect();
+        top.sort_by_key(|(_, counter)| Reverse(counter.count));
+        top.truncate(k);
+        top
+    }
+
+    pub fn clear(&mut self) {
+        self.counters.clear();
+        self.total = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::collections::HashMap;
+
+    #[test]
+    fn counts_exactly_below_capacity() {
+        let mut hitters = HeavyHitters::new(3);
+        for item in ["a", "b", "a", "c", "a", "b"] {
+            hitters.insert(item);
+        }
+
+        let top = hitters.top(2);
+        assert_eq!(top[0], (&"a", Counter { count: 3, error: 0 }));
+        assert_eq!(top[1], (&"b", Counter { count: 2, error: 0 }));
+        assert_eq!(hitters.total(), 6);
+    }
+
+    #[test]
+    fn evicts_smallest() {
+        let mut hitters = HeavyHitters::new(2);
+        hitters.add("a", 5);
+        hitters.add("b", 2);
+        hitters.insert("c");
+
+        assert_eq!(hitters.get(&"b"), None);
+        assert_eq!(hitters.get(&"c"), Some(Counter { count: 3, error: 2 }));
+        assert_eq!(hitters.len(), 2);
+    }
+
+    #[test]
+    fn finds_heavy_hitters_in_skewed_stream() {
+        let mut random = Random::new(41);
+        let capacity = 50;
+        let mut hitters = HeavyHitters::new(capacity);
+        let mut counts = HashMap::new();
+
+        // Half the stream is geometric over small keys, key k with probability 2^-(k + 1), half uniform noise.
+        for _ in 0..200_000 {
+            let key = match random.next_bool(0.5) {
+                true => random.next_u64().trailing_zeros() as usize,
+                false => random.range(0..10_000),
+            };
+            hitters.insert(key);
+            *counts.entry(key).or_insert(0u64) += 1;
+        }
+
+        for (key, counter) in hitters.top(capacity) {
+            let count = counts[key];
+            assert!(counter.count - counter.error <= count && count <= counter.count);
+        }
+
+        let threshold = hitters.total() / capacity as u64;
+        for (key, count) in &counts {
+            if *count > threshold {
+                assert!(hitters.get(key).is_some(), "Heavy key {} was dropped.", key);
+            }
+        }
+
+        let mut expected: Vec<_> = counts.iter().collect();
+        expected.sort_by(|a, b| b.1.cmp(a.1));
+        let found: Vec<_> = hitters.top(5).into_iter().map(|(key, _)| *key).collect();
+        assert!(expected[..3].iter().all(|(key, _)| found.contains(key)));
+    }
+}
@@ -0,0 +1,194 @@
+use super::hash;
+use std::{hash::Hash, marker::PhantomData};
+
+/// HyperLogLog: estimates the number of distinct items with a standard error of about `1.04 / sqrt(m)`, using `m`
+/// one-byte registers.
+///
+/// The first `precision` bits of a hash pick a register, which keeps the longest run of leading zeros seen in the
+/// remaining bits. Long runs are exponentially rare, so they reveal how many distinct hashes went by. Merging takes the
+/// register-wise maximum, giving exactly the sketch of the combined streams.
+pub struct HyperLogLog<T: ?Sized> {
+    precision: u8,
+    registers: Vec<u8>,
+    item: PhantomData<fn(&T)>,
+}
+
+// Fixed seed so separately built sketches can be merged.
+const SEED: u64 = 0x4859_5045_524C_4F47;
+
+impl<T: Hash + ?Sized> HyperLogLog<T> {
+    /// Uses `2^precision` registers, `precision` in 4..=16.
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "Precision must be in 4..=16."
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+            item: PhantomData,
+        }
+    }
+
+    /// Rebuilds a sketch from its registers, `None` if their count isn't a valid power of two.
+    pub fn from_registers(registers: Vec<u8>) -> Option<Self> {
+        if !registers.len().is_power_of_two() {
+            return None;
+        }
+        let precision = registers.len().trailing_zeros() as u8;
+        if !(4..=16).contains(&precision)
+            || registers.iter().any(|register| *register > 65 - precision)
+        {
+            return None;
+        }
+
+        Some(Self {
+            precision,
+            registers,
+            item: PhantomData,
+        })
+    }
+
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|register| *register == 0)
+    }
+
+    pub fn insert(&mut self, item: &T) {
+        let hash = hash(item, SEED);
+        let index = (hash >> (64 - self.precision)) as usize;
+        // Marker bit bounds the run when every remaining bit is zero.
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank);
+    }
+
+    /// Estimated number of distinct items inserted.
+    pub fn estimate(&self) -> f64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        // Few items leave registers empty, linear counting on them is more accurate there.
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+
+    /// Merges a sketch with the same precision, as if its items were inserted here.
+    pub fn merge(&mut self, other: &Self) {
+        assert!(
+            self.precision == other.precision,
+            "Sketches have different precisions."
+        );
+        for (register, other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(*other);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_empty_and_small() {
+        let mut sketch = HyperLogLog::new(10);
+        assert!(sketch.is_empty());
+        assert_eq!(sketch.estimate(), 0.0);
+
+        for _ in 0..100 {
+            sketch.insert("same");
+        }
+        assert!((sketch.estimate() - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn estimates_within_error() {
+        for precision in [8, 12, 14] {
+            let error = 1.04 / ((1 << precision) as f64).sqrt();
+
+            for distinct in [100, 10_000, 200_000] {
+                let mut sketch = HyperLogLog::new(precision);
+                // Every item twice, duplicates must not count.
+                for item in (0..distinct).chain(0..distinct) {
+                    sketch.insert(&(item as u64));
+                }
+
+                let relative = (sketch.estimate() - distinct as f64).abs() / distinct as f64;
+                assert!(
+                    relative < 3.0 * error,
+                    "Precision {} off by {} for {} items.",
+                    precision,
+                    relative,
+                    distinct
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn merges_exactly() {
+        let (mut a, mut b, mut union) = (
+            HyperLogLog::new(12),
+            HyperLogLog::new(12),
+            HyperLogLog::new(12),
+        );
+        for item in 0..50_000u32 {
+            a.insert(&item);
+            union.insert(&item);
+        }
+        for item in 25_000..80_000u32 {
+            b.insert(&item);
+            union.insert(&item);
+        }
+
+        a.merge(&b);
+        assert_eq!(a.registers(), union.registers());
+        assert!((a.estimate() - 80_000.0).abs() / 80_000.0 < 0.05);
+    }
+
+    #[test]
+    fn round_trips_registers() {
+        let mut sketch = HyperLogLog::new(6);
+        for item in 0..1000 {
+            sketch.insert(&item);
+        }
+
+        let read = HyperLogLog::<i32>::from_registers(sketch.registers().to_vec()).unwrap();
+        assert_eq!(read.precision(), 6);
+        assert_eq!(read.estimate(), sketch.estimate());
+        assert!(HyperLogLog::<i32>::from_registers(vec![0; 100]).is_none());
+        assert!(HyperLogLog::<i32>::from_registers(vec![0; 8]).is_none());
+    }
+}
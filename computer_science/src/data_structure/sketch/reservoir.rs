@@ -0,0 +1,146 @@
+use crate::random::Random;
+
+const DEFAULT_SEED: u64 = 0x5EED_5A3F_0E5E_1A50;
+
+/// Uniform random sample of `capacity` items from a stream of unknown length.
+///
+/// Uses Li's Algorithm L: instead of drawing a random number per item, it draws how many items to skip before the
+/// next replacement, so a stream of `n` items costs O(capacity * (1 + log(n / capacity))) random draws.
+pub struct Reservoir<T> {
+    capacity: usize,
+    sample: Vec<T>,
+    seen: usize,
+    // Stream index of the next item entering the sample once it's full.
+    next: usize,
+    weight: f64,
+    random: Random,
+}
+
+impl<T> Reservoir<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self::with_seed(capacity, DEFAULT_SEED)
+    }
+
+    /// Draws come from a PRNG seeded with `seed`, so samples are reproducible.
+    pub fn with_seed(capacity: usize, seed: u64) -> Self {
+        assert!(capacity > 0, "Capacity must be positive.");
+        Self {
+            capacity,
+            sample: Vec::with_capacity(capacity),
+            seen: 0,
+            next: 0,
+            weight: 1.0,
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of stream items offered so far.
+    pub fn seen(&self) -> usize {
+        self.seen
+    }
+
+    pub fn sample(&self) -> &[T] {
+        &self.sample
+    }
+
+    pub fn into_sample(self) -> Vec<T> {
+        self.sample
+    }
+
+    pub fn insert(&mut self, item: T) {
+        let index = self.seen;
+        self.seen += 1;
+
+        if self.sample.len() < self.capacity {
+            self.sample.push(item);
+            if self.sample.len() == self.capacity {
+                self.weight = self.next_factor();
+                self.skip_from(index);
+            }
+        } else if index == self.next {
+            let slot = self.random.range(0..self.capacity);
+            self.sample[slot] = item;
+            self.weight *= self.next_factor();
+            self.skip_from(index);
+        }
+    }
+
+    /// Uniform value in (0, 1], safe to take the logarithm of.
+    fn uniform(&mut self) -> f64 {
+        1.0 - self.random.next_f64()
+    }
+
+    fn next_factor(&mut self) -> f64 {
+        (self.uniform().ln() / self.capacity as f64).exp()
+    }
+
+    /// The gap to the next replacement is geometric with success probability `weight`.
+    fn skip_from(&mut self, index: usize) {
+        let skip = (self.uniform().ln() / (1.0 - self.weight).ln()).floor();
+        self.next = index.saturating_add(1).saturating_add(skip as usize);
+    }
+}
+
+impl<T> Extend<T> for Reservoir<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.insert(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_everything_below_capacity() {
+        let mut reservoir = Reservoir::new(10);
+        reservoir.extend(0..5);
+        assert_eq!(reservoir.sample(), &[0, 1, 2, 3, 4]);
+        assert_eq!(reservoir.seen(), 5);
+    }
+
+    #[test]
+    fn samples_distinct_items() {
+        let mut reservoir = Reservoir::with_seed(100, 41);
+        reservoir.extend(0..100_000);
+
+        let mut sample = reservoir.into_sample();
+        sample.sort();
+        sample.dedup();
+        assert_eq!(sample.len(), 100);
+    }
+
+    #[test]
+    fn samples_uniformly() {
+        let (len, capacity, trials) = (1000, 10, 10_000);
+        let mut picks = vec![0; len];
+
+        for seed in 0..trials {
+            let mut reservoir = Reservoir::with_seed(capacity, seed);
+            reservoir.extend(0..len);
+            for item in reservoir.sample() {
+                picks[*item] += 1;
+            }
+        }
+
+        // Each item is expected 100 times with a standard deviation near 10.
+        let expected = (trials as usize * capacity / len) as i32;
+        assert!(picks.iter().all(|count| (count - expected).abs() < 50));
+
+        // Position in the stream must not matter, compare tenths of it.
+        for tenth in picks.chunks(len / 10) {
+            let count: i32 = tenth.iter().sum();
+            assert!(
+                (count - expected * 100).abs() < 150,
+                "Tenth picked {} times.",
+                count
+            );
+        }
+    }
+}
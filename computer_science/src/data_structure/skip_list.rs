@@ -0,0 +1,382 @@
+use super::ordered_map::OrderedMap;
+use crate::random::Random;
+use std::{
+    marker::PhantomData,
+    mem,
+    ops::{Bound, RangeBounds},
+    ptr::NonNull,
+};
+
+const MAX_LEVEL: usize = 32;
+const DEFAULT_PROBABILITY: f64 = 0.5;
+const DEFAULT_SEED: u64 = 0x5EED;
+
+type Link<K, V> = Option<NonNull<Node<K, V>>>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    // One forward pointer per level the node takes part in, level 0 links all nodes.
+    next: Vec<Link<K, V>>,
+}
+
+/// Ordered map stored as a skip list: a sorted linked list with extra "express lane" levels.
+///
+/// Every node is promoted to the next level with the given probability, so level i holds about n * p^i nodes and a
+/// search skips over most of the list, O(log n) expected. Lower probability means fewer pointers but longer searches.
+pub struct SkipList<K, V> {
+    head: Vec<Link<K, V>>,
+    len: usize,
+    probability: f64,
+    random: Random,
+    marker: PhantomData<Box<Node<K, V>>>,
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    pub fn new() -> Self {
+        Self::with_probability(DEFAULT_PROBABILITY, DEFAULT_SEED)
+    }
+
+    /// Levels are drawn from a PRNG seeded with `seed`, so the structure is reproducible.
+    pub fn with_probability(probability: f64, seed: u64) -> Self {
+        assert!(
+            probability > 0.0 && probability < 1.0,
+            "Probability has to be in (0, 1)."
+        );
+
+        Self {
+            head: vec![None; MAX_LEVEL],
+            len: 0,
+            probability,
+            random: Random::new(seed),
+            marker: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let node = self.find(key)?;
+        Some(unsafe { &(*node.as_ptr()).value })
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let node = self.find(key)?;
+        Some(unsafe { &mut (*node.as_ptr()).value })
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    pub fn first_key_value(&self) -> Option<(&K, &V)> {
+        let node = unsafe { &*self.head[0]?.as_ptr() };
+        Some((&node.key, &node.value))
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let predecessors = self.predecessors(&key, true);
+
+        if let Some(node) = self.next(predecessors[0], 0) {
+            let node = unsafe { &mut *node.as_ptr() };
+            if node.key == key {
+                return Some(mem::replace(&mut node.value, value));
+            }
+        }
+
+        let level = self.random_level();
+        let new_node = NonNull::from(Box::leak(Box::new(Node {
+            key,
+            value,
+            next: vec![None; level],
+        })));
+
+        // Splice the node in after its predecessor on every level it takes part in.
+        for (level, predecessor) in predecessors.iter().enumerate().take(level) {
+            let next = self.next(*predecessor, level);
+            unsafe { (&mut (*new_node.as_ptr()).next)[level] = next };
+            self.set_next(*predecessor, level, Some(new_node));
+        }
+
+        self.len += 1;
+        None
+    }
+
+    /// Removes a key, returns its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let predecessors = self.predecessors(key, true);
+        let node = self.next(predecessors[0], 0)?;
+        if unsafe { (*node.as_ptr()).key != *key } {
+            return None;
+        }
+
+        let node = unsafe { Box::from_raw(node.as_ptr()) };
+        for (level, next) in node.next.iter().enumerate() {
+            self.set_next(predecessors[level], level, *next);
+        }
+
+        self.len -= 1;
+        Some(node.value)
+    }
+
+    pub fn clear(&mut self) {
+        self.free();
+        self.head = vec![None; MAX_LEVEL];
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        self.range(..)
+    }
+
+    /// Iterates over entries whose keys fall into the range, in ascending order.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Iter<'_, K, V> {
+        let next = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key, true),
+            Bound::Excluded(key) => self.lower_bound(key, false),
+            Bound::Unbounded => self.head[0],
+        };
+        let end = match range.end_bound() {
+            Bound::Included(key) => self.lower_bound(key, false),
+            Bound::Excluded(key) => self.lower_bound(key, true),
+            Bound::Unbounded => None,
+        };
+
+        // Start past the end (e.g. `5..3`) yields nothing instead of running to the last entry.
+        let next = match (next, end) {
+            (Some(first), Some(end)) if unsafe { (*first.as_ptr()).key > (*end.as_ptr()).key } => {
+                None
+            }
+            _ => next,
+        };
+
+        Iter {
+            next,
+            end,
+            marker: PhantomData,
+        }
+    }
+
+    fn find(&self, key: &K) -> Link<K, V> {
+        self.lower_bound(key, true)
+            .filter(|node| unsafe { (*node.as_ptr()).key == *key })
+    }
+
+    /// First node with key >= `key` (or > `key` when not inclusive).
+    fn lower_bound(&self, key: &K, inclusive: bool) -> Link<K, V> {
+        let predecessors = self.predecessors(key, inclusive);
+        self.next(predecessors[0], 0)
+    }
+
+    /// Last node on each level whose key is < `key` (or <= `key` when not inclusive), None means the head.
+    fn predecessors(&self, key: &K, inclusive: bool) -> [Link<K, V>; MAX_LEVEL] {
+        let mut predecessors = [None; MAX_LEVEL];
+        let mut current = None;
+
+        // Walk right while the next key is still before `key`, then drop a level.
+        for level in (0..MAX_LEVEL).rev() {
+            while let Some(next) = self.next(current, level) {
+                let next_key = unsafe { &(*next.as_ptr()).key };
+                let is_before = match inclusive {
+                    true => next_key < key,
+                    false => next_key <= key,
+                };
+                if !is_before {
+                    break;
+                }
+                current = Some(next);
+            }
+            predecessors[level] = current;
+        }
+
+        predecessors
+    }
+
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+        while level < MAX_LEVEL && self.random.next_bool(self.probability) {
+            level += 1;
+        }
+        level
+    }
+}
+
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> OrderedMap<K, V> for SkipList<K, V> {
+    type Iter<'a>
+        = Iter<'a, K, V>
+    where
+        K: 'a,
+        V: 'a;
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.remove(key)
+    }
+
+    fn range<R: RangeBounds<K>>(&self, range: R) -> Self::Iter<'_> {
+        self.range(range)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> SkipList<K, V> {
+    fn next(&self, node: Link<K, V>, level: usize) -> Link<K, V> {
+        match node {
+            Some(node) => unsafe { (&(*node.as_ptr()).next)[level] },
+            None => self.head[level],
+        }
+    }
+
+    fn set_next(&mut self, node: Link<K, V>, level: usize, next: Link<K, V>) {
+        match node {
+            Some(node) => unsafe { (&mut (*node.as_ptr()).next)[level] = next },
+            None => self.head[level] = next,
+        }
+    }
+
+    fn free(&mut self) {
+        let mut current = self.head[0];
+        while let Some(node) = current {
+            let node = unsafe { Box::from_raw(node.as_ptr()) };
+            current = node.next[0];
+        }
+    }
+}
+
+impl<K, V> Drop for SkipList<K, V> {
+    fn drop(&mut self) {
+        self.free();
+    }
+}
+
+pub struct Iter<'a, K, V> {
+    next: Link<K, V>,
+    end: Link<K, V>,
+    marker: PhantomData<&'a Node<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.next?;
+        if self.next == self.end {
+            return None;
+        }
+
+        let node = unsafe { &*node.as_ptr() };
+        self.next = node.next[0];
+
+        Some((&node.key, &node.value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structure::ordered_map::ordered_map_conformance_tests;
+
+    fn level_nodes<K, V>(list: &SkipList<K, V>, level: usize) -> Vec<NonNull<Node<K, V>>> {
+        let mut nodes = Vec::new();
+        let mut current = list.head[level];
+        while let Some(node) = current {
+            nodes.push(node);
+            current = unsafe { (&(*node.as_ptr()).next)[level] };
+        }
+        nodes
+    }
+
+    /// Checks that every level is sorted and is a subset of the level below.
+    fn check_invariants<K: Ord, V>(list: &SkipList<K, V>) {
+        let base = level_nodes(list, 0);
+        assert_eq!(base.len(), list.len());
+
+        for level in 0..MAX_LEVEL {
+            let nodes = level_nodes(list, level);
+            let keys: Vec<&K> = nodes
+                .iter()
+                .map(|node| unsafe { &(*node.as_ptr()).key })
+                .collect();
+            assert!(
+                keys.windows(2).all(|pair| pair[0] < pair[1]),
+                "Level not sorted."
+            );
+            assert!(
+                nodes.iter().all(|node| base.contains(node)),
+                "Node missing from base level."
+            );
+        }
+    }
+
+    #[test]
+    fn probability_controls_height() {
+        let count_pointers = |probability| {
+            let mut list = SkipList::with_probability(probability, 1);
+            for key in 0..2000 {
+                list.insert(key, ());
+            }
+            check_invariants(&list);
+            (0..MAX_LEVEL)
+                .map(|level| level_nodes(&list, level).len())
+                .sum::<usize>()
+        };
+
+        // Expected pointers per node are 1 / (1 - p): 1.33 for p = 0.25 and 4 for p = 0.75.
+        let sparse = count_pointers(0.25);
+        let dense = count_pointers(0.75);
+        assert!(sparse < 2000 * 3 / 2, "{sparse}");
+        assert!(dense > 2000 * 3, "{dense}");
+    }
+
+    #[test]
+    fn invalid_probability_panics() {
+        let result = std::panic::catch_unwind(|| SkipList::<i32, ()>::with_probability(1.0, 0));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn keeps_levels_on_random_operations() {
+        let mut random = Random::new(1990);
+        let mut list = SkipList::with_probability(0.5, 1990);
+
+        for step in 0..20_000 {
+            let key = random.range(0..500);
+            match random.range(0..3) {
+                0 | 1 => list.insert(key, step),
+                _ => list.remove(&key),
+            };
+            if step % 500 == 0 {
+                check_invariants(&list);
+            }
+        }
+
+        check_invariants(&list);
+        list.clear();
+        assert!(list.head.iter().all(Option::is_none));
+    }
+
+    ordered_map_conformance_tests!(conformance, <K, V> SkipList<K, V>);
+}
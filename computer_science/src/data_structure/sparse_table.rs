@@ -0,0 +1,108 @@
+use super::segment_tree::bounds;
+use std::ops::RangeBounds;
+
+/// Sparse table: answers range queries of an idempotent operation (`f(x, x) == x`, like min, max or gcd) in O(1)
+/// after O(n log n) preprocessing. The values can't change afterwards.
+///
+/// Level `k` holds the result for every range of length 2^k. Any range is covered by two such ranges that may
+/// overlap, which idempotence makes harmless.
+pub struct SparseTable<T, F> {
+    levels: Vec<Vec<T>>,
+    operation: F,
+}
+
+impl<T: Clone, F: Fn(&T, &T) -> T> SparseTable<T, F> {
+    pub fn new(values: Vec<T>, operation: F) -> Self {
+        let mut levels = vec![values];
+
+        let mut length = 1;
+        while 2 * length <= levels[0].len() {
+            let previous = levels.last().unwrap();
+            let level = (0..previous.len() - length)
+                .map(|start| operation(&previous[start], &previous[start + length]))
+                .collect();
+            levels.push(level);
+            length *= 2;
+        }
+
+        Self { levels, operation }
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> &T {
+        assert!(index < self.len(), "Out of bounds index.");
+        &self.levels[0][index]
+    }
+
+    /// Result of the operation over the range, `None` for an empty one.
+    pub fn query(&self, range: impl RangeBounds<usize>) -> Option<T> {
+        let (start, end) = bounds(range, self.len());
+        if start == end {
+            return None;
+        }
+
+        let level = (end - start).ilog2() as usize;
+        let values = &self.levels[level];
+        Some((self.operation)(
+            &values[start],
+            &values[end - (1 << level)],
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+
+    #[test]
+    fn can_query_ranges() {
+        let table = SparseTable::new(vec![5, 2, 8, 1, 9, 3], |a: &i32, b: &i32| *a.min(b));
+
+        assert_eq!(table.query(..), Some(1));
+        assert_eq!(table.query(..3), Some(2));
+        assert_eq!(table.query(4..), Some(3));
+        assert_eq!(table.query(2..=2), Some(8));
+        assert_eq!(table.query(3..3), None);
+        assert_eq!(*table.get(4), 9);
+    }
+
+    #[test]
+    fn handles_empty() {
+        let table = SparseTable::new(Vec::<u8>::new(), |a: &u8, b: &u8| *a.max(b));
+        assert!(table.is_empty());
+        assert_eq!(table.query(..), None);
+    }
+
+    #[test]
+    fn matches_loops() {
+        let mut random = Random::new(38);
+
+        for len in [1, 2, 3, 8, 31, 100] {
+            let values: Vec<u64> = (0..len).map(|_| random.range(0..1000) as u64).collect();
+            let max = SparseTable::new(values.clone(), |a: &u64, b: &u64| *a.max(b));
+            // Bitwise or is idempotent too.
+            let or = SparseTable::new(values.clone(), |a: &u64, b: &u64| a | b);
+
+            for start in 0..len {
+                for end in start + 1..=len {
+                    assert_eq!(
+                        max.query(start..end),
+                        values[start..end].iter().max().copied()
+                    );
+                    assert_eq!(
+                        or.query(start..end),
+                        values[start..end].iter().copied().reduce(|a, b| a | b)
+                    );
+                }
+            }
+        }
+    }
+}
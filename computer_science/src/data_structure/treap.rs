@@ -0,0 +1,662 @@
+use crate::random::Random;
+use std::{cmp::Ordering, mem, ops::Range};
+
+const DEFAULT_SEED: u64 = 0x5EED;
+
+type Link<T> = Option<Box<Node<T>>>;
+
+struct Node<T> {
+    value: T,
+    priority: u64,
+    size: usize,
+    // Pending reversal of this subtree (implicit mode only), children are swapped lazily by `push_down`.
+    reversed: bool,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T> Node<T> {
+    fn new(value: T, priority: u64) -> Box<Self> {
+        Box::new(Self {
+            value,
+            priority,
+            size: 1,
+            reversed: false,
+            left: None,
+            right: None,
+        })
+    }
+
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+
+    fn push_down(&mut self) {
+        if !self.reversed {
+            return;
+        }
+
+        mem::swap(&mut self.left, &mut self.right);
+        for child in [self.left.as_mut(), self.right.as_mut()]
+            .into_iter()
+            .flatten()
+        {
+            child.reversed = !child.reversed;
+        }
+        self.reversed = false;
+    }
+}
+
+fn size<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.size)
+}
+
+/// Joins two treaps, every element of `left` has to come before every element of `right`.
+fn merge<T>(left: Link<T>, right: Link<T>) -> Link<T> {
+    match (left, right) {
+        (None, right) => right,
+        (left, None) => left,
+        (Some(mut left), Some(mut right)) => {
+            // Higher priority stays on top (max-heap on priorities).
+            if left.priority > right.priority {
+                left.push_down();
+                left.right = merge(left.right.take(), Some(right));
+                left.update();
+                Some(left)
+            } else {
+                right.push_down();
+                right.left = merge(Some(left), right.left.take());
+                right.update();
+                Some(right)
+            }
+        }
+    }
+}
+
+/// Splits into the first `count` elements and the rest.
+fn split_at<T>(link: Link<T>, count: usize) -> (Link<T>, Link<T>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+    node.push_down();
+
+    let left_size = size(&node.left);
+    if count <= left_size {
+        let (left, right) = split_at(node.left.take(), count);
+        node.left = right;
+        node.update();
+        (left, Some(node))
+    } else {
+        let (left, right) = split_at(node.right.take(), count - left_size - 1);
+        node.right = left;
+        node.update();
+        (Some(node), right)
+    }
+}
+
+/// Splits a keyed treap into elements < `key` and elements >= `key`.
+fn split_by_key<T: Ord>(link: Link<T>, key: &T) -> (Link<T>, Link<T>) {
+    let Some(mut node) = link else {
+        return (None, None);
+    };
+
+    if node.value >= *key {
+        let (left, right) = split_by_key(node.left.take(), key);
+        node.left = right;
+        node.update();
+        (left, Some(node))
+    } else {
+        let (left, right) = split_by_key(node.right.take(), key);
+        node.right = left;
+        node.update();
+        (Some(node), right)
+    }
+}
+
+/// Ordered set stored as a treap: a binary search tree on keys which is at the same time a heap on random
+/// priorities. Random priorities make the shape that of a randomly built BST, with O(log n) expected depth.
+///
+/// Everything is expressed through `split` and `merge`, which also makes splitting and joining whole sets O(log n).
+pub struct Treap<K> {
+    root: Link<K>,
+    random: Random,
+}
+
+impl<K: Ord> Treap<K> {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Priorities come from a PRNG seeded with `seed`, so the shape of the tree is reproducible.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            root: None,
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        let mut current = self.root.as_deref();
+
+        while let Some(node) = current {
+            current = match key.cmp(&node.value) {
+                Ordering::Less => node.left.as_deref(),
+                Ordering::Greater => node.right.as_deref(),
+                Ordering::Equal => return true,
+            };
+        }
+
+        false
+    }
+
+    /// Returns false if the key was already present.
+    pub fn insert(&mut self, key: K) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+
+        let (left, right) = split_by_key(self.root.take(), &key);
+        let node = Node::new(key, self.random.next_u64());
+        self.root = merge(merge(left, Some(node)), right);
+
+        true
+    }
+
+    /// Returns false if the key was not present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        if !self.contains(key) {
+            return false;
+        }
+
+        let (left, right) = split_by_key(self.root.take(), key);
+        // `right` starts with the removed key.
+        let (_, right) = split_at(right, 1);
+        self.root = merge(left, right);
+
+        true
+    }
+
+    /// Moves all keys >= `key` into a new treap.
+    pub fn split_off(&mut self, key: &K) -> Self {
+        let (left, right) = split_by_key(self.root.take(), key);
+        self.root = left;
+
+        Self {
+            root: right,
+            random: Random::new(self.random.next_u64()),
+        }
+    }
+
+    /// Joins `other` to the end of this treap in O(log n).
+    ///
+    /// Panics unless all keys of `other` are greater than all keys of this treap.
+    pub fn merge(&mut self, other: Self) {
+        if let (Some(last), Some(first)) = (self.last(), other.first()) {
+            assert!(last < first, "Merged treaps overlap.");
+        }
+
+        self.root = merge(self.root.take(), other.root);
+    }
+
+    pub fn first(&self) -> Option<&K> {
+        self.nth(0)
+    }
+
+    pub fn last(&self) -> Option<&K> {
+        self.nth(self.len().checked_sub(1)?)
+    }
+
+    /// Key at the given position in sorted order, O(log n) thanks to subtree sizes.
+    pub fn nth(&self, index: usize) -> Option<&K> {
+        nth(&self.root, index)
+    }
+
+    /// Number of keys smaller than `key`.
+    pub fn rank(&self, key: &K) -> usize {
+        let mut current = self.root.as_deref();
+        let mut rank = 0;
+
+        while let Some(node) = current {
+            if *key <= node.value {
+                current = node.left.as_deref();
+            } else {
+                rank += size(&node.left) + 1;
+                current = node.right.as_deref();
+            }
+        }
+
+        rank
+    }
+
+    pub fn iter(&self) -> Iter<'_, K> {
+        Iter::new(&self.root)
+    }
+}
+
+impl<K: Ord> FromIterator<K> for Treap<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut treap = Self::new();
+        for key in iter {
+            treap.insert(key);
+        }
+        treap
+    }
+}
+
+/// Treap ordered by position instead of by key ("implicit key" = index computed from subtree sizes).
+///
+/// Works as a sequence with O(log n) expected insert/remove at any index, split, concatenation and range reversal,
+/// which makes it handy for text-editor like sequence editing.
+pub struct ImplicitTreap<T> {
+    root: Link<T>,
+    random: Random,
+}
+
+impl<T> ImplicitTreap<T> {
+    pub fn new() -> Self {
+        Self::with_seed(DEFAULT_SEED)
+    }
+
+    /// Priorities come from a PRNG seeded with `seed`, so the shape of the tree is reproducible.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            root: None,
+            random: Random::new(seed),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        nth(&self.root, index)
+    }
+
+    pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        let mut current = self.root.as_deref_mut();
+
+        while let Some(node) = current {
+            node.push_down();
+
+            let left_size = size(&node.left);
+            current = match index.cmp(&left_size) {
+                Ordering::Less => node.left.as_deref_mut(),
+                Ordering::Equal => return Some(&mut node.value),
+                Ordering::Greater => {
+                    index -= left_size + 1;
+                    node.right.as_deref_mut()
+                }
+            };
+        }
+
+        None
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        let node = Node::new(value, self.random.next_u64());
+        self.root = merge(self.root.take(), Some(node));
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len(), "Out of bounds index.");
+
+        let (left, right) = split_at(self.root.take(), index);
+        let node = Node::new(value, self.random.next_u64());
+        self.root = merge(merge(left, Some(node)), right);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        assert!(index < self.len(), "Out of bounds index.");
+
+        let (left, right) = split_at(self.root.take(), index);
+        let (middle, right) = split_at(right, 1);
+        self.root = merge(left, right);
+
+        middle.unwrap().value
+    }
+
+    /// Moves elements from `index` onwards into a new treap.
+    pub fn split_off(&mut self, index: usize) -> Self {
+        assert!(index <= self.len(), "Out of bounds index.");
+
+        let (left, right) = split_at(self.root.take(), index);
+        self.root = left;
+
+        Self {
+            root: right,
+            random: Random::new(self.random.next_u64()),
+        }
+    }
+
+    /// Concatenates `other` to the end of this sequence, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        self.root = merge(self.root.take(), other.root.take());
+    }
+
+    /// Reverses the elements in the range in O(log n), the reversal is pushed down to the nodes lazily.
+    pub fn reverse(&mut self, range: Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "Out of bounds range."
+        );
+
+        let (left, rest) = split_at(self.root.take(), range.start);
+        let (mut middle, right) = split_at(rest, range.end - range.start);
+        if let Some(middle) = middle.as_mut() {
+            middle.reversed = !middle.reversed;
+        }
+        self.root = merge(merge(left, middle), right);
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(&self.root)
+    }
+}
+
+impl<T> FromIterator<T> for ImplicitTreap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut treap = Self::new();
+        for value in iter {
+            treap.push_back(value);
+        }
+        treap
+    }
+}
+
+/// Element at the position in in-order, honouring reversals which were not pushed down yet.
+fn nth<T>(link: &Link<T>, mut index: usize) -> Option<&T> {
+    let mut current = link.as_deref();
+    let mut flipped = false;
+
+    while let Some(node) = current {
+        flipped ^= node.reversed;
+        let (left, right) = match flipped {
+            true => (&node.right, &node.left),
+            false => (&node.left, &node.right),
+        };
+
+        let left_size = size(left);
+        current = match index.cmp(&left_size) {
+            Ordering::Less => left.as_deref(),
+            Ordering::Equal => return Some(&node.value),
+            Ordering::Greater => {
+                index -= left_size + 1;
+                right.as_deref()
+            }
+        };
+    }
+
+    None
+}
+
+/// In-order iterator, honouring reversals which were not pushed down yet.
+pub struct Iter<'a, T> {
+    // Nodes whose left part is already consumed, together with whether their subtree is flipped.
+    stack: Vec<(&'a Node<T>, bool)>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn new(root: &'a Link<T>) -> Self {
+        let mut iter = Self { stack: Vec::new() };
+        iter.push_left(root.as_deref(), false);
+        iter
+    }
+
+    fn push_left(&mut self, mut current: Option<&'a Node<T>>, mut flipped: bool) {
+        while let Some(node) = current {
+            flipped ^= node.reversed;
+            self.stack.push((node, flipped));
+            current = match flipped {
+                true => node.right.as_deref(),
+                false => node.left.as_deref(),
+            };
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, flipped) = self.stack.pop()?;
+
+        let next = match flipped {
+            true => node.left.as_deref(),
+            false => node.right.as_deref(),
+        };
+        self.push_left(next, flipped);
+
+        Some(&node.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    /// Checks heap order on priorities, BST order on keys (if any) and subtree sizes.
+    fn check_invariants<T>(link: &Link<T>, keyed: Option<fn(&T, &T) -> bool>) -> usize {
+        let Some(node) = link else {
+            return 0;
+        };
+
+        for child in [&node.left, &node.right].into_iter().flatten() {
+            assert!(child.priority <= node.priority, "Heap order violated.");
+        }
+        if let Some(is_less) = keyed {
+            assert!(node
+                .left
+                .as_ref()
+                .is_none_or(|left| is_less(&left.value, &node.value)));
+            assert!(node
+                .right
+                .as_ref()
+                .is_none_or(|right| is_less(&node.value, &right.value)));
+        }
+
+        let size = 1 + check_invariants(&node.left, keyed) + check_invariants(&node.right, keyed);
+        assert_eq!(node.size, size, "Wrong subtree size.");
+        size
+    }
+
+    fn check_keyed<K: Ord>(treap: &Treap<K>) {
+        check_invariants(&treap.root, Some(|left: &K, right: &K| left < right));
+        assert!(treap
+            .iter()
+            .zip(treap.iter().skip(1))
+            .all(|(left, right)| left < right));
+    }
+
+    #[test]
+    fn can_insert_remove() {
+        let mut treap = Treap::new();
+        assert!(treap.insert(5));
+        assert!(treap.insert(1));
+        assert!(treap.insert(9));
+        assert!(!treap.insert(5));
+
+        assert_eq!(treap.len(), 3);
+        assert!(treap.contains(&9));
+        assert!(treap.remove(&9));
+        assert!(!treap.remove(&9));
+        assert!(!treap.contains(&9));
+        assert_eq!(treap.iter().collect::<Vec<_>>(), [&1, &5]);
+        check_keyed(&treap);
+    }
+
+    #[test]
+    fn can_split_merge() {
+        let mut treap: Treap<i32> = (0..100).collect();
+        let upper = treap.split_off(&60);
+
+        check_keyed(&treap);
+        check_keyed(&upper);
+        assert!(treap.iter().copied().eq(0..60));
+        assert!(upper.iter().copied().eq(60..100));
+
+        treap.merge(upper);
+        check_keyed(&treap);
+        assert!(treap.iter().copied().eq(0..100));
+    }
+
+    #[test]
+    fn merge_overlapping_panics() {
+        let result = std::panic::catch_unwind(|| {
+            let mut treap: Treap<i32> = (0..10).collect();
+            treap.merge((5..15).collect());
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn gets_order_statistics() {
+        let treap: Treap<i32> = [50, 10, 40, 20, 30].into_iter().collect();
+
+        assert_eq!(treap.nth(0), Some(&10));
+        assert_eq!(treap.nth(3), Some(&40));
+        assert_eq!(treap.nth(5), None);
+        assert_eq!(treap.first(), Some(&10));
+        assert_eq!(treap.last(), Some(&50));
+        assert_eq!(treap.rank(&10), 0);
+        assert_eq!(treap.rank(&35), 3);
+        assert_eq!(treap.rank(&99), 5);
+    }
+
+    #[test]
+    fn same_seed_same_shape() {
+        // Pre-order subtree sizes determine the shape of the tree.
+        fn shape<T>(link: &Link<T>, sizes: &mut Vec<usize>) {
+            if let Some(node) = link {
+                sizes.push(node.size);
+                shape(&node.left, sizes);
+                shape(&node.right, sizes);
+            }
+        }
+
+        let build = |seed| {
+            let mut treap = Treap::with_seed(seed);
+            for key in 0..50 {
+                treap.insert(key);
+            }
+            let mut sizes = Vec::new();
+            shape(&treap.root, &mut sizes);
+            sizes
+        };
+
+        assert_eq!(build(7), build(7));
+        assert_ne!(build(7), build(8));
+    }
+
+    #[test]
+    fn implicit_edits_sequence() {
+        let mut sequence: ImplicitTreap<char> = "hello".chars().collect();
+        sequence.insert(5, '!');
+        sequence.insert(0, '>');
+        assert_eq!(sequence.remove(1), 'h');
+        *sequence.get_mut(0).unwrap() = '<';
+
+        assert_eq!(sequence.iter().collect::<String>(), "<ello!");
+        assert_eq!(sequence.get(1), Some(&'e'));
+        assert_eq!(sequence.get(6), None);
+
+        sequence.reverse(1..5);
+        assert_eq!(sequence.iter().collect::<String>(), "<olle!");
+
+        let mut tail = sequence.split_off(3);
+        assert_eq!(tail.iter().collect::<String>(), "le!");
+
+        tail.append(&mut sequence);
+        assert!(sequence.is_empty());
+        assert_eq!(tail.iter().collect::<String>(), "le!<ol");
+        check_invariants(&tail.root, None);
+    }
+
+    #[test]
+    fn implicit_out_of_bounds_panics() {
+        let result = std::panic::catch_unwind(|| {
+            let mut sequence = ImplicitTreap::new();
+            sequence.insert(1, 0);
+        });
+        assert!(result.is_err());
+
+        let result = std::panic::catch_unwind(|| {
+            let mut sequence = ImplicitTreap::<i32>::new();
+            sequence.remove(0);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn matches_btree_map_on_random_operations() {
+        let mut random = Random::new(28);
+        let mut treap = Treap::with_seed(28);
+        let mut oracle = BTreeMap::new();
+
+        for step in 0..20_000 {
+            let key = random.range(0..500);
+            match random.range(0..3) {
+                0 | 1 => assert_eq!(treap.insert(key), oracle.insert(key, ()).is_none()),
+                _ => assert_eq!(treap.remove(&key), oracle.remove(&key).is_some()),
+            }
+
+            assert_eq!(treap.contains(&key), oracle.contains_key(&key));
+            if step % 500 == 0 {
+                check_keyed(&treap);
+                assert!(treap.iter().eq(oracle.keys()));
+                assert_eq!(treap.rank(&key), oracle.range(..key).count());
+            }
+        }
+
+        assert_eq!(treap.len(), oracle.len());
+    }
+
+    #[test]
+    fn implicit_matches_vec_on_random_operations() {
+        let mut random = Random::new(29);
+        let mut sequence = ImplicitTreap::with_seed(29);
+        let mut oracle = Vec::new();
+
+        for step in 0..10_000 {
+            match random.range(0..4) {
+                0 | 1 => {
+                    let index = random.range(0..oracle.len() + 1);
+                    sequence.insert(index, step);
+                    oracle.insert(index, step);
+                }
+                2 if !oracle.is_empty() => {
+                    let index = random.range(0..oracle.len());
+                    assert_eq!(sequence.remove(index), oracle.remove(index));
+                }
+                _ => {
+                    let start = random.range(0..oracle.len() + 1);
+                    let end = random.range(start..oracle.len() + 1);
+                    sequence.reverse(start..end);
+                    oracle[start..end].reverse();
+                }
+            }
+
+            if step % 250 == 0 {
+                check_invariants(&sequence.root, None);
+                assert!(sequence.iter().eq(oracle.iter()));
+                let index = random.range(0..oracle.len() + 1);
+                assert_eq!(sequence.get(index), oracle.get(index));
+            }
+        }
+
+        assert_eq!(sequence.len(), oracle.len());
+    }
+}
@@ -0,0 +1,386 @@
+use std::mem;
+
+struct Node<V> {
+    value: Option<V>,
+    // Sorted by byte, so iteration is lexicographic.
+    children: Vec<(u8, Node<V>)>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Self {
+            value: None,
+            children: Vec::new(),
+        }
+    }
+
+    fn child(&self, byte: u8) -> Option<&Node<V>> {
+        let index = self
+            .children
+            .binary_search_by_key(&byte, |(byte, _)| *byte)
+            .ok()?;
+        Some(&self.children[index].1)
+    }
+
+    fn child_mut(&mut self, byte: u8) -> Option<&mut Node<V>> {
+        let index = self
+            .children
+            .binary_search_by_key(&byte, |(byte, _)| *byte)
+            .ok()?;
+        Some(&mut self.children[index].1)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.value.is_none() && self.children.is_empty()
+    }
+}
+
+/// Trie (prefix tree) over byte strings: one node per byte, keys sharing a prefix share its nodes.
+///
+/// Lookups take O(key length) regardless of the number of keys, and everything under a prefix is one subtree, which
+/// gives prefix iteration and longest prefix match for free. See `RadixTree` for a variant using fewer nodes.
+pub struct Trie<V> {
+    root: Node<V>,
+    len: usize,
+}
+
+impl<V> Trie<V> {
+    pub fn new() -> Self {
+        Self {
+            root: Node::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&V> {
+        self.node(key.as_ref())?.value.as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: impl AsRef<[u8]>) -> Option<&mut V> {
+        let mut node = &mut self.root;
+        for byte in key.as_ref() {
+            node = node.child_mut(*byte)?;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key(&self, key: impl AsRef<[u8]>) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts a key-value pair, returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, value: V) -> Option<V> {
+        let mut node = &mut self.root;
+        for byte in key.as_ref() {
+            let index = match node.children.binary_search_by_key(byte, |(byte, _)| *byte) {
+                Ok(index) => index,
+                Err(index) => {
+                    node.children.insert(index, (*byte, Node::new()));
+                    index
+                }
+            };
+            node = &mut node.children[index].1;
+        }
+
+        let previous = node.value.replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    /// Removes a key, returns its value if it was present. Nodes left without keys below them are freed.
+    pub fn remove(&mut self, key: impl AsRef<[u8]>) -> Option<V> {
+        let value = Self::remove_from(&mut self.root, key.as_ref())?;
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        // Goes through `drop`, unlike assigning a new root.
+        *self = Self::new();
+    }
+
+    /// Longest key that is a prefix of `key`, with its value. E.g. the route matching a request path.
+    pub fn longest_prefix_match<'k>(&self, key: &'k [u8]) -> Option<(&'k [u8], &V)> {
+        let mut node = &self.root;
+        let mut longest = node.value.as_ref().map(|value| (0, value));
+
+        for (depth, byte) in key.iter().enumerate() {
+            node = match node.child(*byte) {
+                Some(child) => child,
+                None => break,
+            };
+            if let Some(value) = &node.value {
+                longest = Some((depth + 1, value));
+            }
+        }
+
+        longest.map(|(length, value)| (&key[..length], value))
+    }
+
+    /// Iterates over all entries in lexicographic order of keys.
+    pub fn iter(&self) -> Iter<'_, V> {
+        self.prefix_iter([])
+    }
+
+    /// Iterates over entries whose keys start with `prefix`, in lexicographic order.
+    pub fn prefix_iter(&self, prefix: impl AsRef<[u8]>) -> Iter<'_, V> {
+        let prefix = prefix.as_ref();
+        Iter {
+            stack: self
+                .node(prefix)
+                .map(|node| (prefix.to_vec(), node))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn node(&self, key: &[u8]) -> Option<&Node<V>> {
+        let mut node = &self.root;
+        for byte in key {
+            node = node.child(*byte)?;
+        }
+        Some(node)
+    }
+
+    /// Iterative, so long keys can't overflow the stack.
+    fn remove_from(root: &mut Node<V>, key: &[u8]) -> Option<V> {
+        // Child index taken on every level, and the deepest node that keeps other keys once this one is gone.
+        let mut path = Vec::with_capacity(key.len());
+        let mut keeper = 0;
+        let mut node = &*root;
+        for byte in key {
+            if node.value.is_some() || node.children.len() > 1 {
+                keeper = path.len();
+            }
+            let index = node
+                .children
+                .binary_search_by_key(byte, |(byte, _)| *byte)
+                .ok()?;
+            path.push(index);
+            node = &node.children[index].1;
+        }
+        node.value.as_ref()?;
+        let prune = !path.is_empty() && node.children.is_empty();
+
+        let mut node = root;
+        if !prune {
+            for index in path {
+                node = &mut node.children[index].1;
+            }
+            return node.value.take();
+        }
+
+        for &index in &path[..keeper] {
+            node = &mut node.children[index].1;
+        }
+        // Everything below the keeper is a chain of single children ending in the key's node, unlinked one at a time
+        // so dropping it doesn't recurse either.
+        let (_, mut pruned) = node.children.remove(path[keeper]);
+        while let Some((_, child)) = pruned.children.pop() {
+            pruned = child;
+        }
+        pruned.value.take()
+    }
+}
+
+impl<V> Default for Trie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: AsRef<[u8]>, V> FromIterator<(K, V)> for Trie<V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for (key, value) in iter {
+            trie.insert(key, value);
+        }
+        trie
+    }
+}
+
+/// Depth-first, the key of every node is rebuilt on the way down.
+pub struct Iter<'a, V> {
+    stack: Vec<(Vec<u8>, &'a Node<V>)>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((key, node)) = self.stack.pop() {
+            // Reversed, so the smallest byte is popped first.
+            for (byte, child) in node.children.iter().rev() {
+                let mut child_key = key.clone();
+                child_key.push(*byte);
+                self.stack.push((child_key, child));
+            }
+
+            if let Some(value) = &node.value {
+                return Some((key, value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<V> Drop for Trie<V> {
+    /// Frees nodes iteratively, recursive dropping could overflow the stack on long keys.
+    fn drop(&mut self) {
+        let mut stack = mem::take(&mut self.root.children);
+        while let Some((_, mut node)) = stack.pop() {
+            stack.append(&mut node.children);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::collections::BTreeMap;
+
+    fn keys<'a, V: 'a>(iter: impl Iterator<Item = (Vec<u8>, &'a V)>) -> Vec<String> {
+        iter.map(|(key, _)| String::from_utf8(key).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn can_insert_get() {
+        let mut trie = Trie::new();
+        assert_eq!(trie.insert("tea", 1), None);
+        assert_eq!(trie.insert("ten", 2), None);
+        assert_eq!(trie.insert("te", 3), None);
+        assert_eq!(trie.insert("tea", 4), Some(1));
+
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.get("tea"), Some(&4));
+        assert_eq!(trie.get("te"), Some(&3));
+        assert_eq!(trie.get("t"), None);
+        assert_eq!(trie.get("team"), None);
+        *trie.get_mut(b"ten").unwrap() += 10;
+        assert_eq!(trie.get("ten"), Some(&12));
+
+        // Empty key is a key too.
+        trie.insert("", 0);
+        assert_eq!(trie.get(""), Some(&0));
+        assert_eq!(trie.len(), 4);
+    }
+
+    #[test]
+    fn can_remove() {
+        let mut trie: Trie<i32> = [("a", 1), ("ab", 2), ("abc", 3), ("b", 4)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(trie.remove("ab"), Some(2));
+        assert_eq!(trie.remove("ab"), None);
+        assert_eq!(trie.remove("abcd"), None);
+        assert_eq!(trie.get("abc"), Some(&3));
+
+        assert_eq!(trie.remove("abc"), Some(3));
+        // Nothing is left below "a", so its node has no children anymore.
+        assert!(trie.node(b"a").unwrap().children.is_empty());
+        assert_eq!(trie.len(), 2);
+    }
+
+    #[test]
+    fn iterates_by_prefix() {
+        let trie: Trie<()> = ["car", "cat", "cart", "dog", "ca", "do"]
+            .into_iter()
+            .map(|key| (key, ()))
+            .collect();
+
+        assert_eq!(keys(trie.iter()), ["ca", "car", "cart", "cat", "do", "dog"]);
+        assert_eq!(keys(trie.prefix_iter("car")), ["car", "cart"]);
+        assert_eq!(keys(trie.prefix_iter("c")), ["ca", "car", "cart", "cat"]);
+        assert_eq!(keys(trie.prefix_iter("x")), Vec::<String>::new());
+        assert_eq!(keys(trie.prefix_iter("")).len(), 6);
+    }
+
+    #[test]
+    fn finds_longest_prefix_match() {
+        let routes: Trie<&str> = [("/", "root"), ("/api", "api"), ("/api/users", "users")]
+            .into_iter()
+            .collect();
+
+        let route = |path: &'static str| routes.longest_prefix_match(path.as_bytes());
+        assert_eq!(route("/api/users/7"), Some((&b"/api/users"[..], &"users")));
+        assert_eq!(route("/api/user"), Some((&b"/api"[..], &"api")));
+        assert_eq!(route("/index.html"), Some((&b"/"[..], &"root")));
+        assert_eq!(route("index.html"), None);
+    }
+
+    #[test]
+    fn handles_long_keys() {
+        let mut trie = Trie::new();
+        trie.insert(vec![7; 200_000], ());
+        trie.insert(vec![7; 100_000], ());
+        assert!(trie.contains_key(vec![7; 200_000]));
+
+        assert_eq!(trie.remove(vec![7; 200_000]), Some(()));
+        assert!(!trie.contains_key(vec![7; 200_000]));
+        assert!(trie.contains_key(vec![7; 100_000]));
+        assert_eq!(trie.remove(vec![7; 100_000]), Some(()));
+        assert!(trie.is_empty());
+        assert!(trie.root.children.is_empty());
+    }
+
+    #[test]
+    fn matches_btree_map() {
+        let mut random = Random::new(34);
+        let mut trie = Trie::new();
+        let mut oracle = BTreeMap::new();
+
+        // Short keys over a small alphabet, so they share plenty of prefixes.
+        let random_key = |random: &mut Random| -> Vec<u8> {
+            let length = random.range(0..6);
+            (0..length)
+                .map(|_| b'a' + random.range(0..3) as u8)
+                .collect()
+        };
+
+        for step in 0..20_000 {
+            let key = random_key(&mut random);
+            match random.range(0..10) {
+                0..=4 => assert_eq!(trie.insert(&key, step), oracle.insert(key, step)),
+                5..=7 => assert_eq!(trie.remove(&key), oracle.remove(&key)),
+                8 => {
+                    let expected = (0..=key.len())
+                        .rev()
+                        .find_map(|length| oracle.get_key_value(&key[..length]));
+                    let found = trie.longest_prefix_match(&key);
+                    assert_eq!(
+                        found.map(|(prefix, value)| (prefix.to_vec(), value)),
+                        expected.map(|(key, value)| (key.clone(), value))
+                    );
+                }
+                _ => {
+                    let expected = oracle
+                        .range(key.clone()..)
+                        .take_while(|(other, _)| other.starts_with(&key));
+                    assert!(trie
+                        .prefix_iter(&key)
+                        .eq(expected.map(|(key, value)| (key.clone(), value))));
+                }
+            }
+
+            assert_eq!(trie.len(), oracle.len());
+        }
+
+        assert!(trie
+            .iter()
+            .eq(oracle.iter().map(|(key, value)| (key.clone(), value))));
+    }
+}
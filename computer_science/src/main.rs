@@ -1,4 +1,5 @@
 mod algorithm;
 mod data_structure;
+mod random;
 
 fn main() {}
@@ -0,0 +1,79 @@
+use std::ops::Range;
+
+/// Small seedable pseudo-random number generator (xorshift64*).
+///
+/// Same seed always produces the same sequence, which keeps randomized structures and tests deterministic.
+/// Not suitable for anything security related.
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        // Zero is a fixed point of xorshift, it would only ever produce zeros.
+        let state = match seed {
+            0 => 0x9E37_79B9_7F4A_7C15,
+            seed => seed,
+        };
+
+        Self { state }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniformly distributed value in [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Returns true with the given probability.
+    pub fn next_bool(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    /// Value from the (non-empty) range. Modulo bias is negligible for the small ranges used here.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(range.start < range.end, "Empty range.");
+
+        range.start + (self.next_u64() % (range.end - range.start) as u64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+
+        for _ in 0..100 {
+            assert_eq!(first.next_u64(), second.next_u64());
+        }
+    }
+
+    #[test]
+    fn handles_zero_seed() {
+        let mut random = Random::new(0);
+        assert_ne!(random.next_u64(), 0);
+    }
+
+    #[test]
+    fn stays_in_range() {
+        let mut random = Random::new(7);
+
+        for _ in 0..1000 {
+            let value = random.range(3..10);
+            assert!((3..10).contains(&value));
+
+            let float = random.next_f64();
+            assert!((0.0..1.0).contains(&float));
+        }
+    }
+}